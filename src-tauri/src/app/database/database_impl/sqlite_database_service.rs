@@ -1,13 +1,30 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
 use partial::serial_port::SerialPortId;
 use sea_orm::{
-    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectOptions, DatabaseConnection, EntityTrait,
-    QueryFilter,
+    sea_query::{Alias, Condition, Expr, Func, SimpleExpr},
+    ActiveModelTrait, ActiveValue, ColumnTrait, ConnectOptions, ConnectionTrait,
+    DatabaseConnection, DatabaseTransaction, DbErr, EntityTrait, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect, Statement, TransactionTrait,
 };
 use sqlite_migration::{Migrator, MigratorTrait};
 
 use crate::app::{
     database::{database_service::DatabaseService, error::*, model::UpdateOrInsert},
-    model::managed_serial_port::AppOpenSerialPortOptions,
+    model::{
+        auto_responder::AppAutoResponder,
+        command_macro::AppCommandMacro,
+        managed_serial_port::AppOpenSerialPortOptions,
+        packet::{
+            AppPacketDirectionFilter, AppPacketOriginFilter, AppPacketPage, AppPacketQuery,
+            AppStoredPacket,
+        },
+        port_group::AppPortGroup,
+        retention::{AppDatabaseStats, AppPortPacketStats, AppRetentionPolicy, AppRetentionScope},
+        script::AppScript,
+        subscription::AppSubscription,
+    },
     serial_state::model::CorePacket,
 };
 
@@ -21,6 +38,62 @@ pub enum NewDatabaseError {
     Connect(#[from] sea_orm::error::DbErr),
 }
 
+/// Returns the condition of the packet column that is set for the `origin`.
+fn packet_origin_condition(origin: AppPacketOriginFilter) -> SimpleExpr {
+    use entity::packet::Column;
+
+    match origin {
+        AppPacketOriginFilter::Direct => Column::OutgoingDirect.eq(true),
+        AppPacketOriginFilter::Broadcast => Column::OutgoingBroadcast.eq(true),
+        AppPacketOriginFilter::Subscription => Column::OutgoingSubscription.is_not_null(),
+        AppPacketOriginFilter::File => Column::OutgoingFile.is_not_null(),
+        AppPacketOriginFilter::Transfer => Column::OutgoingTransfer.is_not_null(),
+        AppPacketOriginFilter::Periodic => Column::OutgoingPeriodic.is_not_null(),
+        AppPacketOriginFilter::Macro => Column::OutgoingMacro.is_not_null(),
+        AppPacketOriginFilter::Group => Column::OutgoingGroup.is_not_null(),
+        AppPacketOriginFilter::AutoResponder => Column::OutgoingAutoResponder.is_not_null(),
+    }
+}
+
+/// Out of range timestamps are clamped.
+fn date_time_from_micros(timestamp_micros: u64) -> DateTime<Utc> {
+    DateTime::<Utc>::from_timestamp_micros(timestamp_micros.min(i64::MAX as u64) as i64)
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Rows per insert statement, keeping the bound parameters below the limit of Sqlite.
+const INSERT_PACKETS_CHUNK_SIZE: usize = 500;
+
+/// Packets read at once while summing up the length of their data.
+const PRUNE_SCAN_CHUNK_SIZE: u64 = 1000;
+
+/// Returns the length of the data of a packet in bytes.
+fn packet_data_length() -> SimpleExpr {
+    Func::cust(Alias::new("LENGTH"))
+        .arg(Expr::col((
+            entity::packet::Entity,
+            entity::packet::Column::Data,
+        )))
+        .into()
+}
+
+/// Returns the condition of the retention policy with exactly the `scope`.
+fn retention_scope_condition(scope: &AppRetentionScope) -> Condition {
+    use entity::retention_policy::Column;
+
+    let port = match &scope.port {
+        Some(port) => Column::Port.eq(port),
+        None => Column::Port.is_null(),
+    };
+
+    let tag = match &scope.tag {
+        Some(tag) => Column::Tag.eq(tag),
+        None => Column::Tag.is_null(),
+    };
+
+    Condition::all().add(port).add(tag)
+}
+
 #[derive(Debug, Clone)]
 pub struct SqliteDatabase {
     conn: DatabaseConnection,
@@ -38,6 +111,61 @@ impl SqliteDatabase {
     pub async fn migrate(&self) -> Result<(), sea_orm::error::DbErr> {
         Migrator::up(&self.conn, None).await
     }
+
+    /// Returns the number of deleted packets.
+    async fn delete_packets(&self, condition: Condition) -> Result<u64, DbErr> {
+        let delete_result = entity::packet::Entity::delete_many()
+            .filter(condition)
+            .exec(&self.conn)
+            .await?;
+
+        Ok(delete_result.rows_affected)
+    }
+
+    /// Sums up the length of the data of the packets matching the `condition`, newest first.
+    ///
+    /// Returns the id of the first packet exceeding `max_bytes`.
+    async fn newest_packet_id_exceeding_bytes(
+        &self,
+        condition: Condition,
+        max_bytes: u64,
+    ) -> Result<Option<i32>, DbErr> {
+        let mut bytes = 0_u64;
+        let mut cursor = None;
+
+        loop {
+            let mut chunk_condition = condition.clone();
+
+            if let Some(cursor) = cursor {
+                chunk_condition = chunk_condition.add(entity::packet::Column::Id.lt(cursor));
+            }
+
+            let chunk = entity::packet::Entity::find()
+                .select_only()
+                .column(entity::packet::Column::Id)
+                .column_as(packet_data_length(), "length")
+                .filter(chunk_condition)
+                .order_by_desc(entity::packet::Column::Id)
+                .limit(PRUNE_SCAN_CHUNK_SIZE)
+                .into_tuple::<(i32, i64)>()
+                .all(&self.conn)
+                .await?;
+
+            if chunk.is_empty() {
+                return Ok(None);
+            }
+
+            for (id, length) in chunk {
+                bytes += length as u64;
+
+                if bytes > max_bytes {
+                    return Ok(Some(id));
+                }
+
+                cursor = Some(id);
+            }
+        }
+    }
 }
 
 impl DatabaseService for SqliteDatabase {
@@ -81,7 +209,9 @@ impl DatabaseService for SqliteDatabase {
     ) -> Result<UpdateOrInsert<i32>, UpdateOrInsertOpenSerialPortOptionsError> {
         tracing::trace!(port_id, "Updating or Inserting open serial port options");
 
-        let options_opt = entity::open_options::Entity::find_by_id(port_id)
+        let options_opt = entity::open_options::Entity::find()
+            .filter(entity::open_options::Column::SerialPortId.eq(port_id))
+            .order_by_desc(entity::open_options::Column::Id)
             .one(&self.conn)
             .await
             .map_err(|err| UpdateOrInsertOpenSerialPortOptionsError::Update(err.into()))?;
@@ -117,22 +247,1068 @@ impl DatabaseService for SqliteDatabase {
         }
     }
 
-    async fn insert_packet_returning_id(
+    async fn get_open_serial_port_options(
         &self,
-        port_id: i32,
-        tag: String,
-        packet: CorePacket,
-    ) -> Result<i32, InsertPacketError> {
-        tracing::trace!(port_id, %tag, "Inserting packet");
+        name: &str,
+    ) -> Result<Option<AppOpenSerialPortOptions>, GetOpenSerialPortOptionsError> {
+        tracing::trace!(%name, "Getting open serial port options");
+
+        // Only the latest options are updated. Older ones may exist from before that.
+        let options = entity::open_options::Entity::find()
+            .inner_join(entity::serial_port::Entity)
+            .filter(entity::serial_port::Column::Name.eq(name))
+            .order_by_desc(entity::open_options::Column::Id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| GetOpenSerialPortOptionsError::Get(err.into()))?;
+
+        Ok(options.map(Into::into))
+    }
+
+    async fn get_all_open_serial_port_options(
+        &self,
+    ) -> Result<HashMap<String, AppOpenSerialPortOptions>, GetOpenSerialPortOptionsError> {
+        tracing::trace!("Getting all open serial port options");
+
+        let options = entity::open_options::Entity::find()
+            .find_also_related(entity::serial_port::Entity)
+            .order_by_asc(entity::open_options::Column::Id)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetOpenSerialPortOptionsError::Get(err.into()))?;
+
+        // Ordered by id, so the latest options of a serial port are kept.
+        let options = options
+            .into_iter()
+            .filter_map(|(options, serial_port)| Some((serial_port?.name, options.into())))
+            .collect();
+
+        Ok(options)
+    }
+
+    async fn insert_packets(
+        &self,
+        packets: Vec<(i32, String, CorePacket)>,
+    ) -> Result<(), InsertPacketsError> {
+        tracing::trace!(count = packets.len(), "Inserting packets");
+
+        if packets.is_empty() {
+            return Ok(());
+        }
+
+        let txn = self
+            .conn
+            .begin()
+            .await
+            .map_err(|err| InsertPacketsError::Insert(err.into()))?;
+
+        let mut packets = packets
+            .into_iter()
+            .map(entity::packet::ActiveModel::from)
+            .peekable();
+
+        while packets.peek().is_some() {
+            let chunk = packets
+                .by_ref()
+                .take(INSERT_PACKETS_CHUNK_SIZE)
+                .collect::<Vec<_>>();
+
+            entity::packet::Entity::insert_many(chunk)
+                .exec(&txn)
+                .await
+                .map_err(|err| InsertPacketsError::Insert(err.into()))?;
+        }
+
+        txn.commit()
+            .await
+            .map_err(|err| InsertPacketsError::Insert(err.into()))?;
+
+        Ok(())
+    }
+
+    async fn get_packets(&self, query: AppPacketQuery) -> Result<AppPacketPage, GetPacketsError> {
+        tracing::trace!(?query, "Getting packets");
+
+        let limit = query.limit.clamp(1, AppPacketQuery::MAX_LIMIT);
+
+        let mut condition = Condition::all();
+
+        if let Some(port) = query.port {
+            condition = condition.add(entity::serial_port::Column::Name.eq(port));
+        }
+
+        if let Some(tag) = query.tag {
+            condition = condition.add(entity::packet::Column::Tag.eq(tag));
+        }
+
+        match query.direction {
+            None => {}
+            Some(AppPacketDirectionFilter::Incoming) => {
+                condition = condition.add(entity::packet::Column::Incoming.eq(true));
+            }
+            Some(AppPacketDirectionFilter::Outgoing) => {
+                condition = condition.add(entity::packet::Column::Outgioing.eq(true));
+            }
+        }
+
+        if let Some(origin) = query.origin {
+            condition = condition.add(packet_origin_condition(origin));
+        }
+
+        if let Some(from_timestamp_micros) = query.from_timestamp_micros {
+            condition = condition.add(
+                entity::packet::Column::Timestamp.gte(date_time_from_micros(from_timestamp_micros)),
+            );
+        }
+
+        if let Some(to_timestamp_micros) = query.to_timestamp_micros {
+            condition = condition.add(
+                entity::packet::Column::Timestamp.lt(date_time_from_micros(to_timestamp_micros)),
+            );
+        }
+
+        if let Some(cursor) = query.cursor {
+            condition = condition.add(entity::packet::Column::Id.lt(cursor));
+        }
+
+        // One more to know if there is a next page.
+        let mut packets = entity::packet::Entity::find()
+            .find_also_related(entity::serial_port::Entity)
+            .filter(condition)
+            .order_by_desc(entity::packet::Column::Id)
+            .limit(limit + 1)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetPacketsError::Get(err.into()))?;
+
+        let has_next_page = packets.len() as u64 > limit;
+        packets.truncate(limit as usize);
+
+        let packets = packets
+            .into_iter()
+            .map(|(packet, serial_port)| AppStoredPacket {
+                id: packet.id,
+                tag: packet.tag.clone(),
+                packet: CorePacket::from((
+                    serial_port
+                        .map(|serial_port| serial_port.name)
+                        .unwrap_or_default(),
+                    packet,
+                )),
+            })
+            .collect::<Vec<_>>();
+
+        let next_cursor = has_next_page
+            .then(|| packets.last().map(|packet| packet.id))
+            .flatten();
+
+        Ok(AppPacketPage {
+            packets,
+            next_cursor,
+        })
+    }
+
+    async fn get_command_macros(
+        &self,
+    ) -> Result<Vec<(i32, AppCommandMacro)>, GetCommandMacrosError> {
+        tracing::trace!("Getting command macros");
+
+        let command_macros = entity::command_macro::Entity::find()
+            .order_by_asc(entity::command_macro::Column::Name)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetCommandMacrosError::Get(err.into()))?;
+
+        Ok(command_macros
+            .into_iter()
+            .map(|command_macro| (command_macro.id, command_macro.into()))
+            .collect())
+    }
+
+    async fn get_command_macro(
+        &self,
+        id: i32,
+    ) -> Result<Option<AppCommandMacro>, GetCommandMacroError> {
+        tracing::trace!(id, "Getting command macro");
+
+        let command_macro = entity::command_macro::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| GetCommandMacroError::Get(err.into()))?;
+
+        Ok(command_macro.map(Into::into))
+    }
+
+    async fn insert_command_macro_returning_id(
+        &self,
+        command_macro: AppCommandMacro,
+    ) -> Result<i32, InsertCommandMacroError> {
+        tracing::trace!(name=%command_macro.name, "Inserting command macro");
+
+        let command_macro = entity::command_macro::ActiveModel::from(command_macro);
+
+        let id = command_macro
+            .insert(&self.conn)
+            .await
+            .map_err(|err| InsertCommandMacroError::Insert(err.into()))?
+            .id;
+
+        Ok(id)
+    }
+
+    async fn update_command_macro(
+        &self,
+        id: i32,
+        command_macro: AppCommandMacro,
+    ) -> Result<bool, UpdateCommandMacroError> {
+        tracing::trace!(id, "Updating command macro");
+
+        let existing_command_macro = entity::command_macro::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| UpdateCommandMacroError::Update(err.into()))?;
+
+        if existing_command_macro.is_none() {
+            return Ok(false);
+        }
+
+        let mut command_macro = entity::command_macro::ActiveModel::from(command_macro);
+        command_macro.id = ActiveValue::set(id);
+
+        command_macro
+            .update(&self.conn)
+            .await
+            .map_err(|err| UpdateCommandMacroError::Update(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn delete_command_macro(&self, id: i32) -> Result<bool, DeleteCommandMacroError> {
+        tracing::trace!(id, "Deleting command macro");
+
+        let command_macro = entity::command_macro::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| DeleteCommandMacroError::Delete(err.into()))?;
+
+        let Some(command_macro) = command_macro else {
+            return Ok(false);
+        };
+
+        command_macro
+            .delete(&self.conn)
+            .await
+            .map_err(|err| DeleteCommandMacroError::Delete(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn get_scripts(&self) -> Result<Vec<(i32, AppScript)>, GetScriptsError> {
+        tracing::trace!("Getting scripts");
+
+        let scripts = entity::script::Entity::find()
+            .order_by_asc(entity::script::Column::Name)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetScriptsError::Get(err.into()))?;
+
+        Ok(scripts
+            .into_iter()
+            .map(|script| (script.id, script.into()))
+            .collect())
+    }
+
+    async fn get_script(&self, id: i32) -> Result<Option<AppScript>, GetScriptError> {
+        tracing::trace!(id, "Getting script");
+
+        let script = entity::script::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| GetScriptError::Get(err.into()))?;
+
+        Ok(script.map(Into::into))
+    }
+
+    async fn insert_script_returning_id(
+        &self,
+        script: AppScript,
+    ) -> Result<i32, InsertScriptError> {
+        tracing::trace!(name=%script.name, "Inserting script");
+
+        let script = entity::script::ActiveModel::from(script);
+
+        let id = script
+            .insert(&self.conn)
+            .await
+            .map_err(|err| InsertScriptError::Insert(err.into()))?
+            .id;
+
+        Ok(id)
+    }
+
+    async fn update_script(&self, id: i32, script: AppScript) -> Result<bool, UpdateScriptError> {
+        tracing::trace!(id, "Updating script");
+
+        let existing_script = entity::script::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| UpdateScriptError::Update(err.into()))?;
+
+        if existing_script.is_none() {
+            return Ok(false);
+        }
+
+        let mut script = entity::script::ActiveModel::from(script);
+        script.id = ActiveValue::set(id);
+
+        script
+            .update(&self.conn)
+            .await
+            .map_err(|err| UpdateScriptError::Update(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn delete_script(&self, id: i32) -> Result<bool, DeleteScriptError> {
+        tracing::trace!(id, "Deleting script");
+
+        let script = entity::script::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| DeleteScriptError::Delete(err.into()))?;
+
+        let Some(script) = script else {
+            return Ok(false);
+        };
+
+        script
+            .delete(&self.conn)
+            .await
+            .map_err(|err| DeleteScriptError::Delete(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn get_port_groups(&self) -> Result<Vec<(i32, AppPortGroup)>, GetPortGroupsError> {
+        tracing::trace!("Getting port groups");
+
+        let port_groups = entity::port_group::Entity::find()
+            .find_with_related(entity::port_group_member::Entity)
+            .order_by_asc(entity::port_group::Column::Name)
+            .order_by_asc(entity::port_group_member::Column::Id)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetPortGroupsError::Get(err.into()))?;
+
+        Ok(port_groups
+            .into_iter()
+            .map(|(port_group, members)| (port_group.id, (port_group, members).into()))
+            .collect())
+    }
+
+    async fn get_port_group(&self, id: i32) -> Result<Option<AppPortGroup>, GetPortGroupError> {
+        tracing::trace!(id, "Getting port group");
+
+        let port_group = entity::port_group::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| GetPortGroupError::Get(err.into()))?;
+
+        let Some(port_group) = port_group else {
+            return Ok(None);
+        };
+
+        let members = port_group
+            .find_related(entity::port_group_member::Entity)
+            .order_by_asc(entity::port_group_member::Column::Id)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetPortGroupError::Get(err.into()))?;
+
+        Ok(Some((port_group, members).into()))
+    }
+
+    async fn insert_port_group_returning_id(
+        &self,
+        port_group: AppPortGroup,
+    ) -> Result<i32, InsertPortGroupError> {
+        tracing::trace!(name=%port_group.name, "Inserting port group");
+
+        let txn = self
+            .conn
+            .begin()
+            .await
+            .map_err(|err| InsertPortGroupError::Insert(err.into()))?;
+
+        let id = entity::port_group::ActiveModel::from(&port_group)
+            .insert(&txn)
+            .await
+            .map_err(|err| InsertPortGroupError::Insert(err.into()))?
+            .id;
+
+        insert_port_group_members(&txn, id, port_group.ports)
+            .await
+            .map_err(|err| InsertPortGroupError::Insert(err.into()))?;
+
+        txn.commit()
+            .await
+            .map_err(|err| InsertPortGroupError::Insert(err.into()))?;
+
+        Ok(id)
+    }
+
+    async fn update_port_group(
+        &self,
+        id: i32,
+        port_group: AppPortGroup,
+    ) -> Result<bool, UpdatePortGroupError> {
+        tracing::trace!(id, "Updating port group");
+
+        let txn = self
+            .conn
+            .begin()
+            .await
+            .map_err(|err| UpdatePortGroupError::Update(err.into()))?;
+
+        let existing_port_group = entity::port_group::Entity::find_by_id(id)
+            .one(&txn)
+            .await
+            .map_err(|err| UpdatePortGroupError::Update(err.into()))?;
+
+        if existing_port_group.is_none() {
+            return Ok(false);
+        }
+
+        let mut port_group_model = entity::port_group::ActiveModel::from(&port_group);
+        port_group_model.id = ActiveValue::set(id);
+
+        port_group_model
+            .update(&txn)
+            .await
+            .map_err(|err| UpdatePortGroupError::Update(err.into()))?;
+
+        entity::port_group_member::Entity::delete_many()
+            .filter(entity::port_group_member::Column::PortGroupId.eq(id))
+            .exec(&txn)
+            .await
+            .map_err(|err| UpdatePortGroupError::Update(err.into()))?;
 
-        let packet = entity::packet::ActiveModel::from((port_id, tag, packet));
+        insert_port_group_members(&txn, id, port_group.ports)
+            .await
+            .map_err(|err| UpdatePortGroupError::Update(err.into()))?;
+
+        txn.commit()
+            .await
+            .map_err(|err| UpdatePortGroupError::Update(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn delete_port_group(&self, id: i32) -> Result<bool, DeletePortGroupError> {
+        tracing::trace!(id, "Deleting port group");
+
+        let port_group = entity::port_group::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| DeletePortGroupError::Delete(err.into()))?;
+
+        let Some(port_group) = port_group else {
+            return Ok(false);
+        };
+
+        // Members are deleted by the foreign key.
+        port_group
+            .delete(&self.conn)
+            .await
+            .map_err(|err| DeletePortGroupError::Delete(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn get_auto_responders(
+        &self,
+    ) -> Result<Vec<(i32, AppAutoResponder)>, GetAutoRespondersError> {
+        tracing::trace!("Getting auto-responders");
+
+        let auto_responders = entity::auto_responder::Entity::find()
+            .order_by_asc(entity::auto_responder::Column::Name)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetAutoRespondersError::Get(err.into()))?;
+
+        Ok(auto_responders
+            .into_iter()
+            .map(|auto_responder| (auto_responder.id, auto_responder.into()))
+            .collect())
+    }
 
-        let id = packet
+    async fn insert_auto_responder_returning_id(
+        &self,
+        auto_responder: AppAutoResponder,
+    ) -> Result<i32, InsertAutoResponderError> {
+        tracing::trace!(name=%auto_responder.name, "Inserting auto-responder");
+
+        let auto_responder = entity::auto_responder::ActiveModel::from(auto_responder);
+
+        let id = auto_responder
             .insert(&self.conn)
             .await
-            .map_err(|err| InsertPacketError::Insert(err.into()))?
+            .map_err(|err| InsertAutoResponderError::Insert(err.into()))?
             .id;
 
         Ok(id)
     }
+
+    async fn update_auto_responder(
+        &self,
+        id: i32,
+        auto_responder: AppAutoResponder,
+    ) -> Result<bool, UpdateAutoResponderError> {
+        tracing::trace!(id, "Updating auto-responder");
+
+        let existing_auto_responder = entity::auto_responder::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| UpdateAutoResponderError::Update(err.into()))?;
+
+        if existing_auto_responder.is_none() {
+            return Ok(false);
+        }
+
+        let mut auto_responder = entity::auto_responder::ActiveModel::from(auto_responder);
+        auto_responder.id = ActiveValue::set(id);
+
+        auto_responder
+            .update(&self.conn)
+            .await
+            .map_err(|err| UpdateAutoResponderError::Update(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn update_auto_responder_enabled(
+        &self,
+        id: i32,
+        enabled: bool,
+    ) -> Result<bool, UpdateAutoResponderError> {
+        tracing::trace!(id, enabled, "Updating auto-responder enabled");
+
+        let auto_responder = entity::auto_responder::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| UpdateAutoResponderError::Update(err.into()))?;
+
+        let Some(auto_responder) = auto_responder else {
+            return Ok(false);
+        };
+
+        let mut auto_responder = entity::auto_responder::ActiveModel::from(auto_responder);
+        auto_responder.enabled = ActiveValue::set(enabled);
+
+        auto_responder
+            .update(&self.conn)
+            .await
+            .map_err(|err| UpdateAutoResponderError::Update(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn delete_auto_responder(&self, id: i32) -> Result<bool, DeleteAutoResponderError> {
+        tracing::trace!(id, "Deleting auto-responder");
+
+        let auto_responder = entity::auto_responder::Entity::find_by_id(id)
+            .one(&self.conn)
+            .await
+            .map_err(|err| DeleteAutoResponderError::Delete(err.into()))?;
+
+        let Some(auto_responder) = auto_responder else {
+            return Ok(false);
+        };
+
+        auto_responder
+            .delete(&self.conn)
+            .await
+            .map_err(|err| DeleteAutoResponderError::Delete(err.into()))?;
+
+        Ok(true)
+    }
+
+    async fn get_subscriptions(&self) -> Result<Vec<AppSubscription>, GetSubscriptionsError> {
+        tracing::trace!("Getting subscriptions");
+
+        let subscriptions = entity::subscription::Entity::find()
+            .order_by_asc(entity::subscription::Column::FromPort)
+            .order_by_asc(entity::subscription::Column::ToPort)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetSubscriptionsError::Get(err.into()))?;
+
+        Ok(subscriptions.into_iter().map(Into::into).collect())
+    }
+
+    async fn get_subscription(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<AppSubscription>, GetSubscriptionError> {
+        tracing::trace!(%from, %to, "Getting subscription");
+
+        let subscription = entity::subscription::Entity::find()
+            .filter(entity::subscription::Column::FromPort.eq(from))
+            .filter(entity::subscription::Column::ToPort.eq(to))
+            .one(&self.conn)
+            .await
+            .map_err(|err| GetSubscriptionError::Get(err.into()))?;
+
+        Ok(subscription.map(Into::into))
+    }
+
+    async fn upsert_subscription(
+        &self,
+        subscription: AppSubscription,
+    ) -> Result<(), UpsertSubscriptionError> {
+        tracing::trace!(from=%subscription.from, to=%subscription.to, "Upserting subscription");
+
+        let existing_subscription = entity::subscription::Entity::find()
+            .filter(entity::subscription::Column::FromPort.eq(&subscription.from))
+            .filter(entity::subscription::Column::ToPort.eq(&subscription.to))
+            .one(&self.conn)
+            .await
+            .map_err(|err| UpsertSubscriptionError::Upsert(err.into()))?;
+
+        let mut subscription = entity::subscription::ActiveModel::from(subscription);
+
+        match existing_subscription {
+            Some(existing_subscription) => {
+                subscription.id = ActiveValue::set(existing_subscription.id);
+
+                subscription
+                    .update(&self.conn)
+                    .await
+                    .map_err(|err| UpsertSubscriptionError::Upsert(err.into()))?;
+            }
+            None => {
+                subscription
+                    .insert(&self.conn)
+                    .await
+                    .map_err(|err| UpsertSubscriptionError::Upsert(err.into()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_subscription(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<bool, DeleteSubscriptionError> {
+        tracing::trace!(%from, %to, "Deleting subscription");
+
+        let delete_result = entity::subscription::Entity::delete_many()
+            .filter(entity::subscription::Column::FromPort.eq(from))
+            .filter(entity::subscription::Column::ToPort.eq(to))
+            .exec(&self.conn)
+            .await
+            .map_err(|err| DeleteSubscriptionError::Delete(err.into()))?;
+
+        Ok(delete_result.rows_affected > 0)
+    }
+
+    async fn get_retention_policies(
+        &self,
+    ) -> Result<Vec<AppRetentionPolicy>, GetRetentionPoliciesError> {
+        tracing::trace!("Getting retention policies");
+
+        // Nulls are ordered first.
+        let policies = entity::retention_policy::Entity::find()
+            .order_by_asc(entity::retention_policy::Column::Port)
+            .order_by_asc(entity::retention_policy::Column::Tag)
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetRetentionPoliciesError::Get(err.into()))?;
+
+        Ok(policies.into_iter().map(Into::into).collect())
+    }
+
+    async fn upsert_retention_policy(
+        &self,
+        policy: AppRetentionPolicy,
+    ) -> Result<(), UpsertRetentionPolicyError> {
+        tracing::trace!(?policy, "Upserting retention policy");
+
+        let existing_policy = entity::retention_policy::Entity::find()
+            .filter(retention_scope_condition(&policy.scope))
+            .one(&self.conn)
+            .await
+            .map_err(|err| UpsertRetentionPolicyError::Upsert(err.into()))?;
+
+        let mut policy = entity::retention_policy::ActiveModel::from(policy);
+
+        match existing_policy {
+            Some(existing_policy) => {
+                policy.id = ActiveValue::set(existing_policy.id);
+
+                policy
+                    .update(&self.conn)
+                    .await
+                    .map_err(|err| UpsertRetentionPolicyError::Upsert(err.into()))?;
+            }
+            None => {
+                policy
+                    .insert(&self.conn)
+                    .await
+                    .map_err(|err| UpsertRetentionPolicyError::Upsert(err.into()))?;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn delete_retention_policy(
+        &self,
+        scope: &AppRetentionScope,
+    ) -> Result<bool, DeleteRetentionPolicyError> {
+        tracing::trace!(?scope, "Deleting retention policy");
+
+        let delete_result = entity::retention_policy::Entity::delete_many()
+            .filter(retention_scope_condition(scope))
+            .exec(&self.conn)
+            .await
+            .map_err(|err| DeleteRetentionPolicyError::Delete(err.into()))?;
+
+        Ok(delete_result.rows_affected > 0)
+    }
+
+    async fn prune_packets(&self, policy: &AppRetentionPolicy) -> Result<u64, PrunePacketsError> {
+        tracing::trace!(?policy, "Pruning packets");
+
+        let mut condition = Condition::all();
+
+        if let Some(port) = &policy.scope.port {
+            let Some(serial_port_id) = self
+                .get_serial_port_id(port)
+                .await
+                .map_err(|err| PrunePacketsError::Prune(err.into()))?
+            else {
+                // The serial port was never opened.
+                return Ok(0);
+            };
+
+            condition = condition.add(entity::packet::Column::SerialPortId.eq(serial_port_id));
+        }
+
+        if let Some(tag) = &policy.scope.tag {
+            condition = condition.add(entity::packet::Column::Tag.eq(tag));
+        }
+
+        let mut deleted_packets = 0;
+
+        // Nothing is too old if the limit exceeds the range of the timestamps.
+        let oldest = policy
+            .max_age
+            .and_then(|max_age| chrono::Duration::from_std(max_age).ok())
+            .and_then(|max_age| Utc::now().checked_sub_signed(max_age));
+
+        if let Some(oldest) = oldest {
+            deleted_packets += self
+                .delete_packets(
+                    condition
+                        .clone()
+                        .add(entity::packet::Column::Timestamp.lt(oldest)),
+                )
+                .await
+                .map_err(|err| PrunePacketsError::Prune(err.into()))?;
+        }
+
+        if let Some(max_rows) = policy.max_rows {
+            let newest_exceeding_id = entity::packet::Entity::find()
+                .select_only()
+                .column(entity::packet::Column::Id)
+                .filter(condition.clone())
+                .order_by_desc(entity::packet::Column::Id)
+                .offset(max_rows)
+                .into_tuple::<i32>()
+                .one(&self.conn)
+                .await
+                .map_err(|err| PrunePacketsError::Prune(err.into()))?;
+
+            if let Some(newest_exceeding_id) = newest_exceeding_id {
+                deleted_packets += self
+                    .delete_packets(
+                        condition
+                            .clone()
+                            .add(entity::packet::Column::Id.lte(newest_exceeding_id)),
+                    )
+                    .await
+                    .map_err(|err| PrunePacketsError::Prune(err.into()))?;
+            }
+        }
+
+        if let Some(max_bytes) = policy.max_bytes {
+            let newest_exceeding_id = self
+                .newest_packet_id_exceeding_bytes(condition.clone(), max_bytes)
+                .await
+                .map_err(|err| PrunePacketsError::Prune(err.into()))?;
+
+            if let Some(newest_exceeding_id) = newest_exceeding_id {
+                deleted_packets += self
+                    .delete_packets(
+                        condition.add(entity::packet::Column::Id.lte(newest_exceeding_id)),
+                    )
+                    .await
+                    .map_err(|err| PrunePacketsError::Prune(err.into()))?;
+            }
+        }
+
+        Ok(deleted_packets)
+    }
+
+    async fn vacuum(&self) -> Result<(), VacuumError> {
+        tracing::trace!("Vacuuming database");
+
+        self.conn
+            .execute_unprepared("VACUUM")
+            .await
+            .map_err(|err| VacuumError::Vacuum(err.into()))?;
+
+        Ok(())
+    }
+
+    async fn get_database_stats(&self) -> Result<AppDatabaseStats, GetDatabaseStatsError> {
+        tracing::trace!("Getting database stats");
+
+        let size = self
+            .conn
+            .query_one(Statement::from_string(
+                self.conn.get_database_backend(),
+                "SELECT page_count * page_size AS size_bytes, freelist_count * page_size AS free_bytes \
+                FROM pragma_page_count(), pragma_freelist_count(), pragma_page_size()",
+            ))
+            .await
+            .map_err(|err| GetDatabaseStatsError::Get(err.into()))?;
+
+        let (size_bytes, free_bytes) = match size {
+            Some(size) => (
+                size.try_get::<i64>("", "size_bytes")
+                    .map_err(|err| GetDatabaseStatsError::Get(err.into()))?,
+                size.try_get::<i64>("", "free_bytes")
+                    .map_err(|err| GetDatabaseStatsError::Get(err.into()))?,
+            ),
+            None => (0, 0),
+        };
+
+        let ports = entity::serial_port::Entity::find()
+            .select_only()
+            .column(entity::serial_port::Column::Name)
+            .column_as(
+                Expr::col((entity::packet::Entity, entity::packet::Column::Id)).count(),
+                "packets",
+            )
+            .column_as(SimpleExpr::from(Func::sum(packet_data_length())), "bytes")
+            .left_join(entity::packet::Entity)
+            .group_by(entity::serial_port::Column::Id)
+            .order_by_asc(entity::serial_port::Column::Name)
+            .into_tuple::<(String, i64, Option<i64>)>()
+            .all(&self.conn)
+            .await
+            .map_err(|err| GetDatabaseStatsError::Get(err.into()))?;
+
+        let ports = ports
+            .into_iter()
+            .map(|(port, packets, bytes)| AppPortPacketStats {
+                port,
+                packets: packets as u64,
+                bytes: bytes.unwrap_or_default() as u64,
+            })
+            .collect();
+
+        Ok(AppDatabaseStats {
+            size_bytes: size_bytes as u64,
+            free_bytes: free_bytes as u64,
+            ports,
+        })
+    }
+}
+
+async fn insert_port_group_members(
+    txn: &DatabaseTransaction,
+    port_group_id: i32,
+    ports: Vec<String>,
+) -> Result<(), sea_orm::error::DbErr> {
+    if ports.is_empty() {
+        return Ok(());
+    }
+
+    let members = ports
+        .into_iter()
+        .map(|port_name| entity::port_group_member::ActiveModel::from((port_group_id, port_name)));
+
+    entity::port_group_member::Entity::insert_many(members)
+        .exec(txn)
+        .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_util::bytes::Bytes;
+
+    use crate::app::serial_state::{
+        clock,
+        model::{CoreIncomingPacket, CorePacketDirection},
+    };
+
+    use super::*;
+
+    const HOUR: Duration = Duration::from_secs(3600);
+
+    async fn database() -> SqliteDatabase {
+        let db = SqliteDatabase::new("sqlite::memory:").await.unwrap();
+        db.migrate().await.unwrap();
+
+        db
+    }
+
+    /// Inserts a packet received `age` ago.
+    async fn insert(
+        db: &SqliteDatabase,
+        port: &str,
+        tag: &str,
+        data: &'static [u8],
+        age: Duration,
+    ) {
+        let port_id = db
+            .get_serial_port_id_or_insert_returning_id(port)
+            .await
+            .unwrap();
+        let timestamp_micros = clock::now_micros() - age.as_micros() as u64;
+
+        let packet = CorePacket::new(
+            CorePacketDirection::Incoming(CoreIncomingPacket {
+                line: Bytes::from_static(data),
+            }),
+            port.to_owned(),
+            timestamp_micros,
+            timestamp_micros,
+        );
+
+        db.insert_packets(vec![(port_id, tag.to_owned(), packet)])
+            .await
+            .unwrap();
+    }
+
+    /// Returns the data of the stored packets, oldest first.
+    async fn remaining(db: &SqliteDatabase) -> Vec<Vec<u8>> {
+        entity::packet::Entity::find()
+            .order_by_asc(entity::packet::Column::Id)
+            .all(&db.conn)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|packet| packet.data)
+            .collect()
+    }
+
+    fn policy(port: Option<&str>, tag: Option<&str>) -> AppRetentionPolicy {
+        AppRetentionPolicy {
+            scope: AppRetentionScope {
+                port: port.map(ToOwned::to_owned),
+                tag: tag.map(ToOwned::to_owned),
+            },
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn prunes_by_max_age_in_port_scope() {
+        let db = database().await;
+
+        insert(&db, "COM1", "tag", b"old 1", 2 * HOUR).await;
+        insert(&db, "COM2", "tag", b"old 2", 2 * HOUR).await;
+        insert(&db, "COM1", "tag", b"new 1", Duration::ZERO).await;
+
+        let policy = AppRetentionPolicy {
+            max_age: Some(HOUR),
+            ..policy(Some("COM1"), None)
+        };
+
+        assert_eq!(db.prune_packets(&policy).await.unwrap(), 1);
+        assert_eq!(remaining(&db).await, [b"old 2".to_vec(), b"new 1".to_vec()]);
+    }
+
+    #[tokio::test]
+    async fn prunes_by_max_rows_in_tag_scope() {
+        let db = database().await;
+
+        insert(&db, "COM1", "a", b"a 1", Duration::ZERO).await;
+        insert(&db, "COM2", "b", b"b 1", Duration::ZERO).await;
+        insert(&db, "COM2", "a", b"a 2", Duration::ZERO).await;
+        insert(&db, "COM1", "a", b"a 3", Duration::ZERO).await;
+
+        let policy = AppRetentionPolicy {
+            max_rows: Some(2),
+            ..policy(None, Some("a"))
+        };
+
+        assert_eq!(db.prune_packets(&policy).await.unwrap(), 1);
+        assert_eq!(
+            remaining(&db).await,
+            [b"b 1".to_vec(), b"a 2".to_vec(), b"a 3".to_vec()]
+        );
+    }
+
+    #[tokio::test]
+    async fn prunes_by_max_bytes_in_port_and_tag_scope() {
+        let db = database().await;
+
+        insert(&db, "COM1", "a", b"1111", Duration::ZERO).await;
+        insert(&db, "COM1", "a", b"2222", Duration::ZERO).await;
+        insert(&db, "COM1", "b", b"bbbb", Duration::ZERO).await;
+        insert(&db, "COM2", "a", b"cccc", Duration::ZERO).await;
+        insert(&db, "COM1", "a", b"3333", Duration::ZERO).await;
+
+        let policy = AppRetentionPolicy {
+            max_bytes: Some(8),
+            ..policy(Some("COM1"), Some("a"))
+        };
+
+        assert_eq!(db.prune_packets(&policy).await.unwrap(), 1);
+        assert_eq!(
+            remaining(&db).await,
+            [
+                b"2222".to_vec(),
+                b"bbbb".to_vec(),
+                b"cccc".to_vec(),
+                b"3333".to_vec()
+            ]
+        );
+
+        // Exactly at the limit.
+        assert_eq!(db.prune_packets(&policy).await.unwrap(), 0);
+
+        db.vacuum().await.unwrap();
+
+        assert_eq!(remaining(&db).await.len(), 4);
+    }
+
+    #[tokio::test]
+    async fn ignores_unknown_port() {
+        let db = database().await;
+
+        insert(&db, "COM1", "tag", b"old", 2 * HOUR).await;
+
+        let policy = AppRetentionPolicy {
+            max_age: Some(HOUR),
+            max_rows: Some(0),
+            max_bytes: Some(0),
+            ..policy(Some("COM2"), None)
+        };
+
+        assert_eq!(db.prune_packets(&policy).await.unwrap(), 0);
+        assert_eq!(remaining(&db).await.len(), 1);
+    }
 }
@@ -1,7 +1,16 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.1
 
+// Generated, only some entities are used by name.
+#[allow(unused_imports)]
 pub mod prelude;
 
+pub mod auto_responder;
+pub mod command_macro;
 pub mod open_options;
 pub mod packet;
+pub mod port_group;
+pub mod port_group_member;
+pub mod retention_policy;
+pub mod script;
 pub mod serial_port;
+pub mod subscription;
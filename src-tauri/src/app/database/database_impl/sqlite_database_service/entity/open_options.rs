@@ -16,6 +16,13 @@ pub struct Model {
     pub parity: i16,
     pub stop_bits: i16,
     pub timeout_milli_secs: i32,
+    pub outgoing_line_ending: i16,
+    #[sea_orm(column_type = "Blob", nullable)]
+    pub outgoing_line_ending_custom: Option<Vec<u8>>,
+    pub write_byte_delay_micros: i64,
+    pub write_line_delay_micros: i64,
+    pub write_queue_capacity: i32,
+    pub write_queue_overflow: i16,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -15,8 +15,16 @@ pub struct Model {
     pub outgoing_direct: Option<bool>,
     pub outgoing_broadcast: Option<bool>,
     pub outgoing_subscription: Option<String>,
+    pub outgoing_file: Option<String>,
+    pub outgoing_periodic: Option<i64>,
+    pub outgoing_macro: Option<String>,
+    pub outgoing_group: Option<String>,
+    pub outgoing_auto_responder: Option<String>,
+    pub outgoing_transfer: Option<String>,
     #[sea_orm(column_type = "Blob")]
     pub data: Vec<u8>,
+    pub first_byte_timestamp_micros: i64,
+    pub last_byte_timestamp_micros: i64,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -1,5 +1,12 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.0.1
 
+pub use super::auto_responder::Entity as AutoResponder;
+pub use super::command_macro::Entity as CommandMacro;
 pub use super::open_options::Entity as OpenOptions;
 pub use super::packet::Entity as Packet;
+pub use super::port_group::Entity as PortGroup;
+pub use super::port_group_member::Entity as PortGroupMember;
+pub use super::retention_policy::Entity as RetentionPolicy;
+pub use super::script::Entity as Script;
 pub use super::serial_port::Entity as SerialPort;
+pub use super::subscription::Entity as Subscription;
@@ -3,19 +3,76 @@ use std::time::Duration;
 use sea_orm::ActiveValue;
 
 use crate::app::{
-    model::managed_serial_port::AppOpenSerialPortOptions,
-    serial_state::model::{
-        CoreDataBits, CoreFlowControl, CoreIncomingPacket, CoreOpenSerialPortOptions,
-        CoreOutgoingPacket, CorePacket, CorePacketDirection, CorePacketOrigin, CoreParity,
-        CoreReadState, CoreStopBits, CoreSubscriptionPacketOrigin,
+    model::{
+        auto_responder::AppAutoResponder,
+        command_macro::{AppCommandMacro, AppCommandMacroTarget},
+        managed_serial_port::AppOpenSerialPortOptions,
+        port_group::AppPortGroup,
+        retention::{AppRetentionPolicy, AppRetentionScope},
+        script::AppScript,
+        subscription::{
+            AppRateLimitOverflow, AppSubscription, AppSubscriptionFilter, AppSubscriptionRateLimit,
+        },
+    },
+    serial_state::{
+        input::CoreInputMode,
+        model::{
+            CoreAutoResponderPacketOrigin, CoreDataBits, CoreFilePacketOrigin, CoreFlowControl,
+            CoreGroupPacketOrigin, CoreIncomingPacket, CoreLineEnding, CoreLineForwarding,
+            CoreMacroPacketOrigin, CoreOpenSerialPortOptions, CoreOutgoingPacket, CorePacket,
+            CorePacketDirection, CorePacketOrigin, CoreParity, CorePeriodicPacketOrigin,
+            CoreReadState, CoreStopBits, CoreSubscriptionMode, CoreSubscriptionPacketOrigin,
+            CoreTransferPacketOrigin, CoreWritePacing, CoreWriteQueueOptions,
+            CoreWriteQueueOverflow,
+        },
     },
 };
 
-use super::entity::{
-    open_options::{ActiveModel as OpenOptionsActiveModel, Model as OpenOptionsModel},
-    packet::{ActiveModel as PacketActiveModel, Model as PacketModel},
+use super::{
+    date_time_from_micros,
+    entity::{
+        auto_responder::{ActiveModel as AutoResponderActiveModel, Model as AutoResponderModel},
+        command_macro::{ActiveModel as CommandMacroActiveModel, Model as CommandMacroModel},
+        open_options::{ActiveModel as OpenOptionsActiveModel, Model as OpenOptionsModel},
+        packet::{ActiveModel as PacketActiveModel, Model as PacketModel},
+        port_group::{ActiveModel as PortGroupActiveModel, Model as PortGroupModel},
+        port_group_member::{
+            ActiveModel as PortGroupMemberActiveModel, Model as PortGroupMemberModel,
+        },
+        retention_policy::{
+            ActiveModel as RetentionPolicyActiveModel, Model as RetentionPolicyModel,
+        },
+        script::{ActiveModel as ScriptActiveModel, Model as ScriptModel},
+        subscription::{ActiveModel as SubscriptionActiveModel, Model as SubscriptionModel},
+    },
 };
 
+fn line_ending_from_columns(line_ending: i16, custom: Option<Vec<u8>>) -> CoreLineEnding {
+    match (line_ending, custom) {
+        (0, _) => CoreLineEnding::None,
+        (1, _) => CoreLineEnding::Lf,
+        (2, _) => CoreLineEnding::Cr,
+        (3, _) => CoreLineEnding::CrLf,
+        (4, Some(custom)) => CoreLineEnding::Custom(custom.into()),
+        _ => {
+            tracing::warn!(line_ending, "Unknown line ending. Returning default");
+
+            Default::default()
+        }
+    }
+}
+
+/// Returns the line ending and the bytes of a custom line ending.
+fn line_ending_into_columns(line_ending: CoreLineEnding) -> (i16, Option<Vec<u8>>) {
+    match line_ending {
+        CoreLineEnding::None => (0, None),
+        CoreLineEnding::Lf => (1, None),
+        CoreLineEnding::Cr => (2, None),
+        CoreLineEnding::CrLf => (3, None),
+        CoreLineEnding::Custom(custom) => (4, Some(custom.into())),
+    }
+}
+
 impl From<OpenOptionsModel> for AppOpenSerialPortOptions {
     fn from(model: OpenOptionsModel) -> Self {
         Self {
@@ -84,6 +141,31 @@ impl From<OpenOptionsModel> for AppOpenSerialPortOptions {
                     }
                 },
                 timeout: Duration::from_millis(model.timeout_milli_secs as u64),
+                outgoing_line_ending: line_ending_from_columns(
+                    model.outgoing_line_ending,
+                    model.outgoing_line_ending_custom,
+                ),
+                write_pacing: CoreWritePacing {
+                    byte_delay: Duration::from_micros(model.write_byte_delay_micros as u64),
+                    line_delay: Duration::from_micros(model.write_line_delay_micros as u64),
+                },
+                write_queue: CoreWriteQueueOptions {
+                    capacity: model.write_queue_capacity as usize,
+                    overflow: match model.write_queue_overflow {
+                        0 => CoreWriteQueueOverflow::Block,
+                        1 => CoreWriteQueueOverflow::DropOldest,
+                        2 => CoreWriteQueueOverflow::DropNewest,
+                        3 => CoreWriteQueueOverflow::Error,
+                        _ => {
+                            tracing::warn!(
+                                write_queue_overflow = model.write_queue_overflow,
+                                "Unknown write queue overflow. Returning default"
+                            );
+
+                            Default::default()
+                        }
+                    },
+                },
             },
         }
     }
@@ -91,6 +173,9 @@ impl From<OpenOptionsModel> for AppOpenSerialPortOptions {
 
 impl From<(i32, AppOpenSerialPortOptions)> for OpenOptionsActiveModel {
     fn from((serial_port_id, options): (i32, AppOpenSerialPortOptions)) -> Self {
+        let (outgoing_line_ending, outgoing_line_ending_custom) =
+            line_ending_into_columns(options.core_options.outgoing_line_ending);
+
         Self {
             serial_port_id: ActiveValue::Set(serial_port_id),
             tag: ActiveValue::Set(options.tag),
@@ -120,37 +205,103 @@ impl From<(i32, AppOpenSerialPortOptions)> for OpenOptionsActiveModel {
                 CoreStopBits::Two => 1,
             }),
             timeout_milli_secs: ActiveValue::Set(options.core_options.timeout.as_millis() as i32),
+            outgoing_line_ending: ActiveValue::Set(outgoing_line_ending),
+            outgoing_line_ending_custom: ActiveValue::Set(outgoing_line_ending_custom),
+            write_byte_delay_micros: ActiveValue::Set(
+                options.core_options.write_pacing.byte_delay.as_micros() as i64,
+            ),
+            write_line_delay_micros: ActiveValue::Set(
+                options.core_options.write_pacing.line_delay.as_micros() as i64,
+            ),
+            write_queue_capacity: ActiveValue::Set(
+                options.core_options.write_queue.capacity as i32,
+            ),
+            write_queue_overflow: ActiveValue::Set(
+                match options.core_options.write_queue.overflow {
+                    CoreWriteQueueOverflow::Block => 0,
+                    CoreWriteQueueOverflow::DropOldest => 1,
+                    CoreWriteQueueOverflow::DropNewest => 2,
+                    CoreWriteQueueOverflow::Error => 3,
+                },
+            ),
             ..Default::default()
         }
     }
 }
 
+/// Returns `None` if no origin column is set.
+fn packet_origin(model: &PacketModel) -> Option<CorePacketOrigin> {
+    if model.outgoing_direct == Some(true) {
+        return Some(CorePacketOrigin::Direct);
+    }
+
+    if model.outgoing_broadcast == Some(true) {
+        return Some(CorePacketOrigin::Broadcast);
+    }
+
+    if let Some(name_from) = &model.outgoing_subscription {
+        return Some(CorePacketOrigin::Subscription(
+            // The path is not saved.
+            CoreSubscriptionPacketOrigin {
+                name: name_from.clone(),
+                path: vec![name_from.clone()],
+                echo_guard: false,
+            },
+        ));
+    }
+
+    if let Some(path) = &model.outgoing_file {
+        return Some(CorePacketOrigin::File(CoreFilePacketOrigin {
+            path: path.clone(),
+        }));
+    }
+
+    if let Some(path) = &model.outgoing_transfer {
+        return Some(CorePacketOrigin::Transfer(CoreTransferPacketOrigin {
+            path: path.clone(),
+        }));
+    }
+
+    if let Some(job_id) = model.outgoing_periodic {
+        return Some(CorePacketOrigin::Periodic(CorePeriodicPacketOrigin {
+            job_id: job_id as u64,
+        }));
+    }
+
+    // The line ending of the macro is not saved.
+    if let Some(name) = &model.outgoing_macro {
+        return Some(CorePacketOrigin::Macro(CoreMacroPacketOrigin {
+            name: name.clone(),
+            line_ending: None,
+        }));
+    }
+
+    if let Some(name) = &model.outgoing_group {
+        return Some(CorePacketOrigin::Group(CoreGroupPacketOrigin {
+            name: name.clone(),
+        }));
+    }
+
+    if let Some(name) = &model.outgoing_auto_responder {
+        return Some(CorePacketOrigin::AutoResponder(
+            CoreAutoResponderPacketOrigin { name: name.clone() },
+        ));
+    }
+
+    None
+}
+
 /// Port name is not saved in the model. We have to get it with a join.
 impl From<(String, PacketModel)> for CorePacket {
     fn from((port_name, model): (String, PacketModel)) -> Self {
-        let packet_direction = match (
-            model.incoming,
-            model.outgioing,
-            model.outgoing_direct,
-            model.outgoing_broadcast,
-            model.outgoing_subscription,
-        ) {
-            (true, _, _, _, _) => CorePacketDirection::Incoming(CoreIncomingPacket {
+        let packet_direction = match (model.incoming, model.outgioing, packet_origin(&model)) {
+            (true, _, _) => CorePacketDirection::Incoming(CoreIncomingPacket {
                 line: model.data.into(),
             }),
-            (_, true, Some(true), _, _) => CorePacketDirection::Outgoing(CoreOutgoingPacket {
-                bytes: model.data.into(),
-                packet_origin: CorePacketOrigin::Direct,
-            }),
-            (_, true, _, Some(true), _) => CorePacketDirection::Outgoing(CoreOutgoingPacket {
+            (_, true, Some(packet_origin)) => CorePacketDirection::Outgoing(CoreOutgoingPacket {
                 bytes: model.data.into(),
-                packet_origin: CorePacketOrigin::Broadcast,
-            }),
-            (_, true, _, _, Some(name_from)) => CorePacketDirection::Outgoing(CoreOutgoingPacket {
-                bytes: model.data.into(),
-                packet_origin: CorePacketOrigin::Subscription(CoreSubscriptionPacketOrigin {
-                    name: name_from,
-                }),
+                packet_origin,
+                ack: None,
             }),
             _ => {
                 tracing::warn!("Malformed packet. Defaulting to incoming");
@@ -161,60 +312,414 @@ impl From<(String, PacketModel)> for CorePacket {
             }
         };
 
+        // Packets saved before the byte timestamps were introduced only have the `timestamp`.
+        let (first_byte_timestamp_micros, last_byte_timestamp_micros) = match (
+            model.first_byte_timestamp_micros,
+            model.last_byte_timestamp_micros,
+        ) {
+            (0, 0) => {
+                let timestamp_micros = model.timestamp.timestamp_micros() as u64;
+
+                (timestamp_micros, timestamp_micros)
+            }
+            (first, last) => (first as u64, last as u64),
+        };
+
         Self {
             packet_direction,
             port_name,
-            timestamp_millis: model.timestamp.timestamp_millis() as u64,
+            first_byte_timestamp_micros,
+            last_byte_timestamp_micros,
         }
     }
 }
 
 impl From<(i32, String, CorePacket)> for PacketActiveModel {
     fn from((serial_port_id, tag, packet): (i32, String, CorePacket)) -> Self {
-        let (incoming, outgioing, outgoing_direct, outgoing_broadcast, outgoing_subscription, data) =
-            match packet.packet_direction {
-                CorePacketDirection::Incoming(incoming_packet) => {
-                    (true, false, None, None, None, incoming_packet.line.into())
-                }
-                CorePacketDirection::Outgoing(outgoing_packet) => {
-                    match outgoing_packet.packet_origin {
-                        CorePacketOrigin::Direct => (
-                            false,
-                            true,
-                            Some(true),
-                            None,
-                            None,
-                            outgoing_packet.bytes.into(),
-                        ),
-                        CorePacketOrigin::Broadcast => (
-                            false,
-                            true,
-                            None,
-                            Some(true),
-                            None,
-                            outgoing_packet.bytes.into(),
-                        ),
-                        CorePacketOrigin::Subscription(subscription) => (
-                            false,
-                            true,
-                            None,
-                            None,
-                            Some(subscription.name),
-                            outgoing_packet.bytes.into(),
-                        ),
-                    }
-                }
-            };
+        // Always set, since the rows of a batch must have the same columns.
+        let timestamp = date_time_from_micros(packet.last_byte_timestamp_micros);
+        let first_byte_timestamp_micros = packet.first_byte_timestamp_micros as i64;
+        let last_byte_timestamp_micros = packet.last_byte_timestamp_micros as i64;
 
-        Self {
+        let (incoming, outgioing, packet_origin, data) = match packet.packet_direction {
+            CorePacketDirection::Incoming(incoming_packet) => {
+                (true, false, None, incoming_packet.line.into())
+            }
+            CorePacketDirection::Outgoing(outgoing_packet) => (
+                false,
+                true,
+                Some(outgoing_packet.packet_origin),
+                outgoing_packet.bytes.into(),
+            ),
+        };
+
+        let mut packet_model = Self {
             serial_port_id: ActiveValue::Set(serial_port_id),
             tag: ActiveValue::Set(tag),
+            timestamp: ActiveValue::Set(timestamp),
             incoming: ActiveValue::Set(incoming),
             outgioing: ActiveValue::Set(outgioing),
-            outgoing_direct: ActiveValue::Set(outgoing_direct),
-            outgoing_broadcast: ActiveValue::Set(outgoing_broadcast),
-            outgoing_subscription: ActiveValue::Set(outgoing_subscription),
+            outgoing_direct: ActiveValue::Set(None),
+            outgoing_broadcast: ActiveValue::Set(None),
+            outgoing_subscription: ActiveValue::Set(None),
+            outgoing_file: ActiveValue::Set(None),
+            outgoing_periodic: ActiveValue::Set(None),
+            outgoing_macro: ActiveValue::Set(None),
+            outgoing_group: ActiveValue::Set(None),
+            outgoing_auto_responder: ActiveValue::Set(None),
+            outgoing_transfer: ActiveValue::Set(None),
             data: ActiveValue::Set(data),
+            first_byte_timestamp_micros: ActiveValue::Set(first_byte_timestamp_micros),
+            last_byte_timestamp_micros: ActiveValue::Set(last_byte_timestamp_micros),
+            ..Default::default()
+        };
+
+        match packet_origin {
+            None => {}
+            Some(CorePacketOrigin::Direct) => {
+                packet_model.outgoing_direct = ActiveValue::Set(Some(true));
+            }
+            Some(CorePacketOrigin::Broadcast) => {
+                packet_model.outgoing_broadcast = ActiveValue::Set(Some(true));
+            }
+            Some(CorePacketOrigin::Subscription(subscription)) => {
+                packet_model.outgoing_subscription = ActiveValue::Set(Some(subscription.name));
+            }
+            Some(CorePacketOrigin::File(file)) => {
+                packet_model.outgoing_file = ActiveValue::Set(Some(file.path));
+            }
+            Some(CorePacketOrigin::Transfer(transfer)) => {
+                packet_model.outgoing_transfer = ActiveValue::Set(Some(transfer.path));
+            }
+            Some(CorePacketOrigin::Periodic(periodic)) => {
+                packet_model.outgoing_periodic = ActiveValue::Set(Some(periodic.job_id as i64));
+            }
+            Some(CorePacketOrigin::Macro(origin)) => {
+                packet_model.outgoing_macro = ActiveValue::Set(Some(origin.name));
+            }
+            Some(CorePacketOrigin::Group(origin)) => {
+                packet_model.outgoing_group = ActiveValue::Set(Some(origin.name));
+            }
+            Some(CorePacketOrigin::AutoResponder(origin)) => {
+                packet_model.outgoing_auto_responder = ActiveValue::Set(Some(origin.name));
+            }
+        }
+
+        packet_model
+    }
+}
+
+impl From<CommandMacroModel> for AppCommandMacro {
+    fn from(model: CommandMacroModel) -> Self {
+        let target = match (model.target_port, model.target_tag) {
+            (Some(port), _) => AppCommandMacroTarget::Port(port),
+            (None, Some(tag)) => AppCommandMacroTarget::Tag(tag),
+            (None, None) => AppCommandMacroTarget::None,
+        };
+
+        Self {
+            name: model.name,
+            payload: model.payload,
+            input_mode: match model.input_mode {
+                0 => CoreInputMode::Text,
+                1 => CoreInputMode::Escaped,
+                2 => CoreInputMode::Hex,
+                _ => {
+                    tracing::warn!(
+                        input_mode = model.input_mode,
+                        "Unknown input mode. Returning default"
+                    );
+
+                    Default::default()
+                }
+            },
+            line_ending: model
+                .line_ending
+                .map(|line_ending| line_ending_from_columns(line_ending, model.line_ending_custom)),
+            target,
+        }
+    }
+}
+
+impl From<AppCommandMacro> for CommandMacroActiveModel {
+    fn from(command_macro: AppCommandMacro) -> Self {
+        let (line_ending, line_ending_custom) = match command_macro.line_ending {
+            Some(line_ending) => {
+                let (line_ending, line_ending_custom) = line_ending_into_columns(line_ending);
+
+                (Some(line_ending), line_ending_custom)
+            }
+            None => (None, None),
+        };
+
+        let (target_port, target_tag) = match command_macro.target {
+            AppCommandMacroTarget::None => (None, None),
+            AppCommandMacroTarget::Port(port) => (Some(port), None),
+            AppCommandMacroTarget::Tag(tag) => (None, Some(tag)),
+        };
+
+        Self {
+            name: ActiveValue::Set(command_macro.name),
+            payload: ActiveValue::Set(command_macro.payload),
+            input_mode: ActiveValue::Set(match command_macro.input_mode {
+                CoreInputMode::Text => 0,
+                CoreInputMode::Escaped => 1,
+                CoreInputMode::Hex => 2,
+            }),
+            line_ending: ActiveValue::Set(line_ending),
+            line_ending_custom: ActiveValue::Set(line_ending_custom),
+            target_port: ActiveValue::Set(target_port),
+            target_tag: ActiveValue::Set(target_tag),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<ScriptModel> for AppScript {
+    fn from(model: ScriptModel) -> Self {
+        Self {
+            name: model.name,
+            source: model.source,
+        }
+    }
+}
+
+impl From<AppScript> for ScriptActiveModel {
+    fn from(script: AppScript) -> Self {
+        Self {
+            name: ActiveValue::Set(script.name),
+            source: ActiveValue::Set(script.source),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<(PortGroupModel, Vec<PortGroupMemberModel>)> for AppPortGroup {
+    fn from((model, members): (PortGroupModel, Vec<PortGroupMemberModel>)) -> Self {
+        Self {
+            name: model.name,
+            ports: members.into_iter().map(|member| member.port_name).collect(),
+        }
+    }
+}
+
+impl From<&AppPortGroup> for PortGroupActiveModel {
+    fn from(port_group: &AppPortGroup) -> Self {
+        Self {
+            name: ActiveValue::Set(port_group.name.clone()),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<(i32, String)> for PortGroupMemberActiveModel {
+    fn from((port_group_id, port_name): (i32, String)) -> Self {
+        Self {
+            port_group_id: ActiveValue::Set(port_group_id),
+            port_name: ActiveValue::Set(port_name),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<AutoResponderModel> for AppAutoResponder {
+    fn from(model: AutoResponderModel) -> Self {
+        Self {
+            name: model.name,
+            port: model.port,
+            pattern: model.pattern,
+            response: model.response,
+            input_mode: match model.input_mode {
+                0 => CoreInputMode::Text,
+                1 => CoreInputMode::Escaped,
+                2 => CoreInputMode::Hex,
+                _ => {
+                    tracing::warn!(
+                        input_mode = model.input_mode,
+                        "Unknown input mode. Returning default"
+                    );
+
+                    Default::default()
+                }
+            },
+            target: model.target_port,
+            delay: Duration::from_millis(model.delay_millis as u64),
+            enabled: model.enabled,
+        }
+    }
+}
+
+impl From<AppAutoResponder> for AutoResponderActiveModel {
+    fn from(auto_responder: AppAutoResponder) -> Self {
+        Self {
+            name: ActiveValue::Set(auto_responder.name),
+            port: ActiveValue::Set(auto_responder.port),
+            pattern: ActiveValue::Set(auto_responder.pattern),
+            response: ActiveValue::Set(auto_responder.response),
+            input_mode: ActiveValue::Set(match auto_responder.input_mode {
+                CoreInputMode::Text => 0,
+                CoreInputMode::Escaped => 1,
+                CoreInputMode::Hex => 2,
+            }),
+            target_port: ActiveValue::Set(auto_responder.target),
+            delay_millis: ActiveValue::Set(auto_responder.delay.as_millis() as i64),
+            enabled: ActiveValue::Set(auto_responder.enabled),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<SubscriptionModel> for AppSubscription {
+    fn from(model: SubscriptionModel) -> Self {
+        let filter = match (model.filter_kind, model.filter_value) {
+            (None, _) => None,
+            (Some(0), Some(pattern)) => Some(AppSubscriptionFilter::Regex(
+                String::from_utf8_lossy(&pattern).into_owned(),
+            )),
+            (Some(1), Some(prefix)) => Some(AppSubscriptionFilter::Prefix(prefix.into())),
+            (Some(filter_kind), _) => {
+                tracing::warn!(
+                    filter_kind,
+                    "Unknown subscription filter. Returning no filter"
+                );
+
+                None
+            }
+        };
+
+        let mode = match model.line_mode {
+            false => CoreSubscriptionMode::Raw,
+            true => CoreSubscriptionMode::Lines(CoreLineForwarding {
+                line_ending: line_ending_from_columns(
+                    model.line_ending.unwrap_or(1),
+                    model.line_ending_custom,
+                ),
+                prefix_name: model.prefix_name,
+                prefix_timestamp: model.prefix_timestamp,
+            }),
+        };
+
+        let rate_limit = AppSubscriptionRateLimit {
+            bytes_per_second: model.rate_limit_bytes_per_second as u64,
+            frames_per_second: model.rate_limit_frames_per_second as u64,
+            overflow: match model.rate_limit_overflow {
+                0 => AppRateLimitOverflow::Drop,
+                1 => AppRateLimitOverflow::Buffer,
+                _ => {
+                    tracing::warn!(
+                        rate_limit_overflow = model.rate_limit_overflow,
+                        "Unknown rate limit overflow. Returning default"
+                    );
+
+                    Default::default()
+                }
+            },
+            latency: Duration::from_micros(model.rate_limit_latency_micros as u64),
+            capacity: model.rate_limit_capacity as usize,
+        };
+
+        Self {
+            from: model.from_port,
+            to: model.to_port,
+            filter,
+            mode,
+            rate_limit,
+            echo_guard: model.echo_guard,
+        }
+    }
+}
+
+impl From<AppSubscription> for SubscriptionActiveModel {
+    fn from(subscription: AppSubscription) -> Self {
+        let (filter_kind, filter_value) = match subscription.filter {
+            None => (None, None),
+            Some(AppSubscriptionFilter::Regex(pattern)) => (Some(0), Some(pattern.into_bytes())),
+            Some(AppSubscriptionFilter::Prefix(prefix)) => (Some(1), Some(prefix.to_vec())),
+        };
+
+        let (line_mode, line_ending, line_ending_custom, prefix_name, prefix_timestamp) =
+            match subscription.mode {
+                CoreSubscriptionMode::Raw => (false, None, None, false, false),
+                CoreSubscriptionMode::Lines(line_forwarding) => {
+                    let (line_ending, line_ending_custom) =
+                        line_ending_into_columns(line_forwarding.line_ending);
+
+                    (
+                        true,
+                        Some(line_ending),
+                        line_ending_custom,
+                        line_forwarding.prefix_name,
+                        line_forwarding.prefix_timestamp,
+                    )
+                }
+            };
+
+        Self {
+            from_port: ActiveValue::Set(subscription.from),
+            to_port: ActiveValue::Set(subscription.to),
+            filter_kind: ActiveValue::Set(filter_kind),
+            filter_value: ActiveValue::Set(filter_value),
+            line_mode: ActiveValue::Set(line_mode),
+            line_ending: ActiveValue::Set(line_ending),
+            line_ending_custom: ActiveValue::Set(line_ending_custom),
+            prefix_name: ActiveValue::Set(prefix_name),
+            prefix_timestamp: ActiveValue::Set(prefix_timestamp),
+            rate_limit_bytes_per_second: ActiveValue::Set(
+                subscription.rate_limit.bytes_per_second as i64,
+            ),
+            rate_limit_frames_per_second: ActiveValue::Set(
+                subscription.rate_limit.frames_per_second as i64,
+            ),
+            rate_limit_latency_micros: ActiveValue::Set(
+                subscription.rate_limit.latency.as_micros() as i64,
+            ),
+            rate_limit_overflow: ActiveValue::Set(match subscription.rate_limit.overflow {
+                AppRateLimitOverflow::Drop => 0,
+                AppRateLimitOverflow::Buffer => 1,
+            }),
+            echo_guard: ActiveValue::Set(subscription.echo_guard),
+            rate_limit_capacity: ActiveValue::Set(subscription.rate_limit.capacity as i32),
+            ..Default::default()
+        }
+    }
+}
+
+impl From<RetentionPolicyModel> for AppRetentionPolicy {
+    fn from(model: RetentionPolicyModel) -> Self {
+        Self {
+            scope: AppRetentionScope {
+                port: model.port,
+                tag: model.tag,
+            },
+            max_age: model
+                .max_age_seconds
+                .map(|max_age_seconds| Duration::from_secs(max_age_seconds as u64)),
+            max_rows: model.max_rows.map(|max_rows| max_rows as u64),
+            max_bytes: model.max_bytes.map(|max_bytes| max_bytes as u64),
+        }
+    }
+}
+
+impl From<AppRetentionPolicy> for RetentionPolicyActiveModel {
+    fn from(policy: AppRetentionPolicy) -> Self {
+        Self {
+            port: ActiveValue::Set(policy.scope.port),
+            tag: ActiveValue::Set(policy.scope.tag),
+            max_age_seconds: ActiveValue::Set(
+                policy
+                    .max_age
+                    .map(|max_age| max_age.as_secs().min(i64::MAX as u64) as i64),
+            ),
+            max_rows: ActiveValue::Set(
+                policy
+                    .max_rows
+                    .map(|max_rows| max_rows.min(i64::MAX as u64) as i64),
+            ),
+            max_bytes: ActiveValue::Set(
+                policy
+                    .max_bytes
+                    .map(|max_bytes| max_bytes.min(i64::MAX as u64) as i64),
+            ),
             ..Default::default()
         }
     }
@@ -1,12 +1,48 @@
 pub use sea_orm_migration::prelude::*;
 
 mod m20220101_000001_create_table;
+mod m20261019_000002_add_packet_byte_timestamps;
+mod m20261019_000003_add_open_options_outgoing_line_ending;
+mod m20261019_000004_add_packet_outgoing_file;
+mod m20261019_000005_add_packet_outgoing_transfer;
+mod m20261019_000006_add_packet_outgoing_periodic;
+mod m20261019_000007_create_command_macro_table;
+mod m20261019_000008_create_script_table;
+mod m20261019_000009_add_open_options_write_pacing;
+mod m20261019_000010_add_open_options_write_queue;
+mod m20261019_000011_create_port_group_tables;
+mod m20261019_000012_create_auto_responder_table;
+mod m20261019_000013_create_subscription_table;
+mod m20261019_000014_add_subscription_line_mode;
+mod m20261019_000015_add_subscription_rate_limit;
+mod m20261019_000016_create_retention_policy_table;
+mod m20261019_000017_add_subscription_echo_guard;
+mod m20261019_000018_add_subscription_rate_limit_capacity;
 
 pub struct Migrator;
 
 #[async_trait::async_trait]
 impl MigratorTrait for Migrator {
     fn migrations() -> Vec<Box<dyn MigrationTrait>> {
-        vec![Box::new(m20220101_000001_create_table::Migration)]
+        vec![
+            Box::new(m20220101_000001_create_table::Migration),
+            Box::new(m20261019_000002_add_packet_byte_timestamps::Migration),
+            Box::new(m20261019_000003_add_open_options_outgoing_line_ending::Migration),
+            Box::new(m20261019_000004_add_packet_outgoing_file::Migration),
+            Box::new(m20261019_000005_add_packet_outgoing_transfer::Migration),
+            Box::new(m20261019_000006_add_packet_outgoing_periodic::Migration),
+            Box::new(m20261019_000007_create_command_macro_table::Migration),
+            Box::new(m20261019_000008_create_script_table::Migration),
+            Box::new(m20261019_000009_add_open_options_write_pacing::Migration),
+            Box::new(m20261019_000010_add_open_options_write_queue::Migration),
+            Box::new(m20261019_000011_create_port_group_tables::Migration),
+            Box::new(m20261019_000012_create_auto_responder_table::Migration),
+            Box::new(m20261019_000013_create_subscription_table::Migration),
+            Box::new(m20261019_000014_add_subscription_line_mode::Migration),
+            Box::new(m20261019_000015_add_subscription_rate_limit::Migration),
+            Box::new(m20261019_000016_create_retention_policy_table::Migration),
+            Box::new(m20261019_000017_add_subscription_echo_guard::Migration),
+            Box::new(m20261019_000018_add_subscription_rate_limit_capacity::Migration),
+        ]
     }
 }
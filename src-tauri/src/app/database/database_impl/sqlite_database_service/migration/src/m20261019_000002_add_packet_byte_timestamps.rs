@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sqlite does not support multiple alter options in a single statement.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .add_column(
+                        ColumnDef::new(Packet::FirstByteTimestampMicros)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .add_column(
+                        ColumnDef::new(Packet::LastByteTimestampMicros)
+                            .big_integer()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .drop_column(Packet::LastByteTimestampMicros)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .drop_column(Packet::FirstByteTimestampMicros)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Packet {
+    Table,
+    FirstByteTimestampMicros,
+    LastByteTimestampMicros,
+}
@@ -0,0 +1,65 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sqlite does not support multiple alter options in a single statement.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OpenOptions::Table)
+                    .add_column(
+                        ColumnDef::new(OpenOptions::OutgoingLineEnding)
+                            .small_unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OpenOptions::Table)
+                    // represents the bytes of a custom line ending
+                    .add_column(ColumnDef::new(OpenOptions::OutgoingLineEndingCustom).blob())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OpenOptions::Table)
+                    .drop_column(OpenOptions::OutgoingLineEndingCustom)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(OpenOptions::Table)
+                    .drop_column(OpenOptions::OutgoingLineEnding)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum OpenOptions {
+    Table,
+    OutgoingLineEnding,
+    OutgoingLineEndingCustom,
+}
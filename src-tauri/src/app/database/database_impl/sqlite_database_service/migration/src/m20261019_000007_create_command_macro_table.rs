@@ -0,0 +1,91 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(CommandMacro::Table)
+                    .col(
+                        ColumnDef::new(CommandMacro::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(CommandMacro::Name)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .col(ColumnDef::new(CommandMacro::Payload).text().not_null())
+                    .col(
+                        ColumnDef::new(CommandMacro::InputMode)
+                            .small_unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    // null means the outgoing line ending of the serial port is used
+                    .col(ColumnDef::new(CommandMacro::LineEnding).small_unsigned())
+                    // represents the bytes of a custom line ending
+                    .col(ColumnDef::new(CommandMacro::LineEndingCustom).blob())
+                    .col(ColumnDef::new(CommandMacro::TargetPort).text())
+                    .col(ColumnDef::new(CommandMacro::TargetTag).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    // represents the name of the executed macro
+                    .add_column(ColumnDef::new(Packet::OutgoingMacro).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .drop_column(Packet::OutgoingMacro)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(CommandMacro::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum CommandMacro {
+    Table,
+    Id,
+    Name,
+    Payload,
+    InputMode,
+    LineEnding,
+    LineEndingCustom,
+    TargetPort,
+    TargetTag,
+}
+
+#[derive(DeriveIden)]
+enum Packet {
+    Table,
+    OutgoingMacro,
+}
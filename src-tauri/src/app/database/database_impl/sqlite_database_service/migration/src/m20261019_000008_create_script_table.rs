@@ -0,0 +1,40 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Script::Table)
+                    .col(
+                        ColumnDef::new(Script::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(ColumnDef::new(Script::Name).text().not_null().unique_key())
+                    .col(ColumnDef::new(Script::Source).text().not_null())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Script::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Script {
+    Table,
+    Id,
+    Name,
+    Source,
+}
@@ -0,0 +1,113 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(PortGroup::Table)
+                    .col(
+                        ColumnDef::new(PortGroup::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PortGroup::Name)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_table(
+                Table::create()
+                    .table(PortGroupMember::Table)
+                    .col(
+                        ColumnDef::new(PortGroupMember::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(PortGroupMember::PortGroupId)
+                            .integer()
+                            .not_null(),
+                    )
+                    // not a foreign key, members do not have to be opened before
+                    .col(ColumnDef::new(PortGroupMember::PortName).text().not_null())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .on_update(ForeignKeyAction::Cascade)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .from(PortGroupMember::Table, PortGroupMember::PortGroupId)
+                            .to(PortGroup::Table, PortGroup::Id),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    // represents the name of the port group
+                    .add_column(ColumnDef::new(Packet::OutgoingGroup).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .drop_column(Packet::OutgoingGroup)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PortGroupMember::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(PortGroup::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum PortGroup {
+    Table,
+    Id,
+    Name,
+}
+
+#[derive(DeriveIden)]
+enum PortGroupMember {
+    Table,
+    Id,
+    PortGroupId,
+    PortName,
+}
+
+#[derive(DeriveIden)]
+enum Packet {
+    Table,
+    OutgoingGroup,
+}
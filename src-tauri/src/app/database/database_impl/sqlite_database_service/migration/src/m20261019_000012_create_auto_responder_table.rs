@@ -0,0 +1,103 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(AutoResponder::Table)
+                    .col(
+                        ColumnDef::new(AutoResponder::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    .col(
+                        ColumnDef::new(AutoResponder::Name)
+                            .text()
+                            .not_null()
+                            .unique_key(),
+                    )
+                    // not a foreign key, the port does not have to be opened before
+                    .col(ColumnDef::new(AutoResponder::Port).text().not_null())
+                    .col(ColumnDef::new(AutoResponder::Pattern).text().not_null())
+                    .col(ColumnDef::new(AutoResponder::Response).text().not_null())
+                    .col(
+                        ColumnDef::new(AutoResponder::InputMode)
+                            .small_unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    // null means the response is sent back to the port
+                    .col(ColumnDef::new(AutoResponder::TargetPort).text())
+                    .col(
+                        ColumnDef::new(AutoResponder::DelayMillis)
+                            .big_unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .col(
+                        ColumnDef::new(AutoResponder::Enabled)
+                            .boolean()
+                            .not_null()
+                            .default(true),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    // represents the name of the auto-responder
+                    .add_column(ColumnDef::new(Packet::OutgoingAutoResponder).text())
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Packet::Table)
+                    .drop_column(Packet::OutgoingAutoResponder)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(AutoResponder::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum AutoResponder {
+    Table,
+    Id,
+    Name,
+    Port,
+    Pattern,
+    Response,
+    InputMode,
+    TargetPort,
+    DelayMillis,
+    Enabled,
+}
+
+#[derive(DeriveIden)]
+enum Packet {
+    Table,
+    OutgoingAutoResponder,
+}
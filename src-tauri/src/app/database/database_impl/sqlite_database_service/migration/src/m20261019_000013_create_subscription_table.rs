@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(Subscription::Table)
+                    .col(
+                        ColumnDef::new(Subscription::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // not foreign keys, subscriptions can exist before the serial ports are opened
+                    .col(ColumnDef::new(Subscription::FromPort).text().not_null())
+                    .col(ColumnDef::new(Subscription::ToPort).text().not_null())
+                    // null means every read chunk is forwarded
+                    .col(ColumnDef::new(Subscription::FilterKind).small_unsigned())
+                    // represents the pattern or the prefix bytes of the filter
+                    .col(ColumnDef::new(Subscription::FilterValue).blob())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-subscription-from_port-to_port")
+                    .table(Subscription::Table)
+                    .col(Subscription::FromPort)
+                    .col(Subscription::ToPort)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Subscription::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Subscription {
+    Table,
+    Id,
+    FromPort,
+    ToPort,
+    FilterKind,
+    FilterValue,
+}
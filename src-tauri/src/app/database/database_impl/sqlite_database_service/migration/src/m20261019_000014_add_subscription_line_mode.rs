@@ -0,0 +1,104 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sqlite does not support multiple alter options in a single statement.
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(
+                        ColumnDef::new(Subscription::LineMode)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // null if not in line mode
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(ColumnDef::new(Subscription::LineEnding).small_unsigned())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(ColumnDef::new(Subscription::LineEndingCustom).blob())
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(
+                        ColumnDef::new(Subscription::PrefixName)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(
+                        ColumnDef::new(Subscription::PrefixTimestamp)
+                            .boolean()
+                            .not_null()
+                            .default(false),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Subscription::PrefixTimestamp,
+            Subscription::PrefixName,
+            Subscription::LineEndingCustom,
+            Subscription::LineEnding,
+            Subscription::LineMode,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Subscription::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Subscription {
+    Table,
+    LineMode,
+    LineEnding,
+    LineEndingCustom,
+    PrefixName,
+    PrefixTimestamp,
+}
@@ -0,0 +1,71 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Sqlite does not support multiple alter options in a single statement.
+        // 0 means unlimited
+        for column in [
+            Subscription::RateLimitBytesPerSecond,
+            Subscription::RateLimitFramesPerSecond,
+            Subscription::RateLimitLatencyMicros,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Subscription::Table)
+                        .add_column(ColumnDef::new(column).big_integer().not_null().default(0))
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Subscription::Table)
+                    .add_column(
+                        ColumnDef::new(Subscription::RateLimitOverflow)
+                            .small_unsigned()
+                            .not_null()
+                            .default(0),
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        for column in [
+            Subscription::RateLimitOverflow,
+            Subscription::RateLimitLatencyMicros,
+            Subscription::RateLimitFramesPerSecond,
+            Subscription::RateLimitBytesPerSecond,
+        ] {
+            manager
+                .alter_table(
+                    Table::alter()
+                        .table(Subscription::Table)
+                        .drop_column(column)
+                        .to_owned(),
+                )
+                .await?;
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum Subscription {
+    Table,
+    RateLimitBytesPerSecond,
+    RateLimitFramesPerSecond,
+    RateLimitLatencyMicros,
+    RateLimitOverflow,
+}
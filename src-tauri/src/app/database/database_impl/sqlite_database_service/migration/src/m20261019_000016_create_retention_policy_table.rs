@@ -0,0 +1,82 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .create_table(
+                Table::create()
+                    .table(RetentionPolicy::Table)
+                    .col(
+                        ColumnDef::new(RetentionPolicy::Id)
+                            .integer()
+                            .not_null()
+                            .auto_increment()
+                            .primary_key(),
+                    )
+                    // not a foreign key, policies can exist before the serial port is opened
+                    // null means every serial port
+                    .col(ColumnDef::new(RetentionPolicy::Port).text())
+                    // null means every tag
+                    .col(ColumnDef::new(RetentionPolicy::Tag).text())
+                    // null means no limit
+                    .col(ColumnDef::new(RetentionPolicy::MaxAgeSeconds).big_integer())
+                    .col(ColumnDef::new(RetentionPolicy::MaxRows).big_integer())
+                    .col(ColumnDef::new(RetentionPolicy::MaxBytes).big_integer())
+                    .to_owned(),
+            )
+            .await?;
+
+        // pruning scans the packets of a serial port, newest first
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx-packet-serial_port_id-id")
+                    .table(Packet::Table)
+                    .col(Packet::SerialPortId)
+                    .col(Packet::Id)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_index(
+                Index::drop()
+                    .name("idx-packet-serial_port_id-id")
+                    .table(Packet::Table)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RetentionPolicy::Table).to_owned())
+            .await?;
+
+        Ok(())
+    }
+}
+
+#[derive(DeriveIden)]
+enum RetentionPolicy {
+    Table,
+    Id,
+    Port,
+    Tag,
+    MaxAgeSeconds,
+    MaxRows,
+    MaxBytes,
+}
+
+#[derive(DeriveIden)]
+enum Packet {
+    Table,
+    Id,
+    SerialPortId,
+}
@@ -1,5 +1,17 @@
+use std::collections::HashMap;
+
 use crate::app::{
-    model::managed_serial_port::AppOpenSerialPortOptions, serial_state::model::CorePacket,
+    model::{
+        auto_responder::AppAutoResponder,
+        command_macro::AppCommandMacro,
+        managed_serial_port::AppOpenSerialPortOptions,
+        packet::{AppPacketPage, AppPacketQuery},
+        port_group::AppPortGroup,
+        retention::{AppDatabaseStats, AppRetentionPolicy, AppRetentionScope},
+        script::AppScript,
+        subscription::AppSubscription,
+    },
+    serial_state::model::CorePacket,
 };
 
 use super::{error::*, model::UpdateOrInsert};
@@ -33,10 +45,161 @@ pub trait DatabaseService {
         options: AppOpenSerialPortOptions,
     ) -> Result<UpdateOrInsert<i32>, UpdateOrInsertOpenSerialPortOptionsError>;
 
-    async fn insert_packet_returning_id(
+    /// Returns the options the serial port was last opened with, if it was ever opened.
+    async fn get_open_serial_port_options(
         &self,
-        port_id: i32,
-        tag: String,
-        packet: CorePacket,
-    ) -> Result<i32, InsertPacketError>;
+        name: &str,
+    ) -> Result<Option<AppOpenSerialPortOptions>, GetOpenSerialPortOptionsError>;
+
+    /// Returns the options every serial port was last opened with, by the name of the serial port.
+    async fn get_all_open_serial_port_options(
+        &self,
+    ) -> Result<HashMap<String, AppOpenSerialPortOptions>, GetOpenSerialPortOptionsError>;
+
+    /// Inserts the packets with the serial port id and the tag in a single transaction.
+    async fn insert_packets(
+        &self,
+        packets: Vec<(i32, String, CorePacket)>,
+    ) -> Result<(), InsertPacketsError>;
+
+    /// Returns a page of the packets matching the `query`, newest first.
+    async fn get_packets(&self, query: AppPacketQuery) -> Result<AppPacketPage, GetPacketsError>;
+
+    /// Returns the id and the macro, ordered by name.
+    async fn get_command_macros(
+        &self,
+    ) -> Result<Vec<(i32, AppCommandMacro)>, GetCommandMacrosError>;
+
+    async fn get_command_macro(
+        &self,
+        id: i32,
+    ) -> Result<Option<AppCommandMacro>, GetCommandMacroError>;
+
+    async fn insert_command_macro_returning_id(
+        &self,
+        command_macro: AppCommandMacro,
+    ) -> Result<i32, InsertCommandMacroError>;
+
+    /// Returns `false` if the macro does not exist.
+    async fn update_command_macro(
+        &self,
+        id: i32,
+        command_macro: AppCommandMacro,
+    ) -> Result<bool, UpdateCommandMacroError>;
+
+    /// Returns `false` if the macro does not exist.
+    async fn delete_command_macro(&self, id: i32) -> Result<bool, DeleteCommandMacroError>;
+
+    /// Returns the id and the script, ordered by name.
+    async fn get_scripts(&self) -> Result<Vec<(i32, AppScript)>, GetScriptsError>;
+
+    async fn get_script(&self, id: i32) -> Result<Option<AppScript>, GetScriptError>;
+
+    async fn insert_script_returning_id(&self, script: AppScript)
+        -> Result<i32, InsertScriptError>;
+
+    /// Returns `false` if the script does not exist.
+    async fn update_script(&self, id: i32, script: AppScript) -> Result<bool, UpdateScriptError>;
+
+    /// Returns `false` if the script does not exist.
+    async fn delete_script(&self, id: i32) -> Result<bool, DeleteScriptError>;
+
+    /// Returns the id and the port group, ordered by name.
+    async fn get_port_groups(&self) -> Result<Vec<(i32, AppPortGroup)>, GetPortGroupsError>;
+
+    async fn get_port_group(&self, id: i32) -> Result<Option<AppPortGroup>, GetPortGroupError>;
+
+    async fn insert_port_group_returning_id(
+        &self,
+        port_group: AppPortGroup,
+    ) -> Result<i32, InsertPortGroupError>;
+
+    /// Replaces the name and the ports of the group.
+    ///
+    /// Returns `false` if the port group does not exist.
+    async fn update_port_group(
+        &self,
+        id: i32,
+        port_group: AppPortGroup,
+    ) -> Result<bool, UpdatePortGroupError>;
+
+    /// Returns `false` if the port group does not exist.
+    async fn delete_port_group(&self, id: i32) -> Result<bool, DeletePortGroupError>;
+
+    /// Returns the id and the auto-responder, ordered by name.
+    async fn get_auto_responders(
+        &self,
+    ) -> Result<Vec<(i32, AppAutoResponder)>, GetAutoRespondersError>;
+
+    async fn insert_auto_responder_returning_id(
+        &self,
+        auto_responder: AppAutoResponder,
+    ) -> Result<i32, InsertAutoResponderError>;
+
+    /// Returns `false` if the auto-responder does not exist.
+    async fn update_auto_responder(
+        &self,
+        id: i32,
+        auto_responder: AppAutoResponder,
+    ) -> Result<bool, UpdateAutoResponderError>;
+
+    /// Returns `false` if the auto-responder does not exist.
+    async fn update_auto_responder_enabled(
+        &self,
+        id: i32,
+        enabled: bool,
+    ) -> Result<bool, UpdateAutoResponderError>;
+
+    /// Returns `false` if the auto-responder does not exist.
+    async fn delete_auto_responder(&self, id: i32) -> Result<bool, DeleteAutoResponderError>;
+
+    /// Ordered by the master and then by the subscriber.
+    async fn get_subscriptions(&self) -> Result<Vec<AppSubscription>, GetSubscriptionsError>;
+
+    /// Returns the subscription of `to` to `from`.
+    async fn get_subscription(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<AppSubscription>, GetSubscriptionError>;
+
+    /// Replaces the filter if the subscription already exists.
+    async fn upsert_subscription(
+        &self,
+        subscription: AppSubscription,
+    ) -> Result<(), UpsertSubscriptionError>;
+
+    /// Returns `false` if the subscription does not exist.
+    async fn delete_subscription(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<bool, DeleteSubscriptionError>;
+
+    /// Ordered by the serial port and then by the tag, global policies first.
+    async fn get_retention_policies(
+        &self,
+    ) -> Result<Vec<AppRetentionPolicy>, GetRetentionPoliciesError>;
+
+    /// Replaces the limits if a policy with the same scope already exists.
+    async fn upsert_retention_policy(
+        &self,
+        policy: AppRetentionPolicy,
+    ) -> Result<(), UpsertRetentionPolicyError>;
+
+    /// Returns `false` if the policy does not exist.
+    async fn delete_retention_policy(
+        &self,
+        scope: &AppRetentionScope,
+    ) -> Result<bool, DeleteRetentionPolicyError>;
+
+    /// Deletes the packets in the scope of the `policy` exceeding its limits.
+    ///
+    /// Returns the number of deleted packets.
+    async fn prune_packets(&self, policy: &AppRetentionPolicy) -> Result<u64, PrunePacketsError>;
+
+    /// Rebuilds the database, returning the space of deleted rows to the file system.
+    async fn vacuum(&self) -> Result<(), VacuumError>;
+
+    async fn get_database_stats(&self) -> Result<AppDatabaseStats, GetDatabaseStatsError>;
 }
@@ -35,7 +35,193 @@ pub enum UpdateOrInsertOpenSerialPortOptionsError {
 }
 
 #[derive(Debug, thiserror::Error)]
-pub enum InsertPacketError {
-    #[error("Failed to insert packet: {0}")]
+pub enum GetOpenSerialPortOptionsError {
+    #[error("Failed to get open serial port options: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InsertPacketsError {
+    #[error("Failed to insert packets: {0}")]
+    Insert(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetPacketsError {
+    #[error("Failed to get packets: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetCommandMacrosError {
+    #[error("Failed to get command macros: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetCommandMacroError {
+    #[error("Failed to get command macro: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InsertCommandMacroError {
+    #[error("Failed to insert command macro: {0}")]
+    Insert(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateCommandMacroError {
+    #[error("Failed to update command macro: {0}")]
+    Update(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteCommandMacroError {
+    #[error("Failed to delete command macro: {0}")]
+    Delete(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetScriptsError {
+    #[error("Failed to get scripts: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetScriptError {
+    #[error("Failed to get script: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InsertScriptError {
+    #[error("Failed to insert script: {0}")]
+    Insert(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateScriptError {
+    #[error("Failed to update script: {0}")]
+    Update(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteScriptError {
+    #[error("Failed to delete script: {0}")]
+    Delete(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetPortGroupsError {
+    #[error("Failed to get port groups: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetPortGroupError {
+    #[error("Failed to get port group: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InsertPortGroupError {
+    #[error("Failed to insert port group: {0}")]
     Insert(#[source] anyhow::Error),
 }
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdatePortGroupError {
+    #[error("Failed to update port group: {0}")]
+    Update(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeletePortGroupError {
+    #[error("Failed to delete port group: {0}")]
+    Delete(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetAutoRespondersError {
+    #[error("Failed to get auto-responders: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum InsertAutoResponderError {
+    #[error("Failed to insert auto-responder: {0}")]
+    Insert(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpdateAutoResponderError {
+    #[error("Failed to update auto-responder: {0}")]
+    Update(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteAutoResponderError {
+    #[error("Failed to delete auto-responder: {0}")]
+    Delete(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetSubscriptionsError {
+    #[error("Failed to get subscriptions: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetSubscriptionError {
+    #[error("Failed to get subscription: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpsertSubscriptionError {
+    #[error("Failed to save subscription: {0}")]
+    Upsert(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteSubscriptionError {
+    #[error("Failed to delete subscription: {0}")]
+    Delete(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetRetentionPoliciesError {
+    #[error("Failed to get retention policies: {0}")]
+    Get(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum UpsertRetentionPolicyError {
+    #[error("Failed to save retention policy: {0}")]
+    Upsert(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum DeleteRetentionPolicyError {
+    #[error("Failed to delete retention policy: {0}")]
+    Delete(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PrunePacketsError {
+    #[error("Failed to prune packets: {0}")]
+    Prune(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum VacuumError {
+    #[error("Failed to vacuum database: {0}")]
+    Vacuum(#[source] anyhow::Error),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetDatabaseStatsError {
+    #[error("Failed to get database stats: {0}")]
+    Get(#[source] anyhow::Error),
+}
@@ -1,9 +1,20 @@
+use std::collections::HashMap;
+
 use crate::app::{
     database::{
         database_impl::sqlite_database_service::SqliteDatabase, database_service::DatabaseService,
         error::*, model::UpdateOrInsert,
     },
-    model::managed_serial_port::AppOpenSerialPortOptions,
+    model::{
+        auto_responder::AppAutoResponder,
+        command_macro::AppCommandMacro,
+        managed_serial_port::AppOpenSerialPortOptions,
+        packet::{AppPacketPage, AppPacketQuery},
+        port_group::AppPortGroup,
+        retention::{AppDatabaseStats, AppRetentionPolicy, AppRetentionScope},
+        script::AppScript,
+        subscription::AppSubscription,
+    },
     serial_state::model::CorePacket,
 };
 
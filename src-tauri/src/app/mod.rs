@@ -2,5 +2,7 @@
 
 pub mod database;
 pub mod model;
+pub mod packet_writer;
+pub mod script;
 pub mod serial_state;
 pub mod state;
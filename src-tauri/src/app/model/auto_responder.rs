@@ -0,0 +1,42 @@
+use std::time::Duration;
+
+use crate::app::{
+    serial_state::{input::CoreInputMode, model::CoreAutoResponder},
+    state::error::AppAutoResponderParseError,
+};
+
+/// Replies to incoming lines of a serial port matching a pattern.
+#[derive(Debug, Clone)]
+pub struct AppAutoResponder {
+    /// Unique.
+    pub name: String,
+    /// The serial port whose incoming lines are matched.
+    pub port: String,
+    /// Regular expression matched against every incoming line.
+    pub pattern: String,
+    /// Converted to bytes according to the [`AppAutoResponder::input_mode`].
+    ///
+    /// Capture groups of the [`AppAutoResponder::pattern`] are substituted, e.g. `$1` or `${name}`.
+    pub response: String,
+    pub input_mode: CoreInputMode,
+    /// The serial port the response is sent to. [`AppAutoResponder::port`] if `None`.
+    pub target: Option<String>,
+    /// Waited before sending the response.
+    pub delay: Duration,
+    pub enabled: bool,
+}
+
+impl AppAutoResponder {
+    /// Compiles the pattern and parses the response.
+    pub fn to_core(&self) -> Result<CoreAutoResponder, AppAutoResponderParseError> {
+        Ok(CoreAutoResponder {
+            name: self.name.clone(),
+            port: self.port.clone(),
+            pattern: regex::bytes::Regex::new(&self.pattern)?,
+            response: self.input_mode.parse(&self.response)?,
+            target: self.target.clone(),
+            delay: self.delay,
+            enabled: self.enabled,
+        })
+    }
+}
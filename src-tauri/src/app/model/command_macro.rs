@@ -0,0 +1,48 @@
+use crate::app::serial_state::{input::CoreInputMode, model::CoreLineEnding};
+
+/// Where a macro is sent if it is executed without explicit targets.
+#[derive(Debug, Default, Clone)]
+pub enum AppCommandMacroTarget {
+    /// The macro must be executed with explicit targets.
+    #[default]
+    None,
+    /// The open serial port with the given name.
+    Port(String),
+    /// All open serial ports opened with the given tag.
+    Tag(String),
+}
+
+/// A named payload that can be sent to serial ports repeatedly.
+#[derive(Debug, Clone)]
+pub struct AppCommandMacro {
+    /// Unique.
+    pub name: String,
+    /// Converted to bytes according to the [`AppCommandMacro::input_mode`].
+    pub payload: String,
+    pub input_mode: CoreInputMode,
+    /// Overrides the outgoing line ending of the serial port if set.
+    pub line_ending: Option<CoreLineEnding>,
+    pub target: AppCommandMacroTarget,
+}
+
+/// The serial ports a macro is sent to when executed.
+#[derive(Debug, Default, Clone)]
+pub enum AppCommandMacroExecutionTarget {
+    /// The [`AppCommandMacro::target`] of the macro.
+    #[default]
+    Default,
+    /// The open serial ports with the given names.
+    Ports(Vec<String>),
+    AllOpen,
+}
+
+/// Result of executing a macro.
+///
+/// The macro is sent to every port, even if sending fails for some of them.
+#[derive(Debug, Default)]
+pub struct AppCommandMacroOutcome {
+    /// The names of the serial ports the macro was sent to.
+    pub succeeded: Vec<String>,
+    /// The names of the serial ports sending failed for, with the error.
+    pub failed: Vec<(String, String)>,
+}
@@ -1 +1,8 @@
+pub mod auto_responder;
+pub mod command_macro;
 pub mod managed_serial_port;
+pub mod packet;
+pub mod port_group;
+pub mod retention;
+pub mod script;
+pub mod subscription;
@@ -0,0 +1,92 @@
+use crate::app::serial_state::model::CorePacket;
+
+/// See [`CorePacketDirection`](crate::app::serial_state::model::CorePacketDirection).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppPacketDirectionFilter {
+    Incoming,
+    Outgoing,
+}
+
+/// See [`CorePacketOrigin`](crate::app::serial_state::model::CorePacketOrigin).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AppPacketOriginFilter {
+    Direct,
+    Broadcast,
+    Subscription,
+    File,
+    Transfer,
+    Periodic,
+    Macro,
+    Group,
+    AutoResponder,
+}
+
+/// Selects stored packets, newest first. Every filter that is set must match.
+#[derive(Debug, Clone)]
+pub struct AppPacketQuery {
+    /// Name of the serial port.
+    pub port: Option<String>,
+    pub tag: Option<String>,
+    pub direction: Option<AppPacketDirectionFilter>,
+    /// Only outgoing packets have an origin.
+    pub origin: Option<AppPacketOriginFilter>,
+    /// Inclusive. Compared to the timestamp of the last byte.
+    pub from_timestamp_micros: Option<u64>,
+    /// Exclusive. Compared to the timestamp of the last byte.
+    pub to_timestamp_micros: Option<u64>,
+    /// [`AppPacketPage::next_cursor`] of the previous page. The first page is returned if not set.
+    pub cursor: Option<i32>,
+    /// Maximum number of packets in the page. Clamped to [`AppPacketQuery::MAX_LIMIT`].
+    pub limit: u64,
+}
+
+impl AppPacketQuery {
+    pub const DEFAULT_LIMIT: u64 = 100;
+    pub const MAX_LIMIT: u64 = 1000;
+}
+
+impl Default for AppPacketQuery {
+    fn default() -> Self {
+        Self {
+            port: None,
+            tag: None,
+            direction: None,
+            origin: None,
+            from_timestamp_micros: None,
+            to_timestamp_micros: None,
+            cursor: None,
+            limit: Self::DEFAULT_LIMIT,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct AppStoredPacket {
+    pub id: i32,
+    /// Tag of the open options the serial port was open with.
+    pub tag: String,
+    pub packet: CorePacket,
+}
+
+/// See [`AppPacketWriter`](crate::app::packet_writer::AppPacketWriter).
+#[derive(Debug, Clone, Default)]
+pub struct AppPacketWriterMetrics {
+    /// Packets waiting to be written.
+    pub backlog: u64,
+    /// Packets written since the application started.
+    pub written: u64,
+    /// Failed attempts to write a packet since the application started, including the retried ones.
+    pub failed: u64,
+    /// Packets dropped after running out of [`AppPacketWriterOptions::max_attempts`](crate::app::packet_writer::AppPacketWriterOptions::max_attempts)
+    /// since the application started. They are not saved.
+    pub dropped: u64,
+    /// Error of the last batch that could not be written.
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AppPacketPage {
+    pub packets: Vec<AppStoredPacket>,
+    /// Pass to [`AppPacketQuery::cursor`] to get the next page. `None` if this is the last page.
+    pub next_cursor: Option<i32>,
+}
@@ -0,0 +1,28 @@
+/// A named set of serial ports operated on together.
+#[derive(Debug, Clone)]
+pub struct AppPortGroup {
+    /// Unique.
+    pub name: String,
+    /// Names of the serial ports. The ports do not have to be open or even available.
+    pub ports: Vec<String>,
+}
+
+/// Result of an operation applied to every serial port of a group.
+///
+/// The operation is applied to every port, even if it fails for some of them.
+#[derive(Debug)]
+pub struct AppPortGroupOutcome<T> {
+    /// The names of the serial ports the operation succeeded for, with its output.
+    pub succeeded: Vec<(String, T)>,
+    /// The names of the serial ports the operation failed for, with the error.
+    pub failed: Vec<(String, String)>,
+}
+
+impl<T> Default for AppPortGroupOutcome<T> {
+    fn default() -> Self {
+        Self {
+            succeeded: Vec::new(),
+            failed: Vec::new(),
+        }
+    }
+}
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+/// The stored packets a retention policy applies to.
+///
+/// `None` matches every serial port or every tag.
+/// A scope without a port and a tag applies to all packets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AppRetentionScope {
+    /// Name of the serial port.
+    pub port: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// Limits of the stored packets in a scope.
+///
+/// The oldest packets exceeding any of the limits are deleted. `None` means no limit.
+#[derive(Debug, Clone, Default)]
+pub struct AppRetentionPolicy {
+    pub scope: AppRetentionScope,
+    pub max_age: Option<Duration>,
+    pub max_rows: Option<u64>,
+    /// Counts the data of the packets only.
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct AppPruneReport {
+    pub deleted_packets: u64,
+    /// Scopes of the policies that could not be applied. The other policies are applied anyway.
+    pub failed_scopes: Vec<AppRetentionScope>,
+    /// Whether the space of the deleted packets was returned to the file system.
+    pub vacuumed: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct AppPortPacketStats {
+    /// Name of the serial port.
+    pub port: String,
+    pub packets: u64,
+    /// Data of the packets.
+    pub bytes: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct AppDatabaseStats {
+    /// Size of the database file.
+    pub size_bytes: u64,
+    /// Space of deleted rows, reused for new rows before the file grows.
+    pub free_bytes: u64,
+    /// Every serial port that was ever opened, ordered by name.
+    pub ports: Vec<AppPortPacketStats>,
+}
@@ -0,0 +1,28 @@
+/// A named Rhai script automating serial ports.
+#[derive(Debug, Clone)]
+pub struct AppScript {
+    /// Unique.
+    pub name: String,
+    pub source: String,
+}
+
+/// A running [`AppScript`].
+#[derive(Debug, Clone)]
+pub struct AppScriptRun {
+    /// Unique for the lifetime of the application.
+    pub id: u64,
+    pub script_id: i32,
+    pub script_name: String,
+}
+
+/// Emitted while an [`AppScriptRun`] is running.
+///
+/// [`AppScriptEvent::Finished`], [`AppScriptEvent::Cancelled`] and [`AppScriptEvent::Failed`] are emitted last.
+#[derive(Debug, Clone)]
+pub enum AppScriptEvent {
+    /// Printed or logged by the script.
+    Output(String),
+    Finished,
+    Cancelled,
+    Failed(String),
+}
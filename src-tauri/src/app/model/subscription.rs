@@ -0,0 +1,103 @@
+use std::time::Duration;
+
+use tokio_util::bytes::Bytes;
+
+use crate::app::serial_state::model::CoreSubscriptionMode;
+#[cfg(feature = "subscriptions")]
+use crate::app::serial_state::model::{
+    CoreRateLimitOverflow, CoreSubscriptionFilter, CoreSubscriptionRateLimit,
+};
+
+/// See [`CoreSubscriptionFilter`](crate::app::serial_state::model::CoreSubscriptionFilter).
+#[derive(Debug, Clone)]
+pub enum AppSubscriptionFilter {
+    /// Regular expression matched against the lines.
+    Regex(String),
+    /// Bytes the lines start with.
+    Prefix(Bytes),
+}
+
+#[cfg(feature = "subscriptions")]
+impl AppSubscriptionFilter {
+    /// Compiles the regular expression.
+    pub fn to_core(&self) -> Result<CoreSubscriptionFilter, regex::Error> {
+        match self {
+            Self::Regex(pattern) => Ok(CoreSubscriptionFilter::Regex(regex::bytes::Regex::new(
+                pattern,
+            )?)),
+            Self::Prefix(prefix) => Ok(CoreSubscriptionFilter::Prefix(prefix.clone())),
+        }
+    }
+}
+
+/// See [`CoreRateLimitOverflow`](crate::app::serial_state::model::CoreRateLimitOverflow).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum AppRateLimitOverflow {
+    #[default]
+    Drop,
+    Buffer,
+}
+
+/// See [`CoreSubscriptionRateLimit`](crate::app::serial_state::model::CoreSubscriptionRateLimit).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AppSubscriptionRateLimit {
+    /// `0` means unlimited.
+    pub bytes_per_second: u64,
+    /// `0` means unlimited.
+    pub frames_per_second: u64,
+    pub overflow: AppRateLimitOverflow,
+    pub latency: Duration,
+    pub capacity: usize,
+}
+
+impl Default for AppSubscriptionRateLimit {
+    fn default() -> Self {
+        Self {
+            bytes_per_second: 0,
+            frames_per_second: 0,
+            overflow: Default::default(),
+            latency: Duration::ZERO,
+            capacity: 1024,
+        }
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+impl From<AppSubscriptionRateLimit> for CoreSubscriptionRateLimit {
+    fn from(value: AppSubscriptionRateLimit) -> Self {
+        Self {
+            bytes_per_second: value.bytes_per_second,
+            frames_per_second: value.frames_per_second,
+            overflow: match value.overflow {
+                AppRateLimitOverflow::Drop => CoreRateLimitOverflow::Drop,
+                AppRateLimitOverflow::Buffer => CoreRateLimitOverflow::Buffer,
+            },
+            latency: value.latency,
+            capacity: value.capacity,
+        }
+    }
+}
+
+/// `to` receives the data read from `from`.
+#[derive(Debug, Clone)]
+pub struct AppSubscription {
+    /// Name of the master serial port.
+    pub from: String,
+    /// Name of the subscriber serial port.
+    pub to: String,
+    /// Requires [`CoreSubscriptionMode::Lines`]. See [`AppSubscription::is_filter_applicable`].
+    pub filter: Option<AppSubscriptionFilter>,
+    pub mode: CoreSubscriptionMode,
+    pub rate_limit: AppSubscriptionRateLimit,
+    /// See [`CoreSubscription::echo_guard`](crate::app::serial_state::model::CoreSubscription::echo_guard).
+    pub echo_guard: bool,
+}
+
+#[cfg(feature = "subscriptions")]
+impl AppSubscription {
+    /// A filter matches lines, so it is only applied with [`CoreSubscriptionMode::Lines`].
+    /// Raw chunks are never filtered, nor turned into lines.
+    pub fn is_filter_applicable(&self) -> bool {
+        self.filter.is_none() || matches!(self.mode, CoreSubscriptionMode::Lines(_))
+    }
+}
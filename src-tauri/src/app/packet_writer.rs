@@ -0,0 +1,520 @@
+//! Write-behind persistence of packets.
+//!
+//! Packets are collected and written in batches, each in a single transaction, instead of one insert per packet.
+//! A batch is written once it is full, after [`AppPacketWriterOptions::flush_interval`] or on [`AppPacketWriter::flush`].
+//! A failed batch is kept and retried after the next interval, until its packets run out of [`AppPacketWriterOptions::max_attempts`] and are dropped.
+//! See [`AppPacketWriterMetrics`].
+
+use std::{
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex,
+    },
+    time::Duration,
+};
+
+use tokio::sync::{
+    mpsc::{
+        unbounded_channel, UnboundedReceiver as MPSCUnboundedReceiver,
+        UnboundedSender as MPSCUnboundedSender,
+    },
+    oneshot::{channel as oneshot_channel, Sender as OneshotSender},
+};
+
+use super::{
+    database::{database_service::DatabaseService, error::InsertPacketsError, Database},
+    model::packet::AppPacketWriterMetrics,
+    serial_state::model::CorePacket,
+    state::error::AppPacketError,
+};
+
+/// Receives the error if the batch containing a packet could not be written.
+pub type AppPacketErrorSender = MPSCUnboundedSender<Result<CorePacket, AppPacketError>>;
+
+#[derive(Debug, Clone, Copy)]
+pub struct AppPacketWriterOptions {
+    /// A batch is written once it contains this many packets.
+    pub max_batch_size: usize,
+    /// A non-empty batch is written at least this often.
+    pub flush_interval: Duration,
+    /// How often a packet is tried to be written before it is dropped. It is tried at least once.
+    pub max_attempts: u32,
+}
+
+impl Default for AppPacketWriterOptions {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 256,
+            flush_interval: Duration::from_millis(250),
+            max_attempts: 3,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct PendingPacket {
+    port_id: i32,
+    tag: String,
+    packet: CorePacket,
+    error_tx: AppPacketErrorSender,
+    /// Failed writes so far.
+    attempts: u32,
+}
+
+#[derive(Debug)]
+enum Command {
+    Write(PendingPacket),
+    /// Writes the current batch and notifies the sender.
+    Flush(OneshotSender<()>),
+}
+
+#[derive(Debug, Default)]
+struct Metrics {
+    backlog: AtomicU64,
+    written: AtomicU64,
+    failed: AtomicU64,
+    dropped: AtomicU64,
+    last_error: Mutex<Option<String>>,
+}
+
+/// Cloning the writer is cheap. Clones share the same batch.
+#[derive(Debug, Clone)]
+pub struct AppPacketWriter {
+    db: Database,
+    options: AppPacketWriterOptions,
+    /// Set when the write task is spawned on first use, since the writer may be created outside of a runtime.
+    tx: Arc<Mutex<Option<MPSCUnboundedSender<Command>>>>,
+    metrics: Arc<Metrics>,
+}
+
+impl AppPacketWriter {
+    pub fn new(db: Database, options: AppPacketWriterOptions) -> Self {
+        Self {
+            db,
+            options,
+            tx: Default::default(),
+            metrics: Default::default(),
+        }
+    }
+
+    /// Spawns the write task if it is not running yet. Must be called within a tokio runtime.
+    fn tx(&self) -> MPSCUnboundedSender<Command> {
+        self.tx
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_or_insert_with(|| {
+                let (tx, rx) = unbounded_channel();
+
+                tokio::spawn(write_task(
+                    self.db.clone(),
+                    self.options,
+                    self.metrics.clone(),
+                    rx,
+                ));
+
+                tx
+            })
+            .clone()
+    }
+
+    /// Queues the `packet` of the serial port with `port_id`, open with `tag`.
+    ///
+    /// `error_tx` receives the error if the packet could not be written.
+    pub fn write(
+        &self,
+        port_id: i32,
+        tag: String,
+        packet: CorePacket,
+        error_tx: AppPacketErrorSender,
+    ) {
+        self.metrics.backlog.fetch_add(1, Ordering::Relaxed);
+
+        let pending = PendingPacket {
+            port_id,
+            tag,
+            packet,
+            error_tx,
+            attempts: 0,
+        };
+
+        if self.tx().send(Command::Write(pending)).is_err() {
+            // The task never stops while the writer exists.
+            self.metrics.backlog.fetch_sub(1, Ordering::Relaxed);
+            self.metrics.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Waits until every packet queued before is written or has failed, retrying failed batches without waiting.
+    pub async fn flush(&self) {
+        let (done_tx, done_rx) = oneshot_channel();
+
+        if self.tx().send(Command::Flush(done_tx)).is_ok() {
+            let _ = done_rx.await;
+        }
+    }
+
+    pub fn metrics(&self) -> AppPacketWriterMetrics {
+        AppPacketWriterMetrics {
+            backlog: self.metrics.backlog.load(Ordering::Relaxed),
+            written: self.metrics.written.load(Ordering::Relaxed),
+            failed: self.metrics.failed.load(Ordering::Relaxed),
+            dropped: self.metrics.dropped.load(Ordering::Relaxed),
+            last_error: self
+                .metrics
+                .last_error
+                .lock()
+                .unwrap_or_else(|poisoned| poisoned.into_inner())
+                .clone(),
+        }
+    }
+}
+
+async fn write_task(
+    db: Database,
+    options: AppPacketWriterOptions,
+    metrics: Arc<Metrics>,
+    mut rx: MPSCUnboundedReceiver<Command>,
+) {
+    tracing::debug!(?options, "Packet writer started");
+
+    let mut batch = Vec::with_capacity(options.max_batch_size);
+    // Set while the batch contains packets of a failed write. They are retried on the next tick, not as soon as the batch is full.
+    let mut retrying = false;
+
+    // The first tick is after the interval, not immediately.
+    let mut interval = tokio::time::interval_at(
+        tokio::time::Instant::now() + options.flush_interval,
+        options.flush_interval,
+    );
+    interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        tokio::select! {
+            command = rx.recv() => match command {
+                Some(Command::Write(pending)) => {
+                    batch.push(pending);
+
+                    if !retrying && batch.len() >= options.max_batch_size {
+                        retrying = !write_batch(&db, &options, &metrics, &mut batch).await;
+                    }
+                }
+                Some(Command::Flush(done_tx)) => {
+                    write_all(&db, &options, &metrics, &mut batch).await;
+                    retrying = false;
+
+                    let _ = done_tx.send(());
+                }
+                None => {
+                    write_all(&db, &options, &metrics, &mut batch).await;
+
+                    break;
+                }
+            },
+            _ = interval.tick() => {
+                retrying = !write_batch(&db, &options, &metrics, &mut batch).await;
+            }
+        }
+    }
+
+    tracing::debug!("Packet writer terminated");
+}
+
+/// Writes the batch until it is empty. Every failed write uses up an attempt of every packet, so this ends.
+async fn write_all(
+    db: &Database,
+    options: &AppPacketWriterOptions,
+    metrics: &Metrics,
+    batch: &mut Vec<PendingPacket>,
+) {
+    while !batch.is_empty() {
+        write_batch(db, options, metrics, batch).await;
+    }
+}
+
+/// Returns `false` if the write failed.
+///
+/// The packets of a failed write stay in the batch, unless they ran out of [`AppPacketWriterOptions::max_attempts`].
+async fn write_batch(
+    db: &Database,
+    options: &AppPacketWriterOptions,
+    metrics: &Metrics,
+    batch: &mut Vec<PendingPacket>,
+) -> bool {
+    if batch.is_empty() {
+        return true;
+    }
+
+    // Cloned, since the batch is kept if the write fails.
+    let packets = batch
+        .iter()
+        .map(|pending| (pending.port_id, pending.tag.clone(), pending.packet.clone()))
+        .collect::<Vec<_>>();
+
+    let err = match db.insert_packets(packets).await {
+        Ok(()) => {
+            let count = batch.len() as u64;
+
+            tracing::debug!(count, "Packets saved");
+
+            metrics.backlog.fetch_sub(count, Ordering::Relaxed);
+            metrics.written.fetch_add(count, Ordering::Relaxed);
+
+            batch.clear();
+
+            return true;
+        }
+        Err(err) => err,
+    };
+
+    *metrics
+        .last_error
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner()) = Some(err.to_string());
+
+    metrics
+        .failed
+        .fetch_add(batch.len() as u64, Ordering::Relaxed);
+
+    let (retried, dropped): (Vec<_>, Vec<_>) = batch
+        .drain(..)
+        .partition(|pending| pending.attempts + 1 < options.max_attempts);
+
+    *batch = retried;
+
+    for pending in batch.iter_mut() {
+        pending.attempts += 1;
+    }
+
+    tracing::error!(%err, retried = batch.len(), dropped = dropped.len(), "Error saving packets");
+
+    if !dropped.is_empty() {
+        let count = dropped.len() as u64;
+
+        metrics.backlog.fetch_sub(count, Ordering::Relaxed);
+        metrics.dropped.fetch_add(count, Ordering::Relaxed);
+
+        let mut error_txs = Vec::<AppPacketErrorSender>::new();
+
+        for pending in dropped {
+            if !error_txs
+                .iter()
+                .any(|error_tx| error_tx.same_channel(&pending.error_tx))
+            {
+                error_txs.push(pending.error_tx);
+            }
+        }
+
+        notify_failure(error_txs, err);
+    }
+
+    false
+}
+
+/// Every serial port with a dropped packet receives the error once.
+fn notify_failure(error_txs: Vec<AppPacketErrorSender>, err: InsertPacketsError) {
+    let err = Arc::new(err);
+
+    for error_tx in error_txs {
+        let _ = error_tx.send(Err(AppPacketError::SavePacketError(err.clone())));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio_util::bytes::Bytes;
+
+    use crate::app::{
+        database::database_impl::sqlite_database_service::SqliteDatabase,
+        serial_state::{
+            clock,
+            model::{CoreIncomingPacket, CorePacketDirection},
+        },
+    };
+
+    use super::*;
+
+    async fn database() -> Database {
+        let sqlite = SqliteDatabase::new("sqlite::memory:").await.unwrap();
+        sqlite.migrate().await.unwrap();
+
+        Database::from(sqlite)
+    }
+
+    fn packet() -> CorePacket {
+        let timestamp_micros = clock::now_micros();
+
+        CorePacket::new(
+            CorePacketDirection::Incoming(CoreIncomingPacket {
+                line: Bytes::from_static(b"line"),
+            }),
+            String::from("COM1"),
+            timestamp_micros,
+            timestamp_micros,
+        )
+    }
+
+    fn write(writer: &AppPacketWriter, port_id: i32, count: usize) {
+        let (error_tx, _) = unbounded_channel();
+
+        for _ in 0..count {
+            writer.write(port_id, String::from("tag"), packet(), error_tx.clone());
+        }
+    }
+
+    async fn wait_until(metrics: &Metrics, condition: impl Fn(&Metrics) -> bool) {
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while !condition(metrics) {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("Condition not met in time");
+    }
+
+    fn options(max_batch_size: usize, flush_interval: Duration) -> AppPacketWriterOptions {
+        AppPacketWriterOptions {
+            max_batch_size,
+            flush_interval,
+            ..Default::default()
+        }
+    }
+
+    const NEVER: Duration = Duration::from_secs(3600);
+
+    #[tokio::test]
+    async fn writes_full_batches() {
+        let db = database().await;
+        let port_id = db
+            .get_serial_port_id_or_insert_returning_id("COM1")
+            .await
+            .unwrap();
+        let writer = AppPacketWriter::new(db, options(3, NEVER));
+
+        write(&writer, port_id, 7);
+
+        wait_until(&writer.metrics, |metrics| {
+            metrics.written.load(Ordering::Relaxed) == 6
+        })
+        .await;
+        assert_eq!(writer.metrics().backlog, 1);
+    }
+
+    #[tokio::test]
+    async fn writes_after_flush_interval() {
+        let db = database().await;
+        let port_id = db
+            .get_serial_port_id_or_insert_returning_id("COM1")
+            .await
+            .unwrap();
+        let writer = AppPacketWriter::new(db, options(100, Duration::from_millis(50)));
+
+        write(&writer, port_id, 2);
+
+        wait_until(&writer.metrics, |metrics| {
+            metrics.written.load(Ordering::Relaxed) == 2
+        })
+        .await;
+        assert_eq!(writer.metrics().backlog, 0);
+    }
+
+    #[tokio::test]
+    async fn writes_on_close() {
+        let db = database().await;
+        let port_id = db
+            .get_serial_port_id_or_insert_returning_id("COM1")
+            .await
+            .unwrap();
+        let writer = AppPacketWriter::new(db, options(100, NEVER));
+
+        write(&writer, port_id, 2);
+
+        let metrics = writer.metrics.clone();
+        drop(writer);
+
+        wait_until(&metrics, |metrics| {
+            metrics.written.load(Ordering::Relaxed) == 2
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn retries_failed_batch() {
+        let db = database().await;
+        let writer = AppPacketWriter::new(db.clone(), options(1, NEVER));
+
+        // The serial port does not exist yet.
+        write(&writer, 1, 1);
+
+        wait_until(&writer.metrics, |metrics| {
+            metrics.last_error.lock().unwrap().is_some()
+        })
+        .await;
+        assert_eq!(writer.metrics().backlog, 1);
+        assert_eq!(writer.metrics().failed, 1);
+        assert_eq!(writer.metrics().dropped, 0);
+
+        let port_id = db
+            .get_serial_port_id_or_insert_returning_id("COM1")
+            .await
+            .unwrap();
+        assert_eq!(port_id, 1);
+
+        writer.flush().await;
+
+        let metrics = writer.metrics();
+        assert_eq!(metrics.written, 1);
+        assert_eq!(metrics.failed, 1);
+        assert_eq!(metrics.dropped, 0);
+        assert_eq!(metrics.backlog, 0);
+    }
+
+    #[tokio::test]
+    async fn counts_dropped_packets() {
+        let writer = AppPacketWriter::new(database().await, options(100, NEVER));
+
+        let (error_tx, mut error_rx) = unbounded_channel();
+
+        for _ in 0..2 {
+            writer.write(42, String::from("tag"), packet(), error_tx.clone());
+        }
+
+        writer.flush().await;
+
+        let metrics = writer.metrics();
+        assert_eq!(metrics.written, 0);
+        // Every attempt of both packets.
+        assert_eq!(metrics.failed, 6);
+        assert_eq!(metrics.dropped, 2);
+        assert_eq!(metrics.backlog, 0);
+        assert!(metrics.last_error.is_some());
+
+        // Once per serial port.
+        assert!(matches!(
+            error_rx.try_recv(),
+            Ok(Err(AppPacketError::SavePacketError(_)))
+        ));
+        assert!(error_rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn writes_out_of_range_timestamps() {
+        let db = database().await;
+        let port_id = db
+            .get_serial_port_id_or_insert_returning_id("COM1")
+            .await
+            .unwrap();
+        let writer = AppPacketWriter::new(db, options(100, NEVER));
+
+        let (error_tx, _) = unbounded_channel();
+        let mut out_of_range = packet();
+        out_of_range.last_byte_timestamp_micros = i64::MAX as u64;
+
+        // The rows of a batch must have the same columns.
+        writer.write(port_id, String::from("tag"), packet(), error_tx.clone());
+        writer.write(port_id, String::from("tag"), out_of_range, error_tx);
+
+        writer.flush().await;
+
+        assert_eq!(writer.metrics().written, 2);
+    }
+}
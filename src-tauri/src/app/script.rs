@@ -0,0 +1,371 @@
+//! Sandboxed [Rhai](https://rhai.rs) scripts automating serial ports.
+//!
+//! Scripts run on a blocking thread. Every function exposed to a script blocks on the [`AppState`] until it completes.
+//!
+//! ## Functions
+//!
+//! - `open(name)`: Opens the serial port with its last used open options.
+//! - `open(name, baud_rate)`: Same as `open(name)` with another baud rate.
+//! - `close(name)`
+//! - `send(name, value)`: Sends `value` as text and waits until it is written.
+//! - `send(name, value, input_mode)`: `input_mode` is one of `"text"`, `"escaped"` or `"hex"`.
+//! - `send_and_expect(name, value, pattern, timeout_millis)`: Sends `value` as text and waits for an incoming line matching the regex `pattern`.
+//!   Returns the line. Throws if no line matches in time.
+//! - `wait_for(name, pattern, timeout_millis)`: Waits for an incoming line matching the regex `pattern` and returns it.
+//!   Lines received since the script started or since the last matched line are considered.
+//!   Throws if no line matches in time.
+//! - `toggle_read_state(name)`
+//! - `sleep(millis)`
+//! - `open_ports()`: Names of the open serial ports.
+//! - `log(message)`: Same as `print(message)`.
+
+use std::{cell::RefCell, future::Future, rc::Rc, sync::Arc, time::Duration};
+
+use regex::bytes::Regex;
+use rhai::{Array, Dynamic, Engine, EvalAltResult, ParseError, Position};
+use tokio::{
+    runtime::Handle,
+    sync::{
+        broadcast::{error::RecvError, Receiver as BroadcastReceiver},
+        mpsc::{
+            UnboundedReceiver as MPSCUnboundedReceiver, UnboundedSender as MPSCUnboundedSender,
+        },
+    },
+};
+use tokio_util::sync::CancellationToken;
+
+use super::{
+    model::script::{AppScriptEvent, AppScriptRun},
+    serial_state::{
+        input::CoreInputMode,
+        model::{
+            CoreExpectPattern, CoreOutgoingPacket, CorePacket, CorePacketDirection,
+            CorePacketOrigin,
+        },
+    },
+    state::{error::AppPacketError, AppState},
+};
+
+/// Notified when a script changes the open serial ports.
+///
+/// Called from the blocking thread of the script, but within the runtime.
+pub trait AppScriptHooks: Send + Sync + 'static {
+    /// A script opened the serial port `name`.
+    ///
+    /// `rx` is the same channel returned by [`AppState::open_serial_port`] and should be consumed.
+    fn opened(&self, name: String, rx: MPSCUnboundedReceiver<Result<CorePacket, AppPacketError>>);
+
+    /// A script closed the serial port `name`.
+    fn closed(&self, name: String);
+}
+
+#[derive(Debug)]
+pub struct AppScriptRunHandle {
+    run: AppScriptRun,
+    cancellation_token: CancellationToken,
+}
+
+impl AppScriptRunHandle {
+    pub fn new(run: AppScriptRun, cancellation_token: CancellationToken) -> Self {
+        Self {
+            run,
+            cancellation_token,
+        }
+    }
+
+    pub fn run(&self) -> &AppScriptRun {
+        &self.run
+    }
+
+    pub fn cancelled(self) -> AppScriptRun {
+        self.cancellation_token.cancel();
+
+        self.run
+    }
+}
+
+/// Compiles the `source` without running it.
+pub fn check(source: &str) -> Result<(), ParseError> {
+    Engine::new_raw().compile(source).map(|_| ())
+}
+
+/// Runs the `source` to completion.
+///
+/// Must be called from a blocking thread within the runtime.
+///
+/// Returns the last event, which is not sent through `event_tx`.
+pub(super) fn run(
+    state: AppState,
+    source: String,
+    hooks: Arc<dyn AppScriptHooks>,
+    event_tx: MPSCUnboundedSender<AppScriptEvent>,
+    cancellation_token: CancellationToken,
+) -> AppScriptEvent {
+    let context = ScriptContext {
+        packets_rx: Rc::new(RefCell::new(state.serial_state().subscribe_packets())),
+        state,
+        runtime: Handle::current(),
+        hooks,
+        cancellation_token: cancellation_token.clone(),
+    };
+
+    let engine = engine(context, event_tx);
+
+    match engine.run(&source) {
+        Ok(()) => AppScriptEvent::Finished,
+        // Cancellation may surface as any error of the function the script was blocked on.
+        Err(_) if cancellation_token.is_cancelled() => AppScriptEvent::Cancelled,
+        Err(err) => AppScriptEvent::Failed(err.to_string()),
+    }
+}
+
+fn engine(context: ScriptContext, event_tx: MPSCUnboundedSender<AppScriptEvent>) -> Engine {
+    let mut engine = Engine::new();
+
+    // Scripts can not escape the sandbox, but `eval` would make them harder to reason about.
+    engine.disable_symbol("eval");
+
+    let progress_cancellation_token = context.cancellation_token.clone();
+    engine.on_progress(move |_| {
+        progress_cancellation_token
+            .is_cancelled()
+            .then_some(Dynamic::UNIT)
+    });
+
+    let print_event_tx = event_tx.clone();
+    engine.on_print(move |message| {
+        let _ = print_event_tx.send(AppScriptEvent::Output(message.to_string()));
+    });
+
+    let debug_event_tx = event_tx.clone();
+    engine.on_debug(move |message, _, position| {
+        let _ = debug_event_tx.send(AppScriptEvent::Output(format!("{position:?} | {message}")));
+    });
+
+    engine.register_fn("log", move |message: &str| {
+        let _ = event_tx.send(AppScriptEvent::Output(message.to_string()));
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("open", move |name: &str| ctx.open(name, None));
+
+    let ctx = context.clone();
+    engine.register_fn("open", move |name: &str, baud_rate: i64| {
+        ctx.open(name, Some(baud_rate))
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("close", move |name: &str| ctx.close(name));
+
+    let ctx = context.clone();
+    engine.register_fn("send", move |name: &str, value: &str| {
+        ctx.send(name, value, "text")
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("send", move |name: &str, value: &str, input_mode: &str| {
+        ctx.send(name, value, input_mode)
+    });
+
+    let ctx = context.clone();
+    engine.register_fn(
+        "send_and_expect",
+        move |name: &str, value: &str, pattern: &str, timeout_millis: i64| {
+            ctx.send_and_expect(name, value, pattern, timeout_millis)
+        },
+    );
+
+    let ctx = context.clone();
+    engine.register_fn(
+        "wait_for",
+        move |name: &str, pattern: &str, timeout_millis: i64| {
+            ctx.wait_for(name, pattern, timeout_millis)
+        },
+    );
+
+    let ctx = context.clone();
+    engine.register_fn("toggle_read_state", move |name: &str| {
+        ctx.toggle_read_state(name)
+    });
+
+    let ctx = context.clone();
+    engine.register_fn("sleep", move |millis: i64| ctx.sleep(millis));
+
+    let ctx = context;
+    engine.register_fn("open_ports", move || ctx.open_ports());
+
+    engine
+}
+
+type ScriptResult<T> = Result<T, Box<EvalAltResult>>;
+
+#[derive(Clone)]
+struct ScriptContext {
+    state: AppState,
+    runtime: Handle,
+    hooks: Arc<dyn AppScriptHooks>,
+    cancellation_token: CancellationToken,
+    /// Shared between calls to `wait_for`, so that lines received in between are not missed.
+    packets_rx: Rc<RefCell<BroadcastReceiver<CorePacket>>>,
+}
+
+impl ScriptContext {
+    /// Blocks on the `future` until it completes or the script is cancelled.
+    fn block_on<F: Future>(&self, future: F) -> ScriptResult<F::Output> {
+        self.runtime.block_on(async {
+            tokio::select! {
+                output = future => Ok(output),
+                _ = self.cancellation_token.cancelled() => {
+                    Err(EvalAltResult::ErrorTerminated(Dynamic::UNIT, Position::NONE).into())
+                }
+            }
+        })
+    }
+
+    fn open(&self, name: &str, baud_rate: Option<i64>) -> ScriptResult<()> {
+        let rx = self.block_on(async {
+            let mut options = self
+                .state
+                .get_open_serial_port_options(name)
+                .await
+                .map_err(|err| err.to_string())?;
+
+            if let Some(baud_rate) = baud_rate {
+                options.core_options.baud_rate = baud_rate
+                    .try_into()
+                    .map_err(|_| format!("Invalid baud rate: {baud_rate}"))?;
+            }
+
+            self.state
+                .open_serial_port(name, options)
+                .await
+                .map_err(|err| err.to_string())
+        })??;
+
+        self.hooks.opened(name.to_string(), rx);
+
+        Ok(())
+    }
+
+    fn close(&self, name: &str) -> ScriptResult<()> {
+        self.block_on(
+            self.state
+                .serial_state()
+                .remove_and_cancel_open_serial_port(name),
+        )?
+        .ok_or_else(|| format!("Port not open: {name}"))?;
+
+        self.hooks.closed(name.to_string());
+
+        Ok(())
+    }
+
+    fn send(&self, name: &str, value: &str, input_mode: &str) -> ScriptResult<()> {
+        let input_mode = match input_mode {
+            "text" => CoreInputMode::Text,
+            "escaped" => CoreInputMode::Escaped,
+            "hex" => CoreInputMode::Hex,
+            _ => return Err(format!("Unknown input mode: {input_mode}").into()),
+        };
+
+        let packet = CoreOutgoingPacket {
+            bytes: input_mode.parse(value).map_err(|err| err.to_string())?,
+            packet_origin: CorePacketOrigin::Direct,
+            ack: None,
+        };
+
+        self.block_on(
+            self.state
+                .serial_state()
+                .send_to_open_serial_port_acknowledged(name, packet),
+        )?
+        .map(|_| ())
+        .map_err(|err| format!("{name}: {err}").into())
+    }
+
+    fn send_and_expect(
+        &self,
+        name: &str,
+        value: &str,
+        pattern: &str,
+        timeout_millis: i64,
+    ) -> ScriptResult<String> {
+        let pattern = CoreExpectPattern::Regex(Regex::new(pattern).map_err(|err| err.to_string())?);
+        let timeout = Duration::from_millis(timeout_millis.max(0) as u64);
+
+        let packet = CoreOutgoingPacket {
+            bytes: value.to_string().into(),
+            packet_origin: CorePacketOrigin::Direct,
+            ack: None,
+        };
+
+        let packet = self
+            .block_on(
+                self.state
+                    .serial_state()
+                    .send_and_expect(name, packet, &pattern, timeout),
+            )?
+            .map_err(|err| err.to_string())?;
+
+        match packet.packet_direction {
+            CorePacketDirection::Incoming(incoming) => {
+                Ok(String::from_utf8_lossy(&incoming.line).into_owned())
+            }
+            CorePacketDirection::Outgoing(_) => unreachable!("Only incoming packets are matched"),
+        }
+    }
+
+    fn wait_for(&self, name: &str, pattern: &str, timeout_millis: i64) -> ScriptResult<String> {
+        let expected =
+            CoreExpectPattern::Regex(Regex::new(pattern).map_err(|err| err.to_string())?);
+        let timeout = Duration::from_millis(timeout_millis.max(0) as u64);
+        let mut packets_rx = self.packets_rx.borrow_mut();
+
+        let line = self.block_on(tokio::time::timeout(timeout, async {
+            loop {
+                let packet = match packets_rx.recv().await {
+                    Ok(packet) => packet,
+                    Err(RecvError::Lagged(skipped)) => {
+                        tracing::warn!(skipped, "Script missed packets");
+
+                        continue;
+                    }
+                    // The sender lives as long as the state.
+                    Err(RecvError::Closed) => return None,
+                };
+
+                if packet.port_name != name {
+                    continue;
+                }
+
+                if let CorePacketDirection::Incoming(incoming) = packet.packet_direction {
+                    if expected.matches(&incoming.line) {
+                        return Some(String::from_utf8_lossy(&incoming.line).into_owned());
+                    }
+                }
+            }
+        }))?;
+
+        match line {
+            Ok(Some(line)) => Ok(line),
+            _ => Err(format!("Timed out waiting for `{pattern}` on {name}").into()),
+        }
+    }
+
+    fn toggle_read_state(&self, name: &str) -> ScriptResult<()> {
+        self.block_on(self.state.serial_state().toggle_read_state(name))?
+            .ok_or_else(|| format!("Port not open: {name}").into())
+    }
+
+    fn sleep(&self, millis: i64) -> ScriptResult<()> {
+        self.block_on(tokio::time::sleep(Duration::from_millis(
+            millis.max(0) as u64
+        )))
+    }
+
+    fn open_ports(&self) -> ScriptResult<Array> {
+        let names = self.block_on(self.state.serial_state().open_serial_port_names())?;
+
+        Ok(names.into_iter().map(Dynamic::from).collect())
+    }
+}
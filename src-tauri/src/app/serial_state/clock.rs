@@ -0,0 +1,36 @@
+//! Monotonic clock mapped to wall time.
+//!
+//! Timestamps are taken from [`Instant`] and shifted by a wall clock anchor captured once,
+//! so they are strictly ordered and unaffected by system clock adjustments while the application is running.
+
+use std::{
+    sync::{Mutex, PoisonError},
+    time::Instant,
+};
+
+#[derive(Debug, Clone, Copy)]
+struct Anchor {
+    instant: Instant,
+    /// Wall time in microseconds since the Unix epoch at [`Anchor::instant`].
+    wall_micros: u64,
+}
+
+/// Set on first use.
+static ANCHOR: Mutex<Option<Anchor>> = Mutex::new(None);
+
+fn anchor() -> Anchor {
+    *ANCHOR
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(|| Anchor {
+            instant: Instant::now(),
+            wall_micros: chrono::Utc::now().timestamp_micros() as u64,
+        })
+}
+
+/// Current wall time in microseconds since the Unix epoch.
+pub fn now_micros() -> u64 {
+    let anchor = anchor();
+
+    anchor.wall_micros + anchor.instant.elapsed().as_micros() as u64
+}
@@ -0,0 +1,106 @@
+//! Recognizes forwarded bytes that a device echoes back.
+//!
+//! Bytes written to a serial port by a subscription are remembered with the serial ports they were read from.
+//! If the same bytes are read from that serial port shortly after, they are an echo and carry the same path,
+//! so they are not forwarded back to a serial port they already traversed.
+//!
+//! Only used for subscriptions that are part of a cycle and have [`CoreSubscription::echo_guard`](super::model::CoreSubscription::echo_guard) set.
+
+use std::{
+    collections::VecDeque,
+    sync::{Mutex, MutexGuard, PoisonError},
+    time::{Duration, Instant},
+};
+
+use tokio_util::bytes::{Buf, Bytes};
+
+/// How long written bytes are remembered.
+const ECHO_WINDOW: Duration = Duration::from_secs(1);
+
+/// Maximum number of remembered writes. The oldest is forgotten first.
+const MAX_ENTRIES: usize = 64;
+
+#[derive(Debug)]
+struct Entry {
+    /// Bytes not yet read back.
+    remaining: Bytes,
+    /// See [`CoreSubscriptionPacketOrigin::path`](super::model::CoreSubscriptionPacketOrigin::path).
+    path: Vec<String>,
+    expires_at: Instant,
+}
+
+#[derive(Debug, Default)]
+pub struct EchoGuard {
+    entries: Mutex<VecDeque<Entry>>,
+}
+
+impl EchoGuard {
+    /// The lock is never held across an await point. A poisoned lock still holds valid entries.
+    fn lock(&self) -> MutexGuard<'_, VecDeque<Entry>> {
+        self.entries.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Remembers `bytes` written to the serial port after traversing the serial ports in `path`.
+    pub fn record(&self, bytes: Bytes, path: Vec<String>) {
+        if bytes.is_empty() {
+            return;
+        }
+
+        let mut entries = self.lock();
+
+        if entries.len() == MAX_ENTRIES {
+            entries.pop_front();
+        }
+
+        entries.push_back(Entry {
+            remaining: bytes,
+            path,
+            expires_at: Instant::now() + ECHO_WINDOW,
+        });
+    }
+
+    /// Returns the path of the remembered bytes, if the `chunk` read from the serial port is an echo of them.
+    ///
+    /// An echo may be read in multiple chunks.
+    pub fn take_echo(&self, chunk: &[u8]) -> Option<Vec<String>> {
+        if chunk.is_empty() {
+            return None;
+        }
+
+        let mut entries = self.lock();
+
+        let now = Instant::now();
+        entries.retain(|entry| entry.expires_at > now);
+
+        let index = entries
+            .iter()
+            .position(|entry| entry.remaining.starts_with(chunk))?;
+
+        let entry = &mut entries[index];
+        entry.remaining.advance(chunk.len());
+
+        let path = entry.path.clone();
+
+        if entry.remaining.is_empty() {
+            entries.remove(index);
+        }
+
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn echo_in_chunks() {
+        let guard = EchoGuard::default();
+        guard.record(Bytes::from_static(b"hello"), vec![String::from("A")]);
+
+        assert_eq!(guard.take_echo(b"bye"), None);
+        assert_eq!(guard.take_echo(b"hel"), Some(vec![String::from("A")]));
+        assert_eq!(guard.take_echo(b"lo"), Some(vec![String::from("A")]));
+        assert_eq!(guard.take_echo(b"lo"), None);
+    }
+}
@@ -1,7 +1,7 @@
 use std::io::Error as IOError;
 
 use crate::{
-    app::serial_state::codec::lines_codec::LinesCodecError,
+    app::serial_state::{codec::lines_codec::LinesCodecError, handle::SendError},
     serial_manager::error::{SerialManagerAvailablePortsError, SerialManagerOpenPortError},
 };
 
@@ -61,6 +61,40 @@ pub enum CoreOutgoingPacketError {
     ),
 }
 
+/// Error sent through a [`CoreWriteAckSender`](crate::app::serial_state::model::CoreWriteAckSender).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreWriteError {
+    #[error("An IO error occurred: {0}")]
+    IO(
+        #[source]
+        #[from]
+        IOError,
+    ),
+    #[error("Port was taken by a transfer before the packet was written")]
+    Exclusive,
+}
+
+/// Error returned by [`StateInner::send_to_open_serial_port_acknowledged`](crate::app::serial_state::StateInner::send_to_open_serial_port_acknowledged).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreSendAcknowledgedError {
+    #[error("Port not open")]
+    NotOpen,
+    #[error("Failed to send: {0}")]
+    Send(
+        #[source]
+        #[from]
+        SendError,
+    ),
+    #[error("Failed to write: {0}")]
+    Write(
+        #[source]
+        #[from]
+        CoreWriteError,
+    ),
+    #[error("Packet was dropped or the port was closed before it was written")]
+    NotWritten,
+}
+
 /// Error returned by [`CoreSerialState::open_serial_port`](crate::core::state::CoreSerialState::open_serial_port).
 #[derive(Debug, thiserror::Error)]
 pub enum CoreOpenSerialPortError {
@@ -81,3 +115,160 @@ pub enum CoreOpenSerialPortError {
         SerialManagerOpenPortError,
     ),
 }
+
+/// Error returned by [`CoreSerialState::send_file`](crate::app::serial_state::CoreSerialState::send_file).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreSendFileError {
+    #[error("Port not open")]
+    NotOpen,
+    #[error("Failed to read file: {0}")]
+    Read(
+        #[source]
+        #[from]
+        IOError,
+    ),
+}
+
+/// Error emitted by [`CoreSerialState::send_file`](crate::app::serial_state::CoreSerialState::send_file),
+/// [`CoreSerialState::xmodem_send`](crate::app::serial_state::CoreSerialState::xmodem_send)
+/// and [`CoreSerialState::xmodem_receive`](crate::app::serial_state::CoreSerialState::xmodem_receive) through the channel.
+#[derive(Debug, thiserror::Error)]
+pub enum CoreTransferError {
+    #[error("Port closed during transfer")]
+    NotOpen,
+    #[error("Failed to send: {0}")]
+    Send(
+        #[source]
+        #[from]
+        SendError,
+    ),
+    #[error("Failed to write to the serial port: {0}")]
+    SerialWrite(#[source] CoreWriteError),
+    #[error("Packet was dropped or the port was closed before it was written")]
+    NotWritten,
+    #[error("Transfer protocol error: {0}")]
+    Xmodem(
+        #[source]
+        #[from]
+        CoreXmodemError,
+    ),
+    #[error("Failed to read file: {0}")]
+    Read(#[source] IOError),
+    #[error("Failed to write file: {0}")]
+    Write(#[source] IOError),
+}
+
+impl From<CoreSendAcknowledgedError> for CoreTransferError {
+    fn from(err: CoreSendAcknowledgedError) -> Self {
+        match err {
+            CoreSendAcknowledgedError::NotOpen => Self::NotOpen,
+            CoreSendAcknowledgedError::Send(err) => Self::Send(err),
+            CoreSendAcknowledgedError::Write(err) => Self::SerialWrite(err),
+            CoreSendAcknowledgedError::NotWritten => Self::NotWritten,
+        }
+    }
+}
+
+/// Error returned by [`CoreExclusiveSerialPort::read_byte`](crate::app::serial_state::handle::CoreExclusiveSerialPort::read_byte).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreExclusiveReadError {
+    #[error("Timed out waiting for bytes")]
+    Timeout,
+    #[error("Port closed")]
+    Closed,
+}
+
+/// Error returned by [`xmodem::send`](crate::app::serial_state::xmodem::send) and [`xmodem::receive`](crate::app::serial_state::xmodem::receive).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreXmodemError {
+    #[error("Failed to read: {0}")]
+    Read(
+        #[source]
+        #[from]
+        CoreExclusiveReadError,
+    ),
+    #[error("Failed to read file: {0}")]
+    File(#[source] IOError),
+    #[error("Failed to send: {0}")]
+    Send(
+        #[source]
+        #[from]
+        SendError,
+    ),
+    #[error("Transfer cancelled by the remote")]
+    RemoteCancelled,
+    #[error("Too many retries")]
+    TooManyRetries,
+    #[error("Block out of sequence. Expected: {expected}, received: {received}")]
+    BlockOutOfSequence { expected: u8, received: u8 },
+    #[error("Sender has no file to send")]
+    NoFile,
+}
+
+/// Error returned by [`CoreSerialState::xmodem_send`](crate::app::serial_state::CoreSerialState::xmodem_send)
+/// and [`CoreSerialState::xmodem_receive`](crate::app::serial_state::CoreSerialState::xmodem_receive).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreStartXmodemError {
+    #[error("Port not open")]
+    NotOpen,
+    #[error("Port is already exclusively owned by a transfer")]
+    Exclusive,
+    #[error("Failed to read file: {0}")]
+    Read(
+        #[source]
+        #[from]
+        IOError,
+    ),
+}
+
+/// Error returned by [`CoreSerialState::start_periodic_job`](crate::app::serial_state::CoreSerialState::start_periodic_job).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreStartPeriodicJobError {
+    #[error("Port not open")]
+    NotOpen,
+    #[error("Interval must be greater than zero")]
+    ZeroInterval,
+    #[error("Invalid cron expression: {0}")]
+    InvalidCron(
+        #[source]
+        #[from]
+        cron::error::Error,
+    ),
+}
+
+/// Error returned by [`StateInner::send_and_expect`](crate::app::serial_state::StateInner::send_and_expect).
+#[derive(Debug, thiserror::Error)]
+pub enum CoreSendAndExpectError {
+    #[error("Port not open")]
+    NotOpen,
+    #[error("Failed to send: {0}")]
+    Send(
+        #[source]
+        #[from]
+        SendError,
+    ),
+    #[error("Timed out waiting for a matching packet")]
+    Timeout,
+}
+
+/// Error returned by [`StateInner::create_bridge`](crate::app::serial_state::StateInner::create_bridge).
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, thiserror::Error)]
+pub enum CoreBridgeError {
+    #[error("A serial port can not be bridged with itself")]
+    SamePort,
+    /// A bridge does not replace a subscription, which may be stored.
+    #[error("{to} is already subscribed to {from}")]
+    Subscribed { from: String, to: String },
+}
+
+/// Error returned by [`StateInner::subscribe`](crate::app::serial_state::StateInner::subscribe).
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, thiserror::Error)]
+pub enum CoreSubscribeError {
+    /// Contains the serial ports of the cycle, starting and ending with the master.
+    #[error("Subscription would create a cycle: {}", .0.join(" -> "))]
+    Cycle(Vec<String>),
+    #[error("Subscription is a direction of a bridge")]
+    Bridge,
+}
@@ -1,57 +1,137 @@
+use std::{
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
 use tokio::sync::{
-    mpsc::{error::SendError as TokioSendError, UnboundedSender as MPSCUnboundedSender},
+    mpsc::{UnboundedReceiver as MPSCUnboundedReceiver, UnboundedSender as MPSCUnboundedSender},
     watch::Sender as WatchSender,
 };
-use tokio_util::sync::CancellationToken;
+use tokio_util::{
+    bytes::{Buf, Bytes, BytesMut},
+    sync::CancellationToken,
+};
+
+use super::{
+    error::CoreExclusiveReadError,
+    model::{
+        CoreLineEnding, CoreOutgoingPacket, CorePacketOrigin, CorePeriodicJob, CoreReadState,
+        CoreSerialPort, CoreTransfer, CoreWriteQueueMetrics,
+    },
+    write_queue::{WriteQueueError, WriteQueueSender},
+};
+
+/// Shared between [`CoreOpenSerialPort`], its [`TxHandle`]s, its [`CoreExclusiveSerialPort`] and its read task.
+///
+/// - `Some(_)`: A [`CoreExclusiveSerialPort`] owns the serial port. The read task sends the read bytes through the sender.
+/// - `None`: Normal mode.
+#[derive(Debug, Clone, Default)]
+pub struct ExclusiveSlot {
+    read_tx: Arc<Mutex<Option<MPSCUnboundedSender<Bytes>>>>,
+}
+
+impl ExclusiveSlot {
+    /// The lock is never held across an await point. A poisoned lock still holds a valid value.
+    fn lock(&self) -> MutexGuard<'_, Option<MPSCUnboundedSender<Bytes>>> {
+        self.read_tx
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
 
-use super::model::{CoreOutgoingPacket, CoreReadState, CoreSerialPort};
+    pub(super) fn is_taken(&self) -> bool {
+        self.lock().is_some()
+    }
+
+    /// Cheap clone of the sender to the current [`CoreExclusiveSerialPort`], if any.
+    pub(super) fn read_tx(&self) -> Option<MPSCUnboundedSender<Bytes>> {
+        self.lock().clone()
+    }
+
+    /// Drops the sender to the current [`CoreExclusiveSerialPort`], if any.
+    pub(super) fn release(&self) {
+        self.lock().take();
+    }
+}
 
 /// Used to copy the [`CoreOpenSerialPort::tx`] field from [`CoreOpenSerialPort`].
-/// Used as a handle to send data to a serial port that is a subscriber to another serial port.
-#[derive(Debug)]
-#[cfg(feature = "subscriptions")]
+/// Used as a handle to send data to a serial port without holding a lock on the open serial ports,
+/// e.g. to a serial port that is a subscriber to another serial port.
+#[derive(Debug, Clone)]
 pub struct TxHandle {
-    serial_port: CoreSerialPort,
-    tx: MPSCUnboundedSender<CoreOutgoingPacket>,
+    tx: WriteQueueSender,
+    outgoing_line_ending: CoreLineEnding,
+    exclusive_slot: ExclusiveSlot,
 }
 
-#[cfg(feature = "subscriptions")]
 impl TxHandle {
-    pub fn send(&self, value: CoreOutgoingPacket) -> Result<(), SendError> {
-        Ok(self.tx.send(value)?)
+    /// Appends the line ending of the [`CorePacketOrigin`] of the packet to its bytes before sending it.
+    /// See [`CorePacketOrigin::line_ending`].
+    ///
+    /// Fails with [`SendError::Exclusive`] while a [`CoreExclusiveSerialPort`] owns the serial port.
+    /// Waits for room in the write queue if its overflow policy is [`Block`](super::model::CoreWriteQueueOverflow::Block).
+    pub async fn send(&self, mut value: CoreOutgoingPacket) -> Result<(), SendError> {
+        if self.exclusive_slot.is_taken() {
+            return Err(SendError::Exclusive);
+        }
+
+        value.bytes = value
+            .packet_origin
+            .line_ending(&self.outgoing_line_ending)
+            .apply(value.bytes);
+
+        Ok(self.tx.send(value).await?)
     }
+}
 
-    pub fn name(&self) -> &str {
-        self.serial_port.name()
+#[cfg(test)]
+impl TxHandle {
+    /// A handle to a serial port that is not open. Its packets are received from the queue.
+    pub(super) fn detached(tx: WriteQueueSender) -> Self {
+        Self {
+            tx,
+            outgoing_line_ending: CoreLineEnding::None,
+            exclusive_slot: ExclusiveSlot::default(),
+        }
     }
 }
 
 #[derive(Debug)]
 pub struct CoreOpenSerialPort {
     serial_port: CoreSerialPort,
-    /// Main channel to send data to the serial port.
+    /// Main queue to send data to the serial port.
     ///
     /// The write task is waiting for data to be sent to the serial port.
-    tx: MPSCUnboundedSender<CoreOutgoingPacket>,
+    tx: WriteQueueSender,
+    /// Queue of the [`CoreExclusiveSerialPort`]. See [`CoreOpenSerialPort::take_exclusive`].
+    exclusive_tx: MPSCUnboundedSender<CoreOutgoingPacket>,
     cancellation_token: CancellationToken,
     /// Defines if the read task is currently reading or stopped.
     ///
     /// The read task is always watching for changes to the read state.
-    read_state_tx: WatchSender<CoreReadState>,
+    read_state_tx: Arc<WatchSender<CoreReadState>>,
+    /// Appended to the packets sent with [`TxHandle::send`].
+    outgoing_line_ending: CoreLineEnding,
+    exclusive_slot: ExclusiveSlot,
 }
 
 impl CoreOpenSerialPort {
     pub fn new(
         serial_port: CoreSerialPort,
-        tx: MPSCUnboundedSender<CoreOutgoingPacket>,
+        tx: WriteQueueSender,
+        exclusive_tx: MPSCUnboundedSender<CoreOutgoingPacket>,
         cancellation_token: CancellationToken,
         read_state_tx: WatchSender<CoreReadState>,
+        outgoing_line_ending: CoreLineEnding,
+        exclusive_slot: ExclusiveSlot,
     ) -> Self {
         Self {
             serial_port,
             tx,
+            exclusive_tx,
             cancellation_token,
-            read_state_tx,
+            read_state_tx: Arc::new(read_state_tx),
+            outgoing_line_ending,
+            exclusive_slot,
         }
     }
 
@@ -70,18 +150,18 @@ impl CoreOpenSerialPort {
         self
     }
 
-    pub(super) fn send(&self, value: CoreOutgoingPacket) -> Result<(), SendError> {
-        Ok(self.tx.send(value)?)
-    }
-
-    #[cfg(feature = "subscriptions")]
     pub(super) fn tx_handle(&self) -> TxHandle {
         TxHandle {
-            serial_port: self.serial_port.clone(),
             tx: self.tx.clone(),
+            outgoing_line_ending: self.outgoing_line_ending.clone(),
+            exclusive_slot: self.exclusive_slot.clone(),
         }
     }
 
+    pub(super) fn write_queue_metrics(&self) -> CoreWriteQueueMetrics {
+        self.tx.metrics()
+    }
+
     /// Fails silently if the send fails. Open serial port is probably closed.
     pub(super) fn set_read_state(&self, read_state: CoreReadState) {
         let _ = self.read_state_tx.send(read_state);
@@ -90,17 +170,300 @@ impl CoreOpenSerialPort {
     pub(super) fn read_state(&self) -> CoreReadState {
         *self.read_state_tx.borrow()
     }
+
+    /// Takes exclusive ownership of the read and write paths of the serial port.
+    ///
+    /// While the returned [`CoreExclusiveSerialPort`] is alive, the read task neither decodes lines nor forwards bytes to subscribers
+    /// and [`TxHandle::send`] fails.
+    /// The write task writes the packets of the [`CoreExclusiveSerialPort`] ahead of the write queue
+    /// and discards the packets still waiting in the write queue, failing their acks with [`CoreWriteError::Exclusive`](super::error::CoreWriteError::Exclusive).
+    ///
+    /// The read state is set to [`CoreReadState::Read`] and restored when the [`CoreExclusiveSerialPort`] is dropped,
+    /// unless it was changed in the meantime.
+    ///
+    /// - `Some(_)` => Ok.
+    /// - `None` => The serial port is already owned by another [`CoreExclusiveSerialPort`].
+    pub(super) fn take_exclusive(
+        &self,
+        packet_origin: CorePacketOrigin,
+    ) -> Option<CoreExclusiveSerialPort> {
+        let mut read_tx = self.exclusive_slot.lock();
+
+        if read_tx.is_some() {
+            return None;
+        }
+
+        tracing::debug!(name=%self.name(), "Taking exclusive ownership");
+
+        let (exclusive_read_tx, exclusive_read_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        *read_tx = Some(exclusive_read_tx);
+
+        let previous_read_state = self.read_state();
+        self.set_read_state(CoreReadState::Read);
+
+        Some(CoreExclusiveSerialPort {
+            serial_port: self.serial_port.clone(),
+            tx: self.exclusive_tx.clone(),
+            rx: exclusive_read_rx,
+            buffer: BytesMut::new(),
+            packet_origin,
+            exclusive_slot: self.exclusive_slot.clone(),
+            read_state_tx: self.read_state_tx.clone(),
+            previous_read_state,
+        })
+    }
+}
+
+/// Exclusive ownership of the read and write paths of an open serial port. See [`CoreOpenSerialPort::take_exclusive`].
+///
+/// Hands the serial port back to normal mode when dropped.
+#[derive(Debug)]
+pub struct CoreExclusiveSerialPort {
+    serial_port: CoreSerialPort,
+    /// Clone of [`CoreOpenSerialPort::exclusive_tx`]. Bypasses the line ending, the exclusivity check and the overflow policy of the write queue.
+    tx: MPSCUnboundedSender<CoreOutgoingPacket>,
+    /// Raw bytes read by the read task.
+    rx: MPSCUnboundedReceiver<Bytes>,
+    /// Bytes received but not yet read.
+    buffer: BytesMut,
+    /// Origin of the packets written with [`CoreExclusiveSerialPort::write`].
+    packet_origin: CorePacketOrigin,
+    exclusive_slot: ExclusiveSlot,
+    read_state_tx: Arc<WatchSender<CoreReadState>>,
+    previous_read_state: CoreReadState,
+}
+
+impl CoreExclusiveSerialPort {
+    pub fn name(&self) -> &str {
+        self.serial_port.name()
+    }
+
+    /// Sends the bytes as they are. Never dropped by the overflow policy of the write queue.
+    pub async fn write(&self, bytes: &[u8]) -> Result<(), SendError> {
+        let packet = CoreOutgoingPacket {
+            bytes: Bytes::copy_from_slice(bytes),
+            packet_origin: self.packet_origin.clone(),
+            ack: None,
+        };
+
+        self.tx
+            .send(packet)
+            .map_err(|_| SendError::Send(WriteQueueError::Closed))
+    }
+
+    /// Waits at most `timeout` for a byte.
+    pub async fn read_byte(&mut self, timeout: Duration) -> Result<u8, CoreExclusiveReadError> {
+        while self.buffer.is_empty() {
+            match tokio::time::timeout(timeout, self.rx.recv()).await {
+                Ok(Some(bytes)) => self.buffer.extend_from_slice(&bytes),
+                Ok(None) => return Err(CoreExclusiveReadError::Closed),
+                Err(_) => return Err(CoreExclusiveReadError::Timeout),
+            }
+        }
+
+        Ok(self.buffer.get_u8())
+    }
+
+    /// Waits at most `timeout` for each byte.
+    pub async fn read_exact(
+        &mut self,
+        len: usize,
+        timeout: Duration,
+    ) -> Result<Vec<u8>, CoreExclusiveReadError> {
+        let mut bytes = Vec::with_capacity(len);
+
+        for _ in 0..len {
+            bytes.push(self.read_byte(timeout).await?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Discards the bytes received so far.
+    pub fn clear_input(&mut self) {
+        self.buffer.clear();
+
+        while self.rx.try_recv().is_ok() {}
+    }
+}
+
+#[cfg(test)]
+impl CoreExclusiveSerialPort {
+    /// Two exclusive serial ports connected to each other, as if by a null modem cable.
+    pub(super) fn connected_pair() -> (Self, Self) {
+        fn port(
+            name: &str,
+            tx: MPSCUnboundedSender<CoreOutgoingPacket>,
+            rx: MPSCUnboundedReceiver<Bytes>,
+        ) -> CoreExclusiveSerialPort {
+            CoreExclusiveSerialPort {
+                serial_port: CoreSerialPort::new(name.to_string()),
+                tx,
+                rx,
+                buffer: BytesMut::new(),
+                packet_origin: CorePacketOrigin::default(),
+                exclusive_slot: ExclusiveSlot::default(),
+                read_state_tx: Arc::new(tokio::sync::watch::channel(CoreReadState::Read).0),
+                previous_read_state: CoreReadState::Read,
+            }
+        }
+
+        fn connect(
+            mut from: MPSCUnboundedReceiver<CoreOutgoingPacket>,
+            to: MPSCUnboundedSender<Bytes>,
+        ) {
+            tokio::spawn(async move {
+                while let Some(packet) = from.recv().await {
+                    let _ = to.send(packet.bytes);
+                }
+            });
+        }
+
+        let (a_tx, a_written_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (b_tx, b_written_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (a_read_tx, a_rx) = tokio::sync::mpsc::unbounded_channel();
+        let (b_read_tx, b_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        connect(a_written_rx, b_read_tx);
+        connect(b_written_rx, a_read_tx);
+
+        (port("a", a_tx, a_rx), port("b", b_tx, b_rx))
+    }
+}
+
+impl Drop for CoreExclusiveSerialPort {
+    fn drop(&mut self) {
+        tracing::debug!(name=%self.name(), "Releasing exclusive ownership");
+
+        self.exclusive_slot.release();
+
+        let previous_read_state = self.previous_read_state;
+
+        // Keeps a read state set by the user during the transfer.
+        self.read_state_tx.send_if_modified(|read_state| {
+            if matches!(read_state, CoreReadState::Read) {
+                *read_state = previous_read_state;
+
+                return true;
+            }
+
+            false
+        });
+    }
+}
+
+/// Registered in [`StateInner::transfers`](super::StateInner) while a transfer is running.
+#[derive(Debug)]
+pub struct CoreTransferHandle {
+    transfer: CoreTransfer,
+    cancellation_token: CancellationToken,
 }
 
-/// Error returned by [`CoreOpenSerialPort::send`](CoreOpenSerialPort::send) and [`TxHandle::send`](TxHandle::send)
+impl CoreTransferHandle {
+    pub fn new(transfer: CoreTransfer, cancellation_token: CancellationToken) -> Self {
+        Self {
+            transfer,
+            cancellation_token,
+        }
+    }
+
+    pub fn transfer(&self) -> &CoreTransfer {
+        &self.transfer
+    }
+
+    pub(super) fn cancel(&self) {
+        tracing::debug!(id=%self.transfer.id, "Cancelling transfer");
+
+        self.cancellation_token.cancel()
+    }
+}
+
+/// Registered in [`StateInner::periodic_jobs`](super::StateInner) while a periodic send job is running.
+#[derive(Debug)]
+pub struct CorePeriodicJobHandle {
+    job: CorePeriodicJob,
+    cancellation_token: CancellationToken,
+}
+
+impl CorePeriodicJobHandle {
+    pub fn new(job: CorePeriodicJob, cancellation_token: CancellationToken) -> Self {
+        Self {
+            job,
+            cancellation_token,
+        }
+    }
+
+    pub fn job(&self) -> &CorePeriodicJob {
+        &self.job
+    }
+
+    pub(super) fn cancelled(self) -> CorePeriodicJob {
+        tracing::debug!(id=%self.job.id, "Cancelling periodic job");
+
+        self.cancellation_token.cancel();
+        self.job
+    }
+}
+
+/// Error returned by [`TxHandle::send`](TxHandle::send) and [`CoreExclusiveSerialPort::write`](CoreExclusiveSerialPort::write).
 #[derive(Debug, thiserror::Error)]
 pub enum SendError {
     #[error("Failed to send: {0}")]
     Send(
         #[source]
         #[from]
-        TokioSendError<CoreOutgoingPacket>,
+        WriteQueueError,
     ),
+    #[error("Port is exclusively owned by a transfer")]
+    Exclusive,
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::sync::watch::Receiver as WatchReceiver;
+
+    use super::*;
+    use crate::app::serial_state::write_queue::write_queue;
+
+    /// The receiver keeps the read state writable.
+    fn open_serial_port(
+        read_state: CoreReadState,
+    ) -> (CoreOpenSerialPort, WatchReceiver<CoreReadState>) {
+        let (read_state_tx, read_state_rx) = tokio::sync::watch::channel(read_state);
+
+        let port = CoreOpenSerialPort::new(
+            CoreSerialPort::new(String::from("COM1")),
+            write_queue(Default::default()).0,
+            tokio::sync::mpsc::unbounded_channel().0,
+            CancellationToken::new(),
+            read_state_tx,
+            CoreLineEnding::None,
+            ExclusiveSlot::default(),
+        );
+
+        (port, read_state_rx)
+    }
+
+    #[test]
+    fn exclusive_restores_read_state_unless_changed() {
+        let (port, _read_state_rx) = open_serial_port(CoreReadState::Stop);
+
+        let exclusive = port.take_exclusive(CorePacketOrigin::default()).unwrap();
+        assert!(matches!(port.read_state(), CoreReadState::Read));
+        assert!(port.take_exclusive(CorePacketOrigin::default()).is_none());
+
+        drop(exclusive);
+        assert!(matches!(port.read_state(), CoreReadState::Stop));
+
+        let (port, _read_state_rx) = open_serial_port(CoreReadState::Read);
+
+        let exclusive = port.take_exclusive(CorePacketOrigin::default()).unwrap();
+        port.set_read_state(CoreReadState::Stop);
+
+        drop(exclusive);
+        assert!(matches!(port.read_state(), CoreReadState::Stop));
+    }
 }
 
 mod impl_from {
@@ -110,7 +473,7 @@ mod impl_from {
     };
 
     use crate::app::serial_state::model::{
-        CoreDataBits, CoreFlowControl, CoreOpenSerialPortOptions, CoreParity, CoreReadState,
+        CoreDataBits, CoreFlowControl, CoreOpenSerialPortOptions, CoreParity, CoreStateOptions,
         CoreStopBits,
     };
 
@@ -155,11 +518,14 @@ mod impl_from {
     }
 
     impl CoreOpenSerialPortOptions {
-        pub fn split_into_read_state_and_manager_options(
+        pub fn split_into_state_and_manager_options(
             self,
-        ) -> (CoreReadState, SerialManagerOpenSerialPortOptions) {
+        ) -> (CoreStateOptions, SerialManagerOpenSerialPortOptions) {
             let CoreOpenSerialPortOptions {
                 initial_read_state,
+                outgoing_line_ending,
+                write_pacing,
+                write_queue,
                 baud_rate,
                 data_bits,
                 flow_control,
@@ -169,7 +535,12 @@ mod impl_from {
             } = self;
 
             (
-                initial_read_state,
+                CoreStateOptions {
+                    initial_read_state,
+                    outgoing_line_ending,
+                    write_pacing,
+                    write_queue,
+                },
                 SerialManagerOpenSerialPortOptions {
                     baud_rate,
                     data_bits: data_bits.into(),
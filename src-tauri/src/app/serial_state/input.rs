@@ -0,0 +1,207 @@
+//! Conversion of values typed by the user into the bytes sent to a serial port.
+
+use tokio_util::bytes::{BufMut, Bytes, BytesMut};
+
+/// Describes how a value typed by the user is converted to bytes.
+#[derive(Debug, Clone, Copy, Default)]
+pub enum CoreInputMode {
+    /// UTF-8 bytes of the value.
+    #[default]
+    Text,
+    /// UTF-8 bytes of the value with C-style escape sequences.
+    ///
+    /// Supported: `\r`, `\n`, `\t`, `\0`, `\a`, `\b`, `\f`, `\v`, `\e`, `\\`, `\"`, `\'` and `\xHH`.
+    Escaped,
+    /// Hex bytes, e.g. `DE AD BE EF`, `DEADBEEF` or `0xDE 0xAD`.
+    ///
+    /// Whitespace is only allowed between bytes.
+    Hex,
+}
+
+impl CoreInputMode {
+    pub fn parse(&self, value: &str) -> Result<Bytes, CoreInputParseError> {
+        match self {
+            Self::Text => Ok(Bytes::copy_from_slice(value.as_bytes())),
+            Self::Escaped => parse_escaped(value),
+            Self::Hex => parse_hex(value),
+        }
+    }
+}
+
+fn parse_escaped(value: &str) -> Result<Bytes, CoreInputParseError> {
+    let mut bytes = BytesMut::with_capacity(value.len());
+    let mut chars = value.chars().enumerate();
+
+    while let Some((position, char)) = chars.next() {
+        if char != '\\' {
+            bytes.extend_from_slice(char.encode_utf8(&mut [0; 4]).as_bytes());
+
+            continue;
+        }
+
+        let (_, escaped) = chars
+            .next()
+            .ok_or(CoreInputParseError::IncompleteEscape { position })?;
+
+        let byte = match escaped {
+            'r' => b'\r',
+            'n' => b'\n',
+            't' => b'\t',
+            '0' => b'\0',
+            'a' => 0x07,
+            'b' => 0x08,
+            'f' => 0x0C,
+            'v' => 0x0B,
+            'e' => 0x1B,
+            '\\' => b'\\',
+            '"' => b'"',
+            '\'' => b'\'',
+            'x' => {
+                let mut byte = 0;
+
+                for _ in 0..2 {
+                    let (digit_position, digit) = chars
+                        .next()
+                        .ok_or(CoreInputParseError::IncompleteEscape { position })?;
+
+                    byte = (byte << 4) | hex_digit(digit_position, digit)?;
+                }
+
+                byte
+            }
+            sequence => {
+                return Err(CoreInputParseError::UnknownEscape { position, sequence });
+            }
+        };
+
+        bytes.put_u8(byte);
+    }
+
+    Ok(bytes.freeze())
+}
+
+fn parse_hex(value: &str) -> Result<Bytes, CoreInputParseError> {
+    let mut bytes = BytesMut::with_capacity(value.len() / 2);
+    let mut chars = value.chars().enumerate().peekable();
+    // Position and value of the high nibble of the byte currently being parsed.
+    let mut high_nibble: Option<(usize, u8)> = None;
+
+    while let Some((position, char)) = chars.next() {
+        match high_nibble {
+            None if char.is_whitespace() => {}
+            None if char == '0' && matches!(chars.peek(), Some((_, 'x' | 'X'))) => {
+                chars.next();
+            }
+            None => high_nibble = Some((position, hex_digit(position, char)?)),
+            Some((high_position, _)) if char.is_whitespace() => {
+                return Err(CoreInputParseError::IncompleteHexByte {
+                    position: high_position,
+                });
+            }
+            Some((_, high)) => {
+                bytes.put_u8((high << 4) | hex_digit(position, char)?);
+
+                high_nibble = None;
+            }
+        }
+    }
+
+    if let Some((position, _)) = high_nibble {
+        return Err(CoreInputParseError::IncompleteHexByte { position });
+    }
+
+    Ok(bytes.freeze())
+}
+
+fn hex_digit(position: usize, digit: char) -> Result<u8, CoreInputParseError> {
+    digit
+        .to_digit(16)
+        .map(|value| value as u8)
+        .ok_or(CoreInputParseError::InvalidHexDigit { position, digit })
+}
+
+/// Error returned by [`CoreInputMode::parse`].
+///
+/// Positions are character indices in the parsed value.
+#[derive(Debug, thiserror::Error)]
+pub enum CoreInputParseError {
+    #[error("Unknown escape sequence `\\{sequence}` at position {position}")]
+    UnknownEscape { position: usize, sequence: char },
+    #[error("Incomplete escape sequence at position {position}")]
+    IncompleteEscape { position: usize },
+    #[error("Invalid hex digit `{digit}` at position {position}")]
+    InvalidHexDigit { position: usize, digit: char },
+    #[error("Incomplete hex byte at position {position}")]
+    IncompleteHexByte { position: usize },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_escaped_sequences() {
+        let bytes = CoreInputMode::Escaped.parse(r"AT\r\n\x1B\0\xff\\").unwrap();
+
+        assert_eq!(&bytes[..], b"AT\r\n\x1B\0\xFF\\");
+    }
+
+    #[test]
+    fn parse_escaped_reports_position() {
+        assert!(matches!(
+            CoreInputMode::Escaped.parse(r"ab\q"),
+            Err(CoreInputParseError::UnknownEscape {
+                position: 2,
+                sequence: 'q'
+            })
+        ));
+
+        assert!(matches!(
+            CoreInputMode::Escaped.parse(r"\x1"),
+            Err(CoreInputParseError::IncompleteEscape { position: 0 })
+        ));
+
+        assert!(matches!(
+            CoreInputMode::Escaped.parse(r"\x1G"),
+            Err(CoreInputParseError::InvalidHexDigit {
+                position: 3,
+                digit: 'G'
+            })
+        ));
+    }
+
+    #[test]
+    fn parse_hex_bytes() {
+        let expected = [0xDE, 0xAD, 0xBE, 0xEF];
+
+        for value in [
+            "DE AD BE EF",
+            "deadbeef",
+            "0xDE 0xAD 0XBEEF",
+            " DEAD\tBEEF ",
+        ] {
+            assert_eq!(&CoreInputMode::Hex.parse(value).unwrap()[..], expected);
+        }
+    }
+
+    #[test]
+    fn parse_hex_reports_position() {
+        assert!(matches!(
+            CoreInputMode::Hex.parse("DE A BE"),
+            Err(CoreInputParseError::IncompleteHexByte { position: 3 })
+        ));
+
+        assert!(matches!(
+            CoreInputMode::Hex.parse("DE ADB"),
+            Err(CoreInputParseError::IncompleteHexByte { position: 5 })
+        ));
+
+        assert!(matches!(
+            CoreInputMode::Hex.parse("DE AZ"),
+            Err(CoreInputParseError::InvalidHexDigit {
+                position: 4,
+                digit: 'Z'
+            })
+        ));
+    }
+}
@@ -1,32 +1,80 @@
-use std::{collections::HashMap, ops::Deref, sync::Arc};
+use std::{
+    collections::HashMap,
+    future::Future,
+    ops::Deref,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
-use error::{CoreManagedSerialPortsError, CoreOpenSerialPortError, CorePacketError};
+#[cfg(feature = "subscriptions")]
+use echo_guard::EchoGuard;
+#[cfg(feature = "subscriptions")]
+use error::{CoreBridgeError, CoreSubscribeError};
+use error::{
+    CoreManagedSerialPortsError, CoreOpenSerialPortError, CorePacketError,
+    CoreSendAcknowledgedError, CoreSendAndExpectError, CoreSendFileError,
+    CoreStartPeriodicJobError, CoreStartXmodemError, CoreTransferError, CoreWriteError,
+};
 use futures::{SinkExt, StreamExt};
-use handle::{CoreOpenSerialPort, SendError, TxHandle};
+use handle::{
+    CoreExclusiveSerialPort, CoreOpenSerialPort, CorePeriodicJobHandle, CoreTransferHandle,
+    ExclusiveSlot, SendError, TxHandle,
+};
 use model::{
-    CoreManagedSerialPort, CoreOpenStatus, CoreSerialPort, Status,
+    CoreAutoResponder, CoreAutoResponderPacketOrigin, CoreExpectPattern, CoreFilePacketOrigin,
+    CoreManagedSerialPort, CoreOpenStatus, CorePacketOrigin, CorePeriodicJob,
+    CorePeriodicPacketOrigin, CorePeriodicSchedule, CoreSendFilePacing, CoreSerialPort,
+    CoreTransfer, CoreTransferDirection, CoreTransferEvent, CoreTransferKind,
+    CoreTransferPacketOrigin, CoreWriteAck, CoreWriteAckSender, CoreWritePacing,
+    CoreXmodemProtocol, Status,
     {
         CoreIncomingPacket, CoreOpenSerialPortOptions, CoreOutgoingPacket, CorePacket,
         CorePacketDirection,
     },
 };
 #[cfg(feature = "subscriptions")]
-use model::{CorePacketOrigin, CoreSubscriptionPacketOrigin};
-use tokio::sync::{mpsc::UnboundedReceiver as MPSCUnboundedReceiver, RwLock};
+use model::{
+    CoreSubscription, CoreSubscriptionFilter, CoreSubscriptionMetrics, CoreSubscriptionMode,
+    CoreSubscriptionOptions, CoreSubscriptionPacketOrigin, CoreSubscriptionRateLimit,
+};
+#[cfg(feature = "subscriptions")]
+use rate_limit::SubscriptionForwarder;
+#[cfg(feature = "subscriptions")]
+use subscription_metrics::SubscriptionCounters;
+use tokio::sync::{
+    broadcast::{Receiver as BroadcastReceiver, Sender as BroadcastSender},
+    mpsc::{UnboundedReceiver as MPSCUnboundedReceiver, UnboundedSender as MPSCUnboundedSender},
+    RwLock,
+};
 use tokio_util::{
-    bytes::BytesMut,
+    bytes::{Bytes, BytesMut},
     codec::{BytesCodec, Decoder, FramedRead, FramedWrite},
     sync::CancellationToken,
 };
+use write_queue::{write_queue, WriteQueueError};
 
 use crate::serial_manager::{serial_manager_service::SerialManagerService, SerialManager};
 
 use codec::lines_codec::LinesCodec;
 
+pub mod clock;
 pub mod codec;
+#[cfg(feature = "subscriptions")]
+mod echo_guard;
 pub mod error;
 pub mod handle;
+pub mod input;
 pub mod model;
+mod periodic;
+#[cfg(feature = "subscriptions")]
+mod rate_limit;
+#[cfg(feature = "subscriptions")]
+mod subscription_metrics;
+pub mod write_queue;
+pub mod xmodem;
 
 #[derive(Debug, Clone)]
 pub struct CoreSerialState {
@@ -53,14 +101,122 @@ impl Deref for CoreSerialState {
 /// - `Value`: Open serial port [`CoreOpenSerialPort`].
 type OpenSerialPorts = HashMap<String, CoreOpenSerialPort>;
 
+/// A subscriber of a master serial port.
+#[cfg(feature = "subscriptions")]
+#[derive(Debug)]
+struct Subscription {
+    /// Optional subscriber's [`TxHandle`] to send data to the subscriber.
+    /// - `Some(TxHandle)`: Subscriber is open.
+    /// - `None`: Subscriber is closed.
+    tx_handle: Option<TxHandle>,
+    /// See [`CoreSubscription::filter`].
+    filter: Option<CoreSubscriptionFilter>,
+    /// See [`CoreSubscription::mode`].
+    mode: CoreSubscriptionMode,
+    /// Direction of a bridge. The opposite direction exists as well.
+    ///
+    /// Created and removed together with the opposite direction. See [`StateInner::create_bridge`].
+    bridge: bool,
+    /// See [`CoreSubscription::echo_guard`].
+    echo_guard: bool,
+    /// Whether the bytes forwarded by the subscription are remembered by the [`EchoGuard`] of the subscriber.
+    ///
+    /// Only if [`Subscription::echo_guard`] is set and the subscription is part of a cycle, since otherwise an echo can not be forwarded back.
+    /// Kept up to date by [`update_echo_guards`] whenever the subscriptions change, so the read task does not search for cycles.
+    guards_echo: bool,
+    /// Shared with the read task of the master.
+    counters: Arc<SubscriptionCounters>,
+    /// Sends the frames to the subscriber, so the read task of the master never waits for it.
+    forwarder: Arc<SubscriptionForwarder>,
+}
+
+#[cfg(feature = "subscriptions")]
+impl Subscription {
+    fn rate_limit(&self) -> CoreSubscriptionRateLimit {
+        self.forwarder.rate_limit()
+    }
+}
+
 /// - `Key`: Master Serial port name.
 /// - `Value`:  
 ///     - `Key`: Subscriber serial port name.
-///     - `Value`: Optional subscriber's [`TxHandle`] to send data to the subscriber.
-///        - `Some(TxHandle)`: Subscriber is open.
-///        - `None`: Subscriber is closed.
+///     - `Value`: [`Subscription`].
+#[cfg(feature = "subscriptions")]
+type Subscriptions = HashMap<String, HashMap<String, Subscription>>;
+
+/// Returns the serial ports of the cycle, starting and ending with `from`, if subscribing `to` to `from` would create one.
+#[cfg(feature = "subscriptions")]
+fn find_cycle(subscriptions: &Subscriptions, from: &str, to: &str) -> Option<Vec<String>> {
+    // Breadth-first search for a path from `to` back to `from`.
+    let mut previous = HashMap::<&str, &str>::new();
+    let mut queue = std::collections::VecDeque::from([to]);
+
+    while let Some(name) = queue.pop_front() {
+        if name == from {
+            let mut cycle = vec![from.to_string()];
+            let mut current = from;
+
+            while current != to {
+                current = previous[current];
+                cycle.push(current.to_string());
+            }
+
+            cycle.push(from.to_string());
+            cycle.reverse();
+
+            return Some(cycle);
+        }
+
+        for subscriber in subscriptions.get(name).into_iter().flat_map(HashMap::keys) {
+            if subscriber != to && !previous.contains_key(subscriber.as_str()) {
+                previous.insert(subscriber, name);
+                queue.push_back(subscriber);
+            }
+        }
+    }
+
+    None
+}
+
+/// Updates [`Subscription::guards_echo`] of every subscription. Called after the subscriptions or their echo guards changed.
 #[cfg(feature = "subscriptions")]
-type Subscriptions = HashMap<String, HashMap<String, Option<TxHandle>>>;
+fn update_echo_guards(subscriptions: &mut Subscriptions) {
+    let guards_echo = subscriptions
+        .iter()
+        .flat_map(|(from, subscribers)| {
+            subscribers.iter().map(|(to, subscription)| {
+                let guards_echo =
+                    subscription.echo_guard && find_cycle(subscriptions, from, to).is_some();
+
+                (from.clone(), to.clone(), guards_echo)
+            })
+        })
+        .collect::<Vec<_>>();
+
+    for (from, to, guards_echo) in guards_echo {
+        if let Some(subscription) = subscriptions
+            .get_mut(&from)
+            .and_then(|subscribers| subscribers.get_mut(&to))
+        {
+            subscription.guards_echo = guards_echo;
+        }
+    }
+}
+
+/// - `Key`: Transfer id.
+/// - `Value`: Running transfer [`CoreTransferHandle`].
+type Transfers = HashMap<u64, CoreTransferHandle>;
+
+/// - `Key`: Periodic job id.
+/// - `Value`: Running periodic job [`CorePeriodicJobHandle`].
+type PeriodicJobs = HashMap<u64, CorePeriodicJobHandle>;
+
+/// - `Key`: Auto-responder id, given by the caller.
+/// - `Value`: [`CoreAutoResponder`].
+type AutoResponders = HashMap<i32, CoreAutoResponder>;
+
+/// Capacity of [`StateInner::packets_tx`].
+const PACKETS_CAPACITY: usize = 1024;
 
 /// ## Note
 /// Locks are not optimized. See branch [`feat/optimize-locks`](https://github.com/JadKHaddad/serial-vau/tree/feat/optimize-locks) for optimized locks sacrificing readability.
@@ -73,12 +229,26 @@ pub struct StateInner {
     /// ## Notes
     ///
     /// - Subscriptions can exist before the master or subscriber is open.
-    /// - Subscriptions can be self-referential.
+    /// - Subscriptions can be self-referential or form cycles if explicitly allowed. See [`Self::subscribe`].
     /// - Subscriptions can exist even if the `name` of a serial port does not exist (yet).
     /// - Subscriptions are not removed when the master or subscriber is closed or removed from system.
     /// - Subscriptions are removed manually.
+    /// - Subscriptions are not persisted here. See [`AppState::load_subscriptions`](crate::app::state::AppState::load_subscriptions).
+    /// - Bridges are never persisted. They are lost when the application exits. See [`Self::create_bridge`].
     #[cfg(feature = "subscriptions")]
     subscriptions: Arc<RwLock<Subscriptions>>,
+    /// Transfers are removed when they finish, fail or are cancelled.
+    transfers: RwLock<Transfers>,
+    next_transfer_id: AtomicU64,
+    /// Periodic jobs are removed when they are stopped or their serial port is closed.
+    periodic_jobs: RwLock<PeriodicJobs>,
+    next_periodic_job_id: AtomicU64,
+    /// Matched against every incoming line of every open serial port.
+    auto_responders: RwLock<AutoResponders>,
+    /// Every packet read from or written to any open serial port.
+    ///
+    /// Slow receivers lag and miss packets.
+    packets_tx: BroadcastSender<CorePacket>,
 }
 
 impl StateInner {
@@ -88,6 +258,12 @@ impl StateInner {
             open_serial_ports: RwLock::new(HashMap::new()),
             #[cfg(feature = "subscriptions")]
             subscriptions: Arc::new(RwLock::new(HashMap::new())),
+            transfers: RwLock::new(HashMap::new()),
+            next_transfer_id: AtomicU64::new(0),
+            periodic_jobs: RwLock::new(HashMap::new()),
+            next_periodic_job_id: AtomicU64::new(0),
+            auto_responders: RwLock::new(HashMap::new()),
+            packets_tx: tokio::sync::broadcast::channel(PACKETS_CAPACITY).0,
         }
     }
 
@@ -114,16 +290,37 @@ impl StateInner {
                 #[cfg(feature = "subscriptions")]
                 let subscribed_to = subscriptions
                     .iter()
-                    .filter(|&(_, tx_handles)| tx_handles.contains_key(port.name()))
+                    .filter(|&(_, subscribers)| {
+                        subscribers
+                            .get(port.name())
+                            .map_or(false, |subscription| !subscription.bridge)
+                    })
                     .map(|(name, _)| name.clone())
                     .collect::<Vec<_>>();
 
                 #[cfg(feature = "subscriptions")]
-                let subscriptions = subscriptions
+                let bridged_with = subscriptions
                     .get(port.name())
                     .unwrap_or(&HashMap::new())
                     .iter()
+                    .filter(|(_, subscription)| subscription.bridge)
                     .map(|(name, _)| name.clone())
+                    .collect::<Vec<_>>();
+
+                #[cfg(feature = "subscriptions")]
+                let subscriptions = subscriptions
+                    .get(port.name())
+                    .unwrap_or(&HashMap::new())
+                    .iter()
+                    .filter(|(_, subscription)| !subscription.bridge)
+                    .map(|(name, subscription)| CoreSubscription {
+                        name: name.clone(),
+                        filter: subscription.filter.clone(),
+                        mode: subscription.mode.clone(),
+                        rate_limit: subscription.rate_limit(),
+                        echo_guard: subscription.echo_guard,
+                        metrics: subscription.counters.metrics(),
+                    })
                     .collect();
 
                 let mut managed_serial_port = CoreManagedSerialPort {
@@ -133,11 +330,14 @@ impl StateInner {
                     subscriptions,
                     #[cfg(feature = "subscriptions")]
                     subscribed_to,
+                    #[cfg(feature = "subscriptions")]
+                    bridged_with,
                 };
 
                 if let Some(open_serial_port) = open_serial_ports.get(port.name()) {
                     managed_serial_port.status = Status::Open(CoreOpenStatus {
                         read_state: open_serial_port.read_state(),
+                        write_queue: open_serial_port.write_queue_metrics(),
                     });
                 }
 
@@ -191,10 +391,10 @@ impl StateInner {
 
         let mut subscriptions = self.subscriptions.write().await;
 
-        for (_, tx_handles) in subscriptions.iter_mut() {
-            tx_handles
+        for (_, subscribers) in subscriptions.iter_mut() {
+            subscribers
                 .get_mut(open_serial_port.name())
-                .map(|tx_handle| tx_handle.replace(open_serial_port.tx_handle()));
+                .map(|subscription| subscription.tx_handle.replace(open_serial_port.tx_handle()));
         }
     }
 
@@ -213,25 +413,30 @@ impl StateInner {
 
         let mut subscriptions = self.subscriptions.write().await;
 
-        for (_, tx_handles) in subscriptions.iter_mut() {
-            tx_handles.get_mut(name).map(|tx_handle| tx_handle.take());
+        for (_, subscribers) in subscriptions.iter_mut() {
+            subscribers
+                .get_mut(name)
+                .map(|subscription| subscription.tx_handle.take());
         }
     }
 
-    /// Removes the serial port from [`Self::open_serial_ports`] and cancels its subscription.
+    /// Removes the serial port from [`Self::open_serial_ports`], cancels its subscription and stops its periodic jobs.
     ///
     /// ## Locks
     ///
+    /// - Write: [`Self::periodic_jobs`]. Inherited from [`Self::stop_periodic_jobs_of_port`].
     #[cfg_attr(
         feature = "subscriptions",
         doc = "
-    - Write: [`Self::subscriptions`]. Inherited from [`Self::remove_open_serial_port_from_all_subscriptions`].
+- Write: [`Self::subscriptions`]. Inherited from [`Self::remove_open_serial_port_from_all_subscriptions`].
     "
     )]
     /// - Write: [`Self::open_serial_ports`].
     async fn remove_open_serial_port(&self, name: &str) -> Option<CoreOpenSerialPort> {
         tracing::debug!(name=%name, "Removing serial port");
 
+        self.stop_periodic_jobs_of_port(name).await;
+
         #[cfg(feature = "subscriptions")]
         self.remove_open_serial_port_from_all_subscriptions(name)
             .await;
@@ -245,7 +450,7 @@ impl StateInner {
     #[cfg_attr(
         feature = "subscriptions",
         doc = "
-    - Write: [`Self::subscriptions`]. Inherited from [`Self::remove_open_serial_port`].
+- Write: [`Self::subscriptions`]. Inherited from [`Self::remove_open_serial_port`].
     "
     )]
     /// - Write: [`Self::open_serial_ports`]. Inherited from [`Self::remove_open_serial_port`].
@@ -284,6 +489,8 @@ impl StateInner {
     /// - `Some(Err(_))` => Send error.
     /// - `None` => Port not found.
     ///
+    /// Waits for room in the write queue of the serial port without holding the lock.
+    ///
     /// ## Locks
     ///
     /// - Read: [`Self::open_serial_ports`].
@@ -292,21 +499,108 @@ impl StateInner {
         name: &str,
         packet: CoreOutgoingPacket,
     ) -> Option<Result<(), SendError>> {
-        Some(self.open_serial_ports.read().await.get(name)?.send(packet))
+        let tx_handle = self.open_serial_ports.read().await.get(name)?.tx_handle();
+
+        Some(tx_handle.send(packet).await)
+    }
+
+    /// Same as [`Self::send_to_open_serial_port`], but waits until the `packet` is written to the serial port.
+    ///
+    /// The ack of the `packet` is replaced. See [`CoreOutgoingPacket::ack`].
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`].
+    pub async fn send_to_open_serial_port_acknowledged(
+        &self,
+        name: &str,
+        mut packet: CoreOutgoingPacket,
+    ) -> Result<CoreWriteAck, CoreSendAcknowledgedError> {
+        let (ack_tx, ack_rx) = CoreWriteAckSender::new();
+
+        packet.ack = Some(ack_tx);
+
+        self.send_to_open_serial_port(name, packet)
+            .await
+            .ok_or(CoreSendAcknowledgedError::NotOpen)??;
+
+        Ok(ack_rx
+            .await
+            .map_err(|_| CoreSendAcknowledgedError::NotWritten)??)
+    }
+
+    /// Sends the `packet` to the open serial port and waits for the first incoming packet of the same port with a line matching the `pattern`.
+    ///
+    /// Packets are observed through [`Self::subscribe_packets`], so they still reach the channel returned by [`CoreSerialState::open_serial_port`].
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`].
+    pub async fn send_and_expect(
+        &self,
+        name: &str,
+        packet: CoreOutgoingPacket,
+        pattern: &CoreExpectPattern,
+        timeout: std::time::Duration,
+    ) -> Result<CorePacket, CoreSendAndExpectError> {
+        // Subscribe before sending, so that a fast response is not missed.
+        let mut packets_rx = self.subscribe_packets();
+
+        self.send_to_open_serial_port(name, packet)
+            .await
+            .ok_or(CoreSendAndExpectError::NotOpen)??;
+
+        let expect = async {
+            loop {
+                let packet = match packets_rx.recv().await {
+                    Ok(packet) => packet,
+                    Err(tokio::sync::broadcast::error::RecvError::Lagged(skipped)) => {
+                        tracing::warn!(name=%name, skipped, "Missed packets while expecting");
+
+                        continue;
+                    }
+                    // The sender lives as long as the state.
+                    Err(tokio::sync::broadcast::error::RecvError::Closed) => {
+                        std::future::pending().await
+                    }
+                };
+
+                if packet.port_name != name {
+                    continue;
+                }
+
+                if let CorePacketDirection::Incoming(incoming) = &packet.packet_direction {
+                    if pattern.matches(&incoming.line) {
+                        return packet;
+                    }
+                }
+            }
+        };
+
+        tokio::time::timeout(timeout, expect)
+            .await
+            .map_err(|_| CoreSendAndExpectError::Timeout)
     }
 
+    /// Waits for room in the write queues of all serial ports without holding the lock.
+    ///
     /// ## Locks
     ///
     /// - Read: [`Self::open_serial_ports`].
     pub async fn send_to_all_open_serial_ports(&self, packet: CoreOutgoingPacket) {
-        self.open_serial_ports
+        let tx_handles = self
+            .open_serial_ports
             .read()
             .await
             .values()
-            .for_each(|port| {
-                // Cheap clone
-                let _ = port.send(packet.clone());
-            })
+            .map(|port| port.tx_handle())
+            .collect::<Vec<_>>();
+
+        futures::future::join_all(tx_handles.iter().map(|tx_handle| {
+            // Cheap clone
+            tx_handle.send(packet.clone())
+        }))
+        .await;
     }
 
     #[cfg(feature = "subscriptions")]
@@ -319,16 +613,50 @@ impl StateInner {
     /// - `from` will send data to `to`.
     /// - `to` will receive data from `from`.
     ///
+    /// Replaces the `options` if `to` is already subscribed to `from`.
+    ///
+    /// Fails if `to` is subscribed to `from` as a direction of a bridge. See [`Self::remove_bridge`].
+    /// Fails if the subscription would create a cycle, unless `allow_cycle` is set.
+    /// Bytes are never forwarded back to a serial port they were read from, even if cycles are allowed.
+    /// Echoes of forwarded bytes are recognized as well, unless disabled with [`CoreSubscriptionOptions::echo_guard`].
+    ///
     /// ## Locks
     ///
     /// - Write: [`Self::subscriptions`].
     /// - Read: [`Self::open_serial_ports`].
     #[cfg(feature = "subscriptions")]
-    pub async fn subscribe(&self, from: &str, to: &str) {
-        tracing::debug!(%from, %to, "Subscribing");
+    pub async fn subscribe(
+        &self,
+        from: &str,
+        to: &str,
+        options: CoreSubscriptionOptions,
+        allow_cycle: bool,
+    ) -> Result<(), CoreSubscribeError> {
+        tracing::debug!(%from, %to, ?options, allow_cycle, "Subscribing");
+
+        let CoreSubscriptionOptions {
+            filter,
+            mode,
+            rate_limit,
+            echo_guard,
+        } = options;
 
         let mut subscriptions = self.subscriptions.write().await;
 
+        let existing = subscriptions
+            .get(from)
+            .and_then(|subscribers| subscribers.get(to));
+
+        if existing.map_or(false, |subscription| subscription.bridge) {
+            return Err(CoreSubscribeError::Bridge);
+        }
+
+        if existing.is_none() && !allow_cycle {
+            if let Some(cycle) = find_cycle(&subscriptions, from, to) {
+                return Err(CoreSubscribeError::Cycle(cycle));
+            }
+        }
+
         let tx_handle = self
             .open_serial_ports
             .read()
@@ -336,120 +664,1082 @@ impl StateInner {
             .get(to)
             .map(|port| port.tx_handle());
 
-        subscriptions
-            .entry(from.to_string())
-            .or_default()
-            .insert(to.to_string(), tx_handle);
+        let subscribers = subscriptions.entry(from.to_string()).or_default();
+
+        // The frames waiting in the forwarder of a replaced subscription are forwarded first.
+        let (counters, forwarder) = match subscribers.get(to) {
+            Some(subscription) => {
+                subscription.forwarder.set_rate_limit(rate_limit);
+
+                (
+                    subscription.counters.clone(),
+                    subscription.forwarder.clone(),
+                )
+            }
+            None => (Default::default(), SubscriptionForwarder::new(rate_limit)),
+        };
+
+        subscribers.insert(
+            to.to_string(),
+            Subscription {
+                tx_handle,
+                filter,
+                mode,
+                bridge: false,
+                echo_guard,
+                guards_echo: false,
+                counters,
+                forwarder,
+            },
+        );
+
+        update_echo_guards(&mut subscriptions);
+
+        Ok(())
     }
 
-    /// `to` is unsubscribed from `from`.
+    /// Replaces the filter of the subscription of `to` to `from` without resubscribing.
     ///
-    /// - `from` will no longer send data to `to`.
-    /// - `to` will no longer receive data from `from`.
+    /// - `Some(())` => Ok.
+    /// - `None` => Subscription not found.
     ///
     /// ## Locks
     ///
     /// - Write: [`Self::subscriptions`].
     #[cfg(feature = "subscriptions")]
-    pub async fn unsubscribe(&self, from: &str, to: &str) {
-        tracing::debug!(%from, %to, "Unsubscribing");
-
-        let mut subscriptions = self.subscriptions.write().await;
+    pub async fn set_subscription_filter(
+        &self,
+        from: &str,
+        to: &str,
+        filter: Option<CoreSubscriptionFilter>,
+    ) -> Option<()> {
+        tracing::debug!(%from, %to, ?filter, "Setting subscription filter");
 
-        subscriptions
+        self.subscriptions
+            .write()
+            .await
             .get_mut(from)
-            .and_then(|tx_handles| tx_handles.remove(to));
+            .and_then(|subscribers| subscribers.get_mut(to))
+            .map(|subscription| subscription.filter = filter)
     }
 
+    /// Replaces the rate limit of the subscription of `to` to `from` without resubscribing.
+    ///
+    /// Frames waiting for the replaced rate limit are still forwarded, before the frames limited by the new one.
+    ///
     /// - `Some(())` => Ok.
-    /// - `None` => Port not found.
+    /// - `None` => Subscription not found.
     ///
     /// ## Locks
     ///
-    /// - Read: [`Self::open_serial_ports`].
-    pub async fn toggle_read_state(&self, name: &str) -> Option<()> {
-        tracing::debug!(name=%name, "Toggling read state");
+    /// - Write: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_rate_limit(
+        &self,
+        from: &str,
+        to: &str,
+        rate_limit: CoreSubscriptionRateLimit,
+    ) -> Option<()> {
+        tracing::debug!(%from, %to, ?rate_limit, "Setting subscription rate limit");
 
-        return self.open_serial_ports.read().await.get(name).map(|port| {
-            port.set_read_state(port.read_state().toggle());
-        });
+        self.subscriptions
+            .write()
+            .await
+            .get_mut(from)
+            .and_then(|subscribers| subscribers.get_mut(to))
+            .map(|subscription| subscription.forwarder.set_rate_limit(rate_limit))
     }
-}
 
-impl CoreSerialState {
+    /// - `Some(true)` => `to` is subscribed to `from` as a direction of a bridge.
+    /// - `Some(false)` => `to` is subscribed to `from`.
+    /// - `None` => Subscription not found.
+    ///
     /// ## Locks
     ///
-    /// - Write: [`StateInner::open_serial_ports`]. Inherited from [`StateInner::add_open_serial_port`].
-    #[cfg_attr(
-        feature = "subscriptions",
-        doc = "
-- Write: [`StateInner::subscriptions`]. Inherited from [`StateInner::add_open_serial_port`].
-    "
-    )]
-    pub async fn open_serial_port<'a>(
-        &'a self,
-        name: &'a str,
-        options: CoreOpenSerialPortOptions,
-    ) -> Result<MPSCUnboundedReceiver<Result<CorePacket, CorePacketError>>, CoreOpenSerialPortError>
-    {
-        tracing::debug!(?options, "Opening serial port");
+    /// - Read: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn is_bridge(&self, from: &str, to: &str) -> Option<bool> {
+        self.subscriptions
+            .read()
+            .await
+            .get(from)
+            .and_then(|subscribers| subscribers.get(to))
+            .map(|subscription| subscription.bridge)
+    }
 
-        let port_to_open_name = self
-            .is_port_closed(name)
-            .await?
-            .ok_or(CoreOpenSerialPortError::NotFound)?
-            .then_some(name)
-            .ok_or(CoreOpenSerialPortError::AlreadyOpen)?;
+    /// Enables or disables the echo guard of the subscription of `to` to `from` without resubscribing. See [`CoreSubscription::echo_guard`].
+    ///
+    /// - `Some(())` => Ok.
+    /// - `None` => Subscription not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_echo_guard(
+        &self,
+        from: &str,
+        to: &str,
+        echo_guard: bool,
+    ) -> Option<()> {
+        tracing::debug!(%from, %to, echo_guard, "Setting subscription echo guard");
 
-        let (initial_read_state, options) = options.split_into_read_state_and_manager_options();
+        let mut subscriptions = self.subscriptions.write().await;
 
-        let port = self.manager.open_port(port_to_open_name, options)?;
+        subscriptions
+            .get_mut(from)
+            .and_then(|subscribers| subscribers.get_mut(to))
+            .map(|subscription| subscription.echo_guard = echo_guard)?;
 
-        let (port_read, port_write) = tokio::io::split(port);
-        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel::<CoreOutgoingPacket>();
+        update_echo_guards(&mut subscriptions);
 
-        let (packet_tx, packet_rx) =
-            tokio::sync::mpsc::unbounded_channel::<Result<CorePacket, CorePacketError>>();
+        Some(())
+    }
 
-        let cancellation_token = CancellationToken::new();
+    /// Returns the metrics of the subscription of `to` to `from`, including a direction of a bridge.
+    ///
+    /// - `Some(metrics)` => Ok.
+    /// - `None` => Subscription not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn subscription_metrics(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Option<CoreSubscriptionMetrics> {
+        self.subscriptions
+            .read()
+            .await
+            .get(from)
+            .and_then(|subscribers| subscribers.get(to))
+            .map(|subscription| subscription.counters.metrics())
+    }
 
-        let mut framed_read_bytes_port = FramedRead::new(port_read, BytesCodec::new());
-        let mut framed_write_bytes_port = FramedWrite::new(port_write, BytesCodec::new());
+    /// Replaces the mode of the subscription of `to` to `from` without resubscribing.
+    ///
+    /// - `Some(())` => Ok.
+    /// - `None` => Subscription not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_mode(
+        &self,
+        from: &str,
+        to: &str,
+        mode: CoreSubscriptionMode,
+    ) -> Option<()> {
+        tracing::debug!(%from, %to, ?mode, "Setting subscription mode");
 
-        let (read_state_tx, mut read_state_rx) = tokio::sync::watch::channel(initial_read_state);
+        self.subscriptions
+            .write()
+            .await
+            .get_mut(from)
+            .and_then(|subscribers| subscribers.get_mut(to))
+            .map(|subscription| subscription.mode = mode)
+    }
 
-        self.add_open_serial_port(CoreOpenSerialPort::new(
-            CoreSerialPort::new(name.into()),
-            tx,
-            cancellation_token.clone(),
-            read_state_tx,
-        ))
-        .await;
+    /// `to` is unsubscribed from `from`.
+    ///
+    /// - `from` will no longer send data to `to`.
+    /// - `to` will no longer receive data from `from`.
+    ///
+    /// A direction of a bridge is not removed. See [`Self::remove_bridge`].
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn unsubscribe(&self, from: &str, to: &str) {
+        tracing::debug!(%from, %to, "Unsubscribing");
 
-        #[cfg(feature = "subscriptions")]
-        let subscriptions = self.subscriptions();
-        let read_app_state = self.clone();
-        let read_cancellation_token = cancellation_token.clone();
-        let read_name = name.to_owned();
-        let read_packet_tx = packet_tx.clone();
+        let mut subscriptions = self.subscriptions.write().await;
 
-        tokio::spawn(async move {
-            let mut lines_codec = LinesCodec::new();
-            let mut lines_bytes = BytesMut::new();
+        let Some(subscribers) = subscriptions.get_mut(from) else {
+            return;
+        };
 
-            // Trigger the initial read state.
-            read_state_rx.mark_changed();
+        if subscribers
+            .get(to)
+            .map_or(false, |subscription| subscription.bridge)
+        {
+            tracing::debug!(%from, %to, "Not unsubscribing a direction of a bridge");
 
-            loop {
-                tracing::debug!(target: "serial_core::serial::read:.watch", name=%read_name, "Waiting for read state change");
-                tokio::select! {
-                    changed_result = read_state_rx.changed() => {
-                        match changed_result {
-                            Ok(_) => {
-                                let read_state = *read_state_rx.borrow();
-                                tracing::debug!(target: "serial_core::serial::read::watch", name=%read_name, ?read_state, "Read state changed");
+            return;
+        }
 
-                                if read_state.is_stop() {
+        subscribers.remove(to);
+
+        update_echo_guards(&mut subscriptions);
+    }
+
+    /// Bridges `a` and `b`. Data read from `a` is sent to `b` and data read from `b` is sent to `a`.
+    ///
+    /// Both directions are created at once, replacing an existing bridge between `a` and `b`.
+    /// Fails if `a` and `b` are subscribed to each other in either direction, so that subscriptions are never replaced.
+    /// Like subscriptions, bridges can exist before the serial ports are open.
+    ///
+    /// Bridges are kept in memory only.
+    ///
+    /// With `echo_guard` set, bytes read back from a serial port right after they were forwarded to it are not forwarded back,
+    /// which stops two echoing devices from forwarding the same bytes forever. An identical reply is then dropped as well.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::subscriptions`].
+    /// - Read: [`Self::open_serial_ports`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn create_bridge(
+        &self,
+        a: &str,
+        b: &str,
+        echo_guard: bool,
+    ) -> Result<(), CoreBridgeError> {
+        tracing::debug!(%a, %b, echo_guard, "Creating bridge");
+
+        if a == b {
+            return Err(CoreBridgeError::SamePort);
+        }
+
+        let mut subscriptions = self.subscriptions.write().await;
+
+        for (from, to) in [(a, b), (b, a)] {
+            let subscribed = subscriptions
+                .get(from)
+                .and_then(|subscribers| subscribers.get(to))
+                .map_or(false, |subscription| !subscription.bridge);
+
+            if subscribed {
+                return Err(CoreBridgeError::Subscribed {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                });
+            }
+        }
+
+        let open_serial_ports = self.open_serial_ports.read().await;
+
+        for (from, to) in [(a, b), (b, a)] {
+            let tx_handle = open_serial_ports.get(to).map(|port| port.tx_handle());
+
+            subscriptions.entry(from.to_string()).or_default().insert(
+                to.to_string(),
+                Subscription {
+                    tx_handle,
+                    filter: None,
+                    mode: CoreSubscriptionMode::Raw,
+                    bridge: true,
+                    echo_guard,
+                    guards_echo: false,
+                    counters: Default::default(),
+                    forwarder: SubscriptionForwarder::new(Default::default()),
+                },
+            );
+        }
+
+        update_echo_guards(&mut subscriptions);
+
+        Ok(())
+    }
+
+    /// Removes both directions of the bridge between `a` and `b`.
+    ///
+    /// - `Some(())` => Ok.
+    /// - `None` => Bridge not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::subscriptions`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn remove_bridge(&self, a: &str, b: &str) -> Option<()> {
+        tracing::debug!(%a, %b, "Removing bridge");
+
+        let mut subscriptions = self.subscriptions.write().await;
+
+        let is_bridge = |subscriptions: &Subscriptions, from: &str, to: &str| {
+            subscriptions
+                .get(from)
+                .and_then(|subscribers| subscribers.get(to))
+                .map_or(false, |subscription| subscription.bridge)
+        };
+
+        if !is_bridge(&subscriptions, a, b) || !is_bridge(&subscriptions, b, a) {
+            return None;
+        }
+
+        for (from, to) in [(a, b), (b, a)] {
+            subscriptions
+                .get_mut(from)
+                .and_then(|subscribers| subscribers.remove(to));
+        }
+
+        update_echo_guards(&mut subscriptions);
+
+        Some(())
+    }
+
+    /// Receives every packet read from or written to any open serial port from now on.
+    ///
+    /// Unlike the channel returned by [`CoreSerialState::open_serial_port`], this does not receive errors.
+    pub fn subscribe_packets(&self) -> BroadcastReceiver<CorePacket> {
+        self.packets_tx.subscribe()
+    }
+
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`].
+    pub async fn open_serial_port_names(&self) -> Vec<String> {
+        self.open_serial_ports
+            .read()
+            .await
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`].
+    async fn is_port_open(&self, name: &str) -> bool {
+        self.open_serial_ports.read().await.contains_key(name)
+    }
+
+    /// See [`CoreOpenSerialPort::take_exclusive`].
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`].
+    async fn take_exclusive_open_serial_port(
+        &self,
+        name: &str,
+        packet_origin: CorePacketOrigin,
+    ) -> Result<CoreExclusiveSerialPort, CoreStartXmodemError> {
+        self.open_serial_ports
+            .read()
+            .await
+            .get(name)
+            .ok_or(CoreStartXmodemError::NotOpen)?
+            .take_exclusive(packet_origin)
+            .ok_or(CoreStartXmodemError::Exclusive)
+    }
+
+    /// ## Locks
+    ///
+    /// - Read: [`Self::transfers`].
+    pub async fn transfers(&self) -> Vec<CoreTransfer> {
+        self.transfers
+            .read()
+            .await
+            .values()
+            .map(|handle| handle.transfer().clone())
+            .collect()
+    }
+
+    /// - `Some(())` => Ok.
+    /// - `None` => Transfer not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::transfers`].
+    pub async fn cancel_transfer(&self, id: u64) -> Option<()> {
+        self.transfers
+            .read()
+            .await
+            .get(&id)
+            .map(CoreTransferHandle::cancel)
+    }
+
+    /// ## Locks
+    ///
+    /// - Write: [`Self::transfers`].
+    async fn add_transfer(
+        &self,
+        port_name: &str,
+        kind: CoreTransferKind,
+    ) -> (CoreTransfer, CancellationToken) {
+        let transfer = CoreTransfer {
+            id: self.next_transfer_id.fetch_add(1, Ordering::Relaxed),
+            port_name: port_name.to_string(),
+            kind,
+        };
+        let cancellation_token = CancellationToken::new();
+
+        tracing::debug!(?transfer, "Adding transfer");
+
+        self.transfers.write().await.insert(
+            transfer.id,
+            CoreTransferHandle::new(transfer.clone(), cancellation_token.clone()),
+        );
+
+        (transfer, cancellation_token)
+    }
+
+    /// ## Locks
+    ///
+    /// - Write: [`Self::transfers`].
+    async fn remove_transfer(&self, id: u64) {
+        tracing::debug!(id, "Removing transfer");
+
+        self.transfers.write().await.remove(&id);
+    }
+
+    /// Reads the `file` packet by packet as split by the `pacing` and sends the packets.
+    ///
+    /// Waits for each packet to be written, so that the progress reflects the bytes written to the serial port.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`]. Inherited from [`Self::send_to_open_serial_port_acknowledged`].
+    async fn send_file_packets(
+        &self,
+        name: &str,
+        path: &str,
+        file: tokio::fs::File,
+        pacing: CoreSendFilePacing,
+        event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+    ) -> Result<(), CoreTransferError> {
+        let bytes_total = file
+            .metadata()
+            .await
+            .map_err(CoreTransferError::Read)?
+            .len();
+        let mut bytes_sent = 0;
+        let delay = pacing.delay();
+        let mut reader = tokio::io::BufReader::new(file);
+
+        while let Some(bytes) = pacing
+            .read_packet(&mut reader)
+            .await
+            .map_err(CoreTransferError::Read)?
+        {
+            bytes_sent += bytes.len() as u64;
+
+            let packet = CoreOutgoingPacket {
+                bytes,
+                packet_origin: CorePacketOrigin::File(CoreFilePacketOrigin {
+                    path: path.to_string(),
+                }),
+                ack: None,
+            };
+
+            self.send_to_open_serial_port_acknowledged(name, packet)
+                .await?;
+
+            let _ = event_tx.send(CoreTransferEvent::Progress {
+                bytes_transferred: bytes_sent,
+                bytes_total: Some(bytes_total),
+            });
+
+            if !delay.is_zero() {
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// ## Locks
+    ///
+    /// - Read: [`Self::periodic_jobs`].
+    pub async fn periodic_jobs(&self) -> Vec<CorePeriodicJob> {
+        self.periodic_jobs
+            .read()
+            .await
+            .values()
+            .map(|handle| handle.job().clone())
+            .collect()
+    }
+
+    /// Removes and cancels the periodic job.
+    ///
+    /// - `Some(CorePeriodicJob)` => Ok.
+    /// - `None` => Periodic job not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::periodic_jobs`].
+    pub async fn stop_periodic_job(&self, id: u64) -> Option<CorePeriodicJob> {
+        tracing::debug!(id, "Stopping periodic job");
+
+        self.periodic_jobs
+            .write()
+            .await
+            .remove(&id)
+            .map(CorePeriodicJobHandle::cancelled)
+    }
+
+    /// Removes and cancels all periodic jobs of the serial port.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::periodic_jobs`].
+    async fn stop_periodic_jobs_of_port(&self, name: &str) {
+        tracing::debug!(%name, "Stopping periodic jobs of serial port");
+
+        let mut periodic_jobs = self.periodic_jobs.write().await;
+
+        let ids = periodic_jobs
+            .values()
+            .filter(|handle| handle.job().port_name == name)
+            .map(|handle| handle.job().id)
+            .collect::<Vec<_>>();
+
+        for id in ids {
+            periodic_jobs
+                .remove(&id)
+                .map(CorePeriodicJobHandle::cancelled);
+        }
+    }
+
+    /// - `Some(())` => Ok.
+    /// - `None` => Port not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::open_serial_ports`].
+    pub async fn toggle_read_state(&self, name: &str) -> Option<()> {
+        tracing::debug!(name=%name, "Toggling read state");
+
+        return self.open_serial_ports.read().await.get(name).map(|port| {
+            port.set_read_state(port.read_state().toggle());
+        });
+    }
+
+    /// Ordered by id.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`Self::auto_responders`].
+    pub async fn auto_responders(&self) -> Vec<(i32, CoreAutoResponder)> {
+        let mut auto_responders = self
+            .auto_responders
+            .read()
+            .await
+            .iter()
+            .map(|(id, auto_responder)| (*id, auto_responder.clone()))
+            .collect::<Vec<_>>();
+
+        auto_responders.sort_by_key(|(id, _)| *id);
+
+        auto_responders
+    }
+
+    /// Adds or replaces the auto-responder with the given `id`.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::auto_responders`].
+    pub async fn set_auto_responder(&self, id: i32, auto_responder: CoreAutoResponder) {
+        tracing::debug!(id, ?auto_responder, "Setting auto-responder");
+
+        self.auto_responders
+            .write()
+            .await
+            .insert(id, auto_responder);
+    }
+
+    /// - `Some(CoreAutoResponder)` => Removed.
+    /// - `None` => Auto-responder not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::auto_responders`].
+    pub async fn remove_auto_responder(&self, id: i32) -> Option<CoreAutoResponder> {
+        tracing::debug!(id, "Removing auto-responder");
+
+        self.auto_responders.write().await.remove(&id)
+    }
+
+    /// - `Some(())` => Ok.
+    /// - `None` => Auto-responder not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::auto_responders`].
+    pub async fn set_auto_responder_enabled(&self, id: i32, enabled: bool) -> Option<()> {
+        tracing::debug!(id, enabled, "Setting auto-responder enabled");
+
+        self.auto_responders
+            .write()
+            .await
+            .get_mut(&id)
+            .map(|auto_responder| auto_responder.enabled = enabled)
+    }
+}
+
+impl CoreSerialState {
+    /// Sends the file at `path` to the open serial port through its write task.
+    ///
+    /// The file is read while sending. Progress is emitted once a packet is written to the serial port.
+    /// If an XMODEM or YMODEM transfer takes the serial port, the transfer ends with [`CoreTransferEvent::PortBusy`].
+    ///
+    /// Returns the id of the transfer, which can be cancelled with [`StateInner::cancel_transfer`],
+    /// and a channel emitting the progress of the transfer.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`StateInner::open_serial_ports`].
+    /// - Write: [`StateInner::transfers`]. Inherited from [`StateInner::add_transfer`].
+    pub async fn send_file(
+        &self,
+        name: &str,
+        path: &str,
+        pacing: CoreSendFilePacing,
+    ) -> Result<(u64, MPSCUnboundedReceiver<CoreTransferEvent>), CoreSendFileError> {
+        tracing::debug!(%name, %path, ?pacing, "Sending file");
+
+        if !self.is_port_open(name).await {
+            return Err(CoreSendFileError::NotOpen);
+        }
+
+        let file = tokio::fs::File::open(path).await?;
+
+        let kind = CoreTransferKind::File {
+            path: path.to_string(),
+        };
+
+        let state = self.clone();
+        let task_name = name.to_owned();
+        let path = path.to_owned();
+
+        let transfer = self
+            .spawn_transfer(name, kind, |event_tx, cancellation_token| async move {
+                tokio::select! {
+                    result = state.send_file_packets(&task_name, &path, file, pacing, &event_tx) => result.into(),
+                    _ = cancellation_token.cancelled() => CoreTransferEvent::Cancelled,
+                }
+            })
+            .await;
+
+        Ok(transfer)
+    }
+
+    /// Sends the file at `path` to the open serial port using the XMODEM or YMODEM `protocol`.
+    ///
+    /// Takes exclusive ownership of the open serial port until the transfer is done. See [`CoreOpenSerialPort::take_exclusive`].
+    ///
+    /// Returns the id of the transfer, which can be cancelled with [`StateInner::cancel_transfer`],
+    /// and a channel emitting the progress of the transfer.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`StateInner::open_serial_ports`]. Inherited from [`StateInner::take_exclusive_open_serial_port`].
+    /// - Write: [`StateInner::transfers`]. Inherited from [`StateInner::add_transfer`].
+    pub async fn xmodem_send(
+        &self,
+        name: &str,
+        path: &str,
+        protocol: CoreXmodemProtocol,
+    ) -> Result<(u64, MPSCUnboundedReceiver<CoreTransferEvent>), CoreStartXmodemError> {
+        tracing::debug!(%name, %path, %protocol, "Sending file via XMODEM");
+
+        let mut file = tokio::fs::File::open(path).await?;
+        let size = file.metadata().await?.len();
+
+        let mut port = self
+            .take_exclusive_open_serial_port(
+                name,
+                CorePacketOrigin::Transfer(CoreTransferPacketOrigin {
+                    path: path.to_string(),
+                }),
+            )
+            .await?;
+
+        let file_name = Path::new(path)
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let kind = CoreTransferKind::Xmodem {
+            protocol,
+            direction: CoreTransferDirection::Send,
+            path: path.to_string(),
+        };
+
+        let transfer = self
+            .spawn_transfer(name, kind, move |event_tx, cancellation_token| async move {
+                let result = tokio::select! {
+                    result = xmodem::send(&mut port, protocol, &file_name, &mut file, size, &event_tx) => result,
+                    _ = cancellation_token.cancelled() => {
+                        xmodem::cancel(&port).await;
+
+                        return CoreTransferEvent::Cancelled;
+                    }
+                };
+
+                result.map_err(CoreTransferError::from).into()
+            })
+            .await;
+
+        Ok(transfer)
+    }
+
+    /// Receives a file from the open serial port using the XMODEM or YMODEM `protocol` and writes it to `path`.
+    ///
+    /// If `path` is a directory, the file name transmitted by YMODEM is appended.
+    ///
+    /// Takes exclusive ownership of the open serial port until the transfer is done. See [`CoreOpenSerialPort::take_exclusive`].
+    ///
+    /// Returns the id of the transfer, which can be cancelled with [`StateInner::cancel_transfer`],
+    /// and a channel emitting the progress of the transfer.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`StateInner::open_serial_ports`]. Inherited from [`StateInner::take_exclusive_open_serial_port`].
+    /// - Write: [`StateInner::transfers`]. Inherited from [`StateInner::add_transfer`].
+    pub async fn xmodem_receive(
+        &self,
+        name: &str,
+        path: &str,
+        protocol: CoreXmodemProtocol,
+    ) -> Result<(u64, MPSCUnboundedReceiver<CoreTransferEvent>), CoreStartXmodemError> {
+        tracing::debug!(%name, %path, %protocol, "Receiving file via XMODEM");
+
+        let mut port = self
+            .take_exclusive_open_serial_port(
+                name,
+                CorePacketOrigin::Transfer(CoreTransferPacketOrigin {
+                    path: path.to_string(),
+                }),
+            )
+            .await?;
+
+        let kind = CoreTransferKind::Xmodem {
+            protocol,
+            direction: CoreTransferDirection::Receive,
+            path: path.to_string(),
+        };
+
+        let path = path.to_owned();
+
+        let transfer = self
+            .spawn_transfer(name, kind, move |event_tx, cancellation_token| async move {
+                let result = tokio::select! {
+                    result = xmodem::receive(&mut port, protocol, &event_tx) => result,
+                    _ = cancellation_token.cancelled() => {
+                        xmodem::cancel(&port).await;
+
+                        return CoreTransferEvent::Cancelled;
+                    }
+                };
+
+                // Hand the serial port back before writing the file.
+                drop(port);
+
+                let file = match result {
+                    Ok(file) => file,
+                    Err(err) => return Err(CoreTransferError::from(err)).into(),
+                };
+
+                let is_dir = tokio::fs::metadata(&path)
+                    .await
+                    .map(|metadata| metadata.is_dir())
+                    .unwrap_or(false);
+
+                let path = match file.name {
+                    Some(file_name) if is_dir => Path::new(&path).join(file_name),
+                    _ => Path::new(&path).to_path_buf(),
+                };
+
+                tokio::fs::write(&path, &file.data)
+                    .await
+                    .map_err(CoreTransferError::Write)
+                    .into()
+            })
+            .await;
+
+        Ok(transfer)
+    }
+
+    /// Starts a job sending `bytes` to the open serial port on the given `schedule`.
+    ///
+    /// The job keeps running while the read state is toggled and while a transfer owns the serial port, skipping the sends in the latter case.
+    /// It is stopped with [`StateInner::stop_periodic_job`] or when the serial port is closed.
+    ///
+    /// Returns the id of the job.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`StateInner::open_serial_ports`].
+    /// - Write: [`StateInner::periodic_jobs`].
+    pub async fn start_periodic_job(
+        &self,
+        name: &str,
+        bytes: Bytes,
+        schedule: CorePeriodicSchedule,
+    ) -> Result<u64, CoreStartPeriodicJobError> {
+        tracing::debug!(%name, ?bytes, ?schedule, "Starting periodic job");
+
+        let mut ticker = periodic::Ticker::new(&schedule)?;
+
+        if !self.is_port_open(name).await {
+            return Err(CoreStartPeriodicJobError::NotOpen);
+        }
+
+        let job = CorePeriodicJob {
+            id: self.next_periodic_job_id.fetch_add(1, Ordering::Relaxed),
+            port_name: name.to_string(),
+            bytes,
+            schedule,
+        };
+        let cancellation_token = CancellationToken::new();
+
+        self.periodic_jobs.write().await.insert(
+            job.id,
+            CorePeriodicJobHandle::new(job.clone(), cancellation_token.clone()),
+        );
+
+        let state = self.clone();
+        let id = job.id;
+
+        tokio::spawn(async move {
+            tracing::debug!(id, name=%job.port_name, "Periodic job task started");
+
+            loop {
+                tokio::select! {
+                    tick = ticker.tick() => {
+                        if tick.is_none() {
+                            tracing::debug!(id, name=%job.port_name, "Schedule has no upcoming times");
+
+                            state.stop_periodic_job(id).await;
+
+                            break;
+                        }
+
+                        let packet = CoreOutgoingPacket {
+                            bytes: job.bytes.clone(),
+                            packet_origin: CorePacketOrigin::Periodic(CorePeriodicPacketOrigin { job_id: id }),
+                            ack: None,
+                        };
+
+                        match state.send_to_open_serial_port(&job.port_name, packet).await {
+                            Some(Ok(_)) => {}
+                            Some(Err(SendError::Exclusive)) => {
+                                tracing::trace!(id, name=%job.port_name, "Skipping send. Port is exclusively owned");
+                            }
+                            Some(Err(SendError::Send(WriteQueueError::Dropped))) => {
+                                tracing::trace!(id, name=%job.port_name, "Skipping send. Write queue is full");
+                            }
+                            Some(Err(err)) => {
+                                tracing::error!(id, name=%job.port_name, %err, "Periodic job failed to send");
+
+                                state.stop_periodic_job(id).await;
+
+                                break;
+                            }
+                            None => {
+                                // The port was closed while the job was being started.
+                                state.stop_periodic_job(id).await;
+
+                                break;
+                            }
+                        }
+                    },
+                    _ = cancellation_token.cancelled() => break,
+                }
+            }
+
+            tracing::debug!(id, name=%job.port_name, "Periodic job task terminated");
+        });
+
+        Ok(id)
+    }
+
+    /// Sends the responses of the auto-responders of the serial port `name` matching the `line`.
+    ///
+    /// Every response is sent by its own task after the delay of its auto-responder, so replying never blocks reading.
+    ///
+    /// ## Locks
+    ///
+    /// - Read: [`StateInner::auto_responders`].
+    async fn spawn_auto_responses(&self, name: &str, line: &[u8]) {
+        let responses = self
+            .auto_responders
+            .read()
+            .await
+            .values()
+            .filter(|auto_responder| auto_responder.port == name)
+            .filter_map(|auto_responder| {
+                let bytes = auto_responder.respond(line)?;
+
+                Some((
+                    bytes,
+                    auto_responder.name.clone(),
+                    auto_responder.target().to_string(),
+                    auto_responder.delay,
+                ))
+            })
+            .collect::<Vec<_>>();
+
+        for (bytes, auto_responder_name, target, delay) in responses {
+            let state = self.clone();
+
+            tokio::spawn(async move {
+                if !delay.is_zero() {
+                    tokio::time::sleep(delay).await;
+                }
+
+                tracing::trace!(name=%auto_responder_name, %target, ?bytes, "Sending auto-response");
+
+                let packet = CoreOutgoingPacket {
+                    bytes,
+                    packet_origin: CorePacketOrigin::AutoResponder(CoreAutoResponderPacketOrigin {
+                        name: auto_responder_name.clone(),
+                    }),
+                    ack: None,
+                };
+
+                match state.send_to_open_serial_port(&target, packet).await {
+                    Some(Ok(())) => {}
+                    Some(Err(err)) => {
+                        tracing::warn!(name=%auto_responder_name, %target, %err, "Failed to send auto-response");
+                    }
+                    None => {
+                        tracing::warn!(name=%auto_responder_name, %target, "Auto-response target not open");
+                    }
+                }
+            });
+        }
+    }
+
+    /// Registers a transfer and spawns a task running the future returned by `run`.
+    ///
+    /// `run` receives the sender of the transfer events and the cancellation token of the transfer.
+    /// The future resolves to the last event, which is sent after the transfer is removed.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`StateInner::transfers`]. Inherited from [`StateInner::add_transfer`].
+    async fn spawn_transfer<F, Fut>(
+        &self,
+        name: &str,
+        kind: CoreTransferKind,
+        run: F,
+    ) -> (u64, MPSCUnboundedReceiver<CoreTransferEvent>)
+    where
+        F: FnOnce(MPSCUnboundedSender<CoreTransferEvent>, CancellationToken) -> Fut,
+        Fut: Future<Output = CoreTransferEvent> + Send + 'static,
+    {
+        let (transfer, cancellation_token) = self.add_transfer(name, kind).await;
+
+        let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel::<CoreTransferEvent>();
+
+        let state = self.clone();
+        let task = run(event_tx.clone(), cancellation_token);
+
+        tokio::spawn(async move {
+            tracing::debug!(id=%transfer.id, name=%transfer.port_name, kind=?transfer.kind, "Transfer task started");
+
+            let event = task.await;
+
+            if let CoreTransferEvent::Failed(err) = &event {
+                tracing::error!(id=%transfer.id, name=%transfer.port_name, %err, "Transfer failed");
+            }
+
+            state.remove_transfer(transfer.id).await;
+
+            let _ = event_tx.send(event);
+
+            tracing::debug!(id=%transfer.id, name=%transfer.port_name, "Transfer task terminated");
+        });
+
+        (transfer.id, event_rx)
+    }
+
+    /// ## Locks
+    ///
+    /// - Write: [`StateInner::open_serial_ports`]. Inherited from [`StateInner::add_open_serial_port`].
+    #[cfg_attr(
+        feature = "subscriptions",
+        doc = "
+- Write: [`StateInner::subscriptions`]. Inherited from [`StateInner::add_open_serial_port`].
+    "
+    )]
+    pub async fn open_serial_port<'a>(
+        &'a self,
+        name: &'a str,
+        options: CoreOpenSerialPortOptions,
+    ) -> Result<MPSCUnboundedReceiver<Result<CorePacket, CorePacketError>>, CoreOpenSerialPortError>
+    {
+        tracing::debug!(?options, "Opening serial port");
+
+        let port_to_open_name = self
+            .is_port_closed(name)
+            .await?
+            .ok_or(CoreOpenSerialPortError::NotFound)?
+            .then_some(name)
+            .ok_or(CoreOpenSerialPortError::AlreadyOpen)?;
+
+        let (state_options, options) = options.split_into_state_and_manager_options();
+
+        let port = self.manager.open_port(port_to_open_name, options)?;
+
+        let (port_read, port_write) = tokio::io::split(port);
+        let (tx, mut rx) = write_queue(state_options.write_queue);
+        let (exclusive_tx, mut exclusive_rx) =
+            tokio::sync::mpsc::unbounded_channel::<CoreOutgoingPacket>();
+
+        let (packet_tx, packet_rx) =
+            tokio::sync::mpsc::unbounded_channel::<Result<CorePacket, CorePacketError>>();
+
+        let cancellation_token = CancellationToken::new();
+
+        let mut framed_read_bytes_port = FramedRead::new(port_read, BytesCodec::new());
+        let mut framed_write_bytes_port = FramedWrite::new(port_write, BytesCodec::new());
+
+        let (read_state_tx, mut read_state_rx) =
+            tokio::sync::watch::channel(state_options.initial_read_state);
+
+        let exclusive_slot = ExclusiveSlot::default();
+
+        self.add_open_serial_port(CoreOpenSerialPort::new(
+            CoreSerialPort::new(name.into()),
+            tx,
+            exclusive_tx,
+            cancellation_token.clone(),
+            read_state_tx,
+            state_options.outgoing_line_ending,
+            exclusive_slot.clone(),
+        ))
+        .await;
+
+        #[cfg(feature = "subscriptions")]
+        let echo_guard = Arc::new(EchoGuard::default());
+
+        #[cfg(feature = "subscriptions")]
+        let subscriptions = self.subscriptions();
+        #[cfg(feature = "subscriptions")]
+        let read_echo_guard = echo_guard.clone();
+        let read_app_state = self.clone();
+        let read_cancellation_token = cancellation_token.clone();
+        let read_name = name.to_owned();
+        let read_packet_tx = packet_tx.clone();
+        let read_packets_tx = self.packets_tx.clone();
+        let read_exclusive_slot = exclusive_slot.clone();
+
+        tokio::spawn(async move {
+            let mut lines_codec = LinesCodec::new();
+            let mut lines_bytes = BytesMut::new();
+            // Timestamp of the chunk containing the first byte of the line currently in `lines_bytes`.
+            let mut line_first_byte_timestamp_micros = 0;
+
+            // Trigger the initial read state.
+            read_state_rx.mark_changed();
+
+            loop {
+                tracing::debug!(target: "serial_core::serial::read:.watch", name=%read_name, "Waiting for read state change");
+                tokio::select! {
+                    changed_result = read_state_rx.changed() => {
+                        match changed_result {
+                            Ok(_) => {
+                                let read_state = *read_state_rx.borrow();
+                                tracing::debug!(target: "serial_core::serial::read::watch", name=%read_name, ?read_state, "Read state changed");
+
+                                if read_state.is_stop() {
 
                                     continue;
                                 }
@@ -460,24 +1750,64 @@ impl CoreSerialState {
                                         bytes = framed_read_bytes_port.next() => {
                                             match bytes {
                                                 Some(Ok(bytes)) => {
+                                                    let chunk_timestamp_micros = clock::now_micros();
+
                                                     tracing::trace!(target: "serial_core::serial::read::byte", name=%read_name, ?bytes, "Read");
 
+                                                    // Lines are neither decoded nor forwarded to subscribers while a transfer owns the port.
+                                                    if let Some(exclusive_read_tx) = read_exclusive_slot.read_tx() {
+                                                        let _ = exclusive_read_tx.send(bytes.freeze());
+
+                                                        continue;
+                                                    }
+
+                                                    // The serial ports the bytes were read from. More than this one if the bytes are an echo of forwarded bytes.
                                                     #[cfg(feature = "subscriptions")]
-                                                    if let Some(subscriptions) = subscriptions.read().await.get(&read_name){
-                                                        for (subscriber_name, tx_handle) in subscriptions {
-                                                            if let Some(tx_handle) = tx_handle {
-                                                                tracing::trace!(target: "serial_core::serial::read::byte::subscribe", name=%read_name, subscriber=%subscriber_name, "Sending bytes to subscriber");
-
-                                                                let outgoing_packet = CoreOutgoingPacket {
-                                                                    bytes: bytes.clone().into(),
-                                                                    packet_origin: CorePacketOrigin::Subscription(CoreSubscriptionPacketOrigin{ name: read_name.clone() }),
-                                                                };
-
-                                                                if let Err(err) = tx_handle.send(outgoing_packet) {
-                                                                    tracing::error!(target: "serial_core::serial::read::byte::subscribe", name=%read_name, subscriber=%subscriber_name, %err, "Failed to send bytes to subscriber");
-                                                                }
-                                                            }
+                                                    let path = {
+                                                        let mut path = read_echo_guard.take_echo(&bytes).unwrap_or_default();
+                                                        path.push(read_name.clone());
+                                                        path
+                                                    };
+
+                                                    // Cloned out of the lock, so forwarding does not hold it.
+                                                    #[cfg(feature = "subscriptions")]
+                                                    let (subscribers, line_subscribers): (Vec<_>, Vec<_>) = {
+                                                        let subscriptions = subscriptions.read().await;
+
+                                                        subscriptions.get(&read_name).map(|subscribers| {
+                                                            subscribers.iter().filter_map(|(subscriber_name, subscription)| {
+                                                                let line_forwarding = subscription.mode.line_forwarding();
+
+                                                                Some((subscriber_name.clone(), subscription.tx_handle.clone()?, subscription.filter.clone(), line_forwarding, subscription.guards_echo, subscription.counters.clone(), subscription.forwarder.clone()))
+                                                            }).collect::<Vec<_>>()
+                                                        }).unwrap_or_default()
+                                                    }.into_iter().filter(|(subscriber_name, _, _, _, _, _, _)| {
+                                                        // Loop protection.
+                                                        let traversed = path.contains(subscriber_name);
+
+                                                        if traversed {
+                                                            tracing::debug!(target: "serial_core::serial::read::byte::subscribe", name=%read_name, subscriber=%subscriber_name, ?path, "Not sending bytes back to a serial port they already traversed");
                                                         }
+
+                                                        !traversed
+                                                    }).partition(|(_, _, _, line_forwarding, _, _, _)| line_forwarding.is_none());
+
+                                                    #[cfg(feature = "subscriptions")]
+                                                    for (subscriber_name, tx_handle, _, _, echo_guard, counters, forwarder) in subscribers {
+                                                        tracing::trace!(target: "serial_core::serial::read::byte::subscribe", name=%read_name, subscriber=%subscriber_name, "Sending bytes to subscriber");
+
+                                                        let outgoing_packet = CoreOutgoingPacket {
+                                                            bytes: bytes.clone().into(),
+                                                            packet_origin: CorePacketOrigin::Subscription(CoreSubscriptionPacketOrigin{ name: read_name.clone(), path: path.clone(), echo_guard }),
+                                                            ack: None,
+                                                        };
+
+                                                        forwarder.forward(&subscriber_name, tx_handle, outgoing_packet, counters).await;
+                                                    }
+
+                                                    // A new line starts with this chunk.
+                                                    if lines_bytes.is_empty() {
+                                                        line_first_byte_timestamp_micros = chunk_timestamp_micros;
                                                     }
 
                                                     lines_bytes.extend_from_slice(&bytes);
@@ -488,15 +1818,45 @@ impl CoreSerialState {
                                                             Ok(Some(line)) => {
                                                                 tracing::trace!(target: "serial_core::serial::read::line", name=%read_name, ?line, "Read");
 
-                                                                let packet = CorePacket::new_with_current_timestamp(
+                                                                read_app_state.spawn_auto_responses(&read_name, &line).await;
+
+                                                                #[cfg(feature = "subscriptions")]
+                                                                for (subscriber_name, tx_handle, filter, line_forwarding, echo_guard, counters, forwarder) in line_subscribers.iter() {
+                                                                    if filter.as_ref().map_or(false, |filter| !filter.matches(&line)) {
+                                                                        continue;
+                                                                    }
+
+                                                                    let Some(line_forwarding) = line_forwarding else {
+                                                                        continue;
+                                                                    };
+
+                                                                    tracing::trace!(target: "serial_core::serial::read::line::subscribe", name=%read_name, subscriber=%subscriber_name, "Sending line to subscriber");
+
+                                                                    let outgoing_packet = CoreOutgoingPacket {
+                                                                        bytes: line_forwarding.format(&read_name, &line, line_first_byte_timestamp_micros),
+                                                                        packet_origin: CorePacketOrigin::Subscription(CoreSubscriptionPacketOrigin{ name: read_name.clone(), path: path.clone(), echo_guard: *echo_guard }),
+                                                                        ack: None,
+                                                                    };
+
+                                                                    forwarder.forward(subscriber_name, tx_handle.clone(), outgoing_packet, counters.clone()).await;
+                                                                }
+
+                                                                let packet = CorePacket::new(
                                                                     CorePacketDirection::Incoming(
                                                                         CoreIncomingPacket {
                                                                             line,
                                                                         }
                                                                     ),
                                                                     read_name.clone(),
+                                                                    line_first_byte_timestamp_micros,
+                                                                    chunk_timestamp_micros,
                                                                 );
 
+                                                                // The remaining bytes in the buffer belong to this chunk.
+                                                                line_first_byte_timestamp_micros = chunk_timestamp_micros;
+
+                                                                let _ = read_packets_tx.send(packet.clone());
+
                                                                 // Feedback
                                                                 let _ = read_packet_tx.send(
                                                                     Ok(packet)
@@ -568,36 +1928,101 @@ impl CoreSerialState {
                 }
             }
 
+            // Wake up a waiting exclusive owner.
+            read_exclusive_slot.release();
+
             tracing::debug!(target: "serial_core::serial::read", name=%read_name, "Read task terminated")
         });
 
         let write_name = name.to_owned();
         let write_cancellation_token = cancellation_token;
         let write_packet_tx = packet_tx.clone();
+        let write_packets_tx = self.packets_tx.clone();
+        let write_pacing = state_options.write_pacing;
+        let write_exclusive_slot = exclusive_slot;
+        #[cfg(feature = "subscriptions")]
+        let write_echo_guard = echo_guard;
 
         tokio::spawn(async move {
-            // Dropping the sender will automatically break the loop.
-            while let Some(packet) = rx.recv().await {
+            loop {
+                // Packets of an exclusive owner are written first and the write queue is discarded while it owns the port.
+                let mut packet = tokio::select! {
+                    biased;
+                    Some(packet) = exclusive_rx.recv() => packet,
+                    packet = rx.recv() => match packet {
+                        Some(packet) if write_exclusive_slot.is_taken() => {
+                            tracing::debug!(target: "serial_core::serial::write", name=%write_name, origin=%packet.packet_origin, "Discarding packet. Port is exclusively owned");
+
+                            rx.discarded();
+
+                            if let Some(ack) = packet.ack {
+                                ack.send(Err(CoreWriteError::Exclusive));
+                            }
+
+                            continue;
+                        }
+                        Some(packet) => packet,
+                        // Dropping the sender will automatically break the loop.
+                        None => break,
+                    },
+                };
+
+                let ack = packet.ack.take();
+
                 tracing::trace!(target: "serial_core::serial::write::byte", name=%write_name, origin=%packet.packet_origin, bytes=?packet.bytes, "Sending");
                 tracing::trace!(target: "serial_core::serial::write::string", name=%write_name, origin=%packet.packet_origin, bytes=%String::from_utf8_lossy(&packet.bytes), "Sending");
 
+                // Recorded before writing, since the echo may be read before the write completes.
+                #[cfg(feature = "subscriptions")]
+                if let CorePacketOrigin::Subscription(origin) = &packet.packet_origin {
+                    if origin.echo_guard {
+                        write_echo_guard.record(packet.bytes.clone(), origin.path.clone());
+                    }
+                }
+
+                let first_byte_timestamp_micros = clock::now_micros();
+
+                // Transfer blocks are written at once, so the receiver does not time out.
+                let pacing = match packet.packet_origin {
+                    CorePacketOrigin::Transfer(_) => CoreWritePacing::default(),
+                    _ => write_pacing,
+                };
+
                 tokio::select! {
                     // Note: Might get stuck here, therefor the cancellation token.
-                    send_result = framed_write_bytes_port.send(packet.bytes.clone()) => {
+                    send_result = async {
+                        let mut last_byte_timestamp_micros = first_byte_timestamp_micros;
+
+                        for (segment, delay) in pacing.segments(&packet.bytes) {
+                            framed_write_bytes_port.send(segment).await?;
+
+                            last_byte_timestamp_micros = clock::now_micros();
+
+                            if !delay.is_zero() {
+                                tokio::time::sleep(delay).await;
+                            }
+                        }
+
+                        Ok::<_, std::io::Error>(last_byte_timestamp_micros)
+                    } => {
                         match send_result {
-                            Ok(_) => {
+                            Ok(last_byte_timestamp_micros) => {
+
                                 tracing::trace!(target: "serial_core::serial::write::result", name=%write_name, origin=%packet.packet_origin, "Ok");
 
-                                let packet = CorePacket::new_with_current_timestamp(
-                                    CorePacketDirection::Outgoing(
-                                        CoreOutgoingPacket {
-                                            packet_origin: packet.packet_origin,
-                                            bytes: packet.bytes,
-                                        }
-                                    ),
+                                if let Some(ack) = ack {
+                                    ack.send(Ok(CoreWriteAck { first_byte_timestamp_micros, last_byte_timestamp_micros }));
+                                }
+
+                                let packet = CorePacket::new(
+                                    CorePacketDirection::Outgoing(packet),
                                     write_name.clone(),
+                                    first_byte_timestamp_micros,
+                                    last_byte_timestamp_micros,
                                 );
 
+                                let _ = write_packets_tx.send(packet.clone());
+
                                 // Feedback
                                 let _ = write_packet_tx.send(Ok(packet));
                             }
@@ -606,6 +2031,10 @@ impl CoreSerialState {
                                 // Read task must have also been terminated due to the same error.
                                 tracing::error!(target: "serial_core::serial::write::result", name=%write_name, origin=?packet.packet_origin, %err);
 
+                                if let Some(ack) = ack {
+                                    ack.send(Err(std::io::Error::new(err.kind(), err.to_string()).into()));
+                                }
+
                                 // Feedback
                                 let _ = write_packet_tx.send(Err(CorePacketError::Outgoing(err.into())));
 
@@ -627,3 +2056,90 @@ impl CoreSerialState {
         Ok(packet_rx)
     }
 }
+
+#[cfg(all(test, feature = "subscriptions"))]
+mod tests {
+    use super::*;
+
+    fn subscription(echo_guard: bool) -> Subscription {
+        Subscription {
+            tx_handle: None,
+            filter: None,
+            mode: CoreSubscriptionMode::Raw,
+            bridge: false,
+            echo_guard,
+            guards_echo: false,
+            counters: Default::default(),
+            forwarder: SubscriptionForwarder::new(Default::default()),
+        }
+    }
+
+    fn subscriptions(pairs: &[(&str, &str)], echo_guard: bool) -> Subscriptions {
+        let mut subscriptions = Subscriptions::new();
+
+        for (from, to) in pairs {
+            subscriptions
+                .entry(from.to_string())
+                .or_default()
+                .insert(to.to_string(), subscription(echo_guard));
+        }
+
+        update_echo_guards(&mut subscriptions);
+
+        subscriptions
+    }
+
+    fn guards_echo(subscriptions: &Subscriptions, from: &str, to: &str) -> bool {
+        subscriptions
+            .get(from)
+            .and_then(|subscribers| subscribers.get(to))
+            .map_or(false, |subscription| subscription.guards_echo)
+    }
+
+    /// Forwards `bytes` read from `A` to `B` and returns the path of the same bytes read back from `B`, like the read and write tasks do.
+    fn reply_path(subscriptions: &Subscriptions, bytes: &'static [u8]) -> Vec<String> {
+        let echo_guard_of_b = EchoGuard::default();
+
+        if guards_echo(subscriptions, "A", "B") {
+            echo_guard_of_b.record(Bytes::from_static(bytes), vec![String::from("A")]);
+        }
+
+        let mut path = echo_guard_of_b.take_echo(bytes).unwrap_or_default();
+        path.push(String::from("B"));
+        path
+    }
+
+    #[tokio::test]
+    async fn identical_bridged_reply_is_forwarded() {
+        let bridge = [("A", "B"), ("B", "A")];
+
+        // Forwarded back to `A`.
+        assert_eq!(reply_path(&subscriptions(&bridge, false), b"OK"), ["B"]);
+        // Treated as an echo.
+        assert_eq!(reply_path(&subscriptions(&bridge, true), b"OK"), ["A", "B"]);
+    }
+
+    #[tokio::test]
+    async fn echo_guard_only_on_cycles() {
+        assert!(!guards_echo(&subscriptions(&[("A", "B")], true), "A", "B"));
+        assert!(guards_echo(
+            &subscriptions(&[("A", "B"), ("B", "C"), ("C", "A")], true),
+            "A",
+            "B"
+        ));
+    }
+
+    #[tokio::test]
+    async fn echo_guard_updated_when_cycle_closes() {
+        let mut subscriptions = subscriptions(&[("A", "B")], true);
+        assert!(!guards_echo(&subscriptions, "A", "B"));
+
+        subscriptions
+            .entry(String::from("B"))
+            .or_default()
+            .insert(String::from("A"), subscription(true));
+        update_echo_guards(&mut subscriptions);
+        assert!(guards_echo(&subscriptions, "A", "B"));
+        assert!(guards_echo(&subscriptions, "B", "A"));
+    }
+}
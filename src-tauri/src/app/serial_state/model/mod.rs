@@ -1,6 +1,19 @@
-use std::time::Duration;
+use std::{
+    sync::{Arc, Mutex},
+    time::Duration,
+};
 
-use tokio_util::bytes::Bytes;
+use tokio::{
+    io::{AsyncBufRead, AsyncBufReadExt, AsyncReadExt},
+    sync::oneshot::{Receiver as OneshotReceiver, Sender as OneshotSender},
+};
+
+use tokio_util::bytes::{Bytes, BytesMut};
+
+use super::{
+    error::{CoreTransferError, CoreWriteError},
+    handle::SendError,
+};
 
 #[derive(Debug, Clone)]
 pub struct CoreSerialPort {
@@ -49,11 +62,138 @@ pub enum CoreStopBits {
     Two,
 }
 
+/// Line ending appended to the bytes of an outgoing packet before it is sent to an open serial port.
+#[derive(Debug, Default, Clone)]
+pub enum CoreLineEnding {
+    #[default]
+    None,
+    /// `\n`.
+    Lf,
+    /// `\r`.
+    Cr,
+    /// `\r\n`.
+    CrLf,
+    Custom(Bytes),
+}
+
+impl CoreLineEnding {
+    pub fn as_bytes(&self) -> &[u8] {
+        match self {
+            Self::None => b"",
+            Self::Lf => b"\n",
+            Self::Cr => b"\r",
+            Self::CrLf => b"\r\n",
+            Self::Custom(bytes) => bytes,
+        }
+    }
+
+    /// Returns `bytes` followed by the line ending.
+    pub fn apply(&self, bytes: Bytes) -> Bytes {
+        let line_ending = self.as_bytes();
+
+        if line_ending.is_empty() {
+            return bytes;
+        }
+
+        let mut bytes_with_line_ending = BytesMut::with_capacity(bytes.len() + line_ending.len());
+        bytes_with_line_ending.extend_from_slice(&bytes);
+        bytes_with_line_ending.extend_from_slice(line_ending);
+
+        bytes_with_line_ending.freeze()
+    }
+}
+
+/// Slows down the writes to a serial port for devices with small receive buffers.
+///
+/// A line terminator is a `\n` or a `\r` not followed by a `\n`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoreWritePacing {
+    /// Delay after each written byte.
+    pub byte_delay: Duration,
+    /// Delay after each written line terminator. Added to [`CoreWritePacing::byte_delay`].
+    pub line_delay: Duration,
+}
+
+impl CoreWritePacing {
+    pub fn is_disabled(&self) -> bool {
+        self.byte_delay.is_zero() && self.line_delay.is_zero()
+    }
+
+    /// Splits `bytes` into the segments written at once, each followed by its delay.
+    pub fn segments(&self, bytes: &Bytes) -> Vec<(Bytes, Duration)> {
+        if self.is_disabled() {
+            return vec![(bytes.clone(), Duration::ZERO)];
+        }
+
+        let mut segments = Vec::new();
+        let mut start = 0;
+
+        for (index, byte) in bytes.iter().enumerate() {
+            let is_line_terminator =
+                *byte == b'\n' || (*byte == b'\r' && bytes.get(index + 1) != Some(&b'\n'));
+
+            if is_line_terminator {
+                segments.push((
+                    bytes.slice(start..=index),
+                    self.byte_delay + self.line_delay,
+                ));
+                start = index + 1;
+            } else if !self.byte_delay.is_zero() {
+                segments.push((bytes.slice(start..=index), self.byte_delay));
+                start = index + 1;
+            }
+        }
+
+        if start < bytes.len() {
+            segments.push((bytes.slice(start..), Duration::ZERO));
+        }
+
+        segments
+    }
+}
+
+/// Defines what happens to a packet sent to a full write queue.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoreWriteQueueOverflow {
+    /// The sender waits until the write task makes room.
+    #[default]
+    Block,
+    /// The oldest queued packet is dropped to make room.
+    DropOldest,
+    /// The sent packet is dropped. The send fails with [`WriteQueueError::Dropped`](super::write_queue::WriteQueueError::Dropped).
+    DropNewest,
+    /// The send fails.
+    Error,
+}
+
+/// Bounds the packets waiting to be written to a serial port.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CoreWriteQueueOptions {
+    /// Maximum number of queued packets. `0` means unbounded.
+    pub capacity: usize,
+    pub overflow: CoreWriteQueueOverflow,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct CoreWriteQueueMetrics {
+    /// Packets waiting to be written.
+    pub depth: usize,
+    /// See [`CoreWriteQueueOptions::capacity`].
+    pub capacity: usize,
+    /// Packets dropped due to [`CoreWriteQueueOverflow::DropOldest`] or [`CoreWriteQueueOverflow::DropNewest`] since the serial port was opened.
+    /// Includes the packets discarded when a transfer takes exclusive ownership of the serial port.
+    pub dropped: u64,
+}
+
 /// Describes how a given serial port should be open.
 #[derive(Debug, Clone)]
 pub struct CoreOpenSerialPortOptions {
     /// Defines the [`CoreReadState`] of a serial port before it is even open.
     pub initial_read_state: CoreReadState,
+    /// Appended to the packets sent to the serial port. See [`CorePacketOrigin::line_ending`].
+    pub outgoing_line_ending: CoreLineEnding,
+    pub write_pacing: CoreWritePacing,
+    pub write_queue: CoreWriteQueueOptions,
     pub baud_rate: u32,
     pub data_bits: CoreDataBits,
     pub flow_control: CoreFlowControl,
@@ -66,6 +206,9 @@ impl Default for CoreOpenSerialPortOptions {
     fn default() -> Self {
         Self {
             initial_read_state: Default::default(),
+            outgoing_line_ending: Default::default(),
+            write_pacing: Default::default(),
+            write_queue: Default::default(),
             baud_rate: 115200,
             data_bits: Default::default(),
             flow_control: Default::default(),
@@ -76,6 +219,15 @@ impl Default for CoreOpenSerialPortOptions {
     }
 }
 
+/// Part of [`CoreOpenSerialPortOptions`] that is handled by the core itself and not by the serial manager.
+#[derive(Debug, Clone)]
+pub struct CoreStateOptions {
+    pub initial_read_state: CoreReadState,
+    pub outgoing_line_ending: CoreLineEnding,
+    pub write_pacing: CoreWritePacing,
+    pub write_queue: CoreWriteQueueOptions,
+}
+
 /// Represents a packet that is received from a serial port.
 #[derive(Debug, Clone, Default)]
 pub struct CoreIncomingPacket {
@@ -87,6 +239,50 @@ pub struct CoreIncomingPacket {
 pub struct CoreSubscriptionPacketOrigin {
     /// The name of the serial port that sent the packet.
     pub name: String,
+    /// The serial ports the bytes were read from, oldest first. Ends with [`CoreSubscriptionPacketOrigin::name`].
+    ///
+    /// Longer than one if the bytes are an echo of bytes forwarded before. Used to stop forwarding loops.
+    pub path: Vec<String>,
+    /// The bytes are remembered to recognize their echo. See [`CoreSubscription::echo_guard`].
+    pub echo_guard: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreFilePacketOrigin {
+    /// The path of the file that is being sent.
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreTransferPacketOrigin {
+    /// The path of the file that is being sent or received.
+    pub path: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CorePeriodicPacketOrigin {
+    /// The id of the periodic send job that sent the packet.
+    pub job_id: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreMacroPacketOrigin {
+    /// The name of the macro that sent the packet.
+    pub name: String,
+    /// Overrides the outgoing line ending of the serial port if set.
+    pub line_ending: Option<CoreLineEnding>,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreGroupPacketOrigin {
+    /// The name of the port group the packet was sent to.
+    pub name: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct CoreAutoResponderPacketOrigin {
+    /// The name of the auto-responder that sent the packet.
+    pub name: String,
 }
 
 /// Origin of an [`CoreOutgoingPacket`].
@@ -100,6 +296,42 @@ pub enum CorePacketOrigin {
     /// Sent via a subscription from another serial port.
     #[cfg(feature = "subscriptions")]
     Subscription(CoreSubscriptionPacketOrigin),
+    /// Sent as a part of a file.
+    File(CoreFilePacketOrigin),
+    /// Sent as a block or a control byte of an XMODEM or YMODEM transfer.
+    ///
+    /// Written without the write pacing of the serial port, since the receiver times out on slow blocks.
+    Transfer(CoreTransferPacketOrigin),
+    /// Sent by a periodic send job.
+    Periodic(CorePeriodicPacketOrigin),
+    /// Sent by executing a macro.
+    Macro(CoreMacroPacketOrigin),
+    /// Sent to all serial ports of a port group.
+    Group(CoreGroupPacketOrigin),
+    /// Sent by an auto-responder replying to an incoming packet.
+    AutoResponder(CoreAutoResponderPacketOrigin),
+}
+
+impl CorePacketOrigin {
+    /// The line ending appended to packets of this origin, given the `outgoing_line_ending` of the serial port.
+    ///
+    /// Forwarded bytes, files and transfers are sent as they are. Macros may override the outgoing line ending.
+    pub fn line_ending<'a>(
+        &'a self,
+        outgoing_line_ending: &'a CoreLineEnding,
+    ) -> &'a CoreLineEnding {
+        match self {
+            Self::Direct
+            | Self::Broadcast
+            | Self::Periodic(_)
+            | Self::Group(_)
+            | Self::AutoResponder(_) => outgoing_line_ending,
+            #[cfg(feature = "subscriptions")]
+            Self::Subscription(_) => &CoreLineEnding::None,
+            Self::File(_) | Self::Transfer(_) => &CoreLineEnding::None,
+            Self::Macro(origin) => origin.line_ending.as_ref().unwrap_or(outgoing_line_ending),
+        }
+    }
 }
 
 /// Usefull for tracing.
@@ -112,6 +344,12 @@ impl std::fmt::Display for CorePacketOrigin {
             Self::Subscription(subscription) => {
                 write!(f, "Subscription from: [{}]", subscription.name)
             }
+            Self::File(file) => write!(f, "File: [{}]", file.path),
+            Self::Transfer(transfer) => write!(f, "Transfer: [{}]", transfer.path),
+            Self::Periodic(periodic) => write!(f, "Periodic job: [{}]", periodic.job_id),
+            Self::Macro(origin) => write!(f, "Macro: [{}]", origin.name),
+            Self::Group(origin) => write!(f, "Group: [{}]", origin.name),
+            Self::AutoResponder(origin) => write!(f, "Auto-responder: [{}]", origin.name),
         }
     }
 }
@@ -123,6 +361,54 @@ pub struct CoreOutgoingPacket {
     pub bytes: Bytes,
     /// Origin of an [`CoreOutgoingPacket`].
     pub packet_origin: CorePacketOrigin,
+    /// Notified by the write task once the packet is written or failed to be written.
+    ///
+    /// Taken by the write task, so it is always `None` in a [`CorePacket`].
+    pub ack: Option<CoreWriteAckSender>,
+}
+
+/// Timestamps of a written [`CoreOutgoingPacket`]. See [`CorePacket`].
+#[derive(Debug, Clone, Copy)]
+pub struct CoreWriteAck {
+    pub first_byte_timestamp_micros: u64,
+    /// Completion of the write.
+    pub last_byte_timestamp_micros: u64,
+}
+
+pub type CoreWriteAckResult = Result<CoreWriteAck, CoreWriteError>;
+
+/// Sends the result of writing a [`CoreOutgoingPacket`] back to its sender.
+///
+/// Clones share the same oneshot channel. Only the first result is sent.
+/// The receiver fails if the packet is never written, e.g. the port was closed or the packet was dropped from the write queue.
+#[derive(Debug, Clone)]
+pub struct CoreWriteAckSender {
+    tx: Arc<Mutex<Option<OneshotSender<CoreWriteAckResult>>>>,
+}
+
+impl CoreWriteAckSender {
+    pub fn new() -> (Self, OneshotReceiver<CoreWriteAckResult>) {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+
+        (
+            Self {
+                tx: Arc::new(Mutex::new(Some(tx))),
+            },
+            rx,
+        )
+    }
+
+    pub fn send(&self, result: CoreWriteAckResult) {
+        let tx = self
+            .tx
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take();
+
+        if let Some(tx) = tx {
+            let _ = tx.send(result);
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -143,25 +429,40 @@ impl Default for CorePacketDirection {
 ///
 /// Represents a packet that should be sent as single packet and not in a collection.
 /// That is why we need the [`Self::port_name`] to know where to send the packet.
+///
+/// Timestamps are microseconds since the Unix epoch taken from [`clock`](crate::app::serial_state::clock).
 #[derive(Debug, Clone, Default)]
 pub struct CorePacket {
     pub packet_direction: CorePacketDirection,
     /// The name of the corresponding serial port.
     pub port_name: String,
-    pub timestamp_millis: u64,
+    /// - Incoming: The chunk containing the first byte of the line was read.
+    /// - Outgoing: The write of the bytes started.
+    pub first_byte_timestamp_micros: u64,
+    /// - Incoming: The chunk containing the line terminator was read.
+    /// - Outgoing: The write of the bytes completed.
+    pub last_byte_timestamp_micros: u64,
 }
 
 impl CorePacket {
-    pub fn new_with_current_timestamp(
+    pub fn new(
         packet_direction: CorePacketDirection,
         port_name: String,
+        first_byte_timestamp_micros: u64,
+        last_byte_timestamp_micros: u64,
     ) -> Self {
         Self {
             packet_direction,
             port_name,
-            timestamp_millis: chrono::Utc::now().timestamp_millis() as u64,
+            first_byte_timestamp_micros,
+            last_byte_timestamp_micros,
         }
     }
+
+    /// Timestamp of the last byte in milliseconds.
+    pub fn timestamp_millis(&self) -> u64 {
+        self.last_byte_timestamp_micros / 1000
+    }
 }
 
 /// Defines if an open serial port is currently reading or stopped.
@@ -176,6 +477,7 @@ pub enum CoreReadState {
 #[derive(Debug)]
 pub struct CoreOpenStatus {
     pub read_state: CoreReadState,
+    pub write_queue: CoreWriteQueueMetrics,
 }
 
 /// Status of a serial port.
@@ -198,14 +500,204 @@ impl CoreReadState {
     }
 }
 
+/// Restricts the data forwarded by a subscription to the matching lines.
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone)]
+pub enum CoreSubscriptionFilter {
+    /// Lines matching the regular expression.
+    Regex(regex::bytes::Regex),
+    /// Lines starting with the bytes.
+    Prefix(Bytes),
+}
+
+#[cfg(feature = "subscriptions")]
+impl CoreSubscriptionFilter {
+    /// The `line` is expected without its line terminator.
+    pub fn matches(&self, line: &[u8]) -> bool {
+        match self {
+            Self::Regex(regex) => regex.is_match(line),
+            Self::Prefix(prefix) => line.starts_with(prefix),
+        }
+    }
+}
+
+/// Options of [`CoreSubscriptionMode::Lines`].
+#[derive(Debug, Clone)]
+pub struct CoreLineForwarding {
+    /// Appended to every forwarded line, replacing the line terminator read from the master.
+    pub line_ending: CoreLineEnding,
+    /// Prefixes every forwarded line with `[name] `, the name of the master.
+    pub prefix_name: bool,
+    /// Prefixes every forwarded line with `[timestamp] `, the RFC 3339 time the line was read.
+    pub prefix_timestamp: bool,
+}
+
+impl Default for CoreLineForwarding {
+    fn default() -> Self {
+        Self {
+            line_ending: CoreLineEnding::Lf,
+            prefix_name: false,
+            prefix_timestamp: false,
+        }
+    }
+}
+
+impl CoreLineForwarding {
+    /// Returns the bytes forwarded for the `line` read from the serial port `name` at `timestamp_micros`.
+    ///
+    /// The `line` is expected without its line terminator.
+    pub fn format(&self, name: &str, line: &[u8], timestamp_micros: u64) -> Bytes {
+        let mut bytes = BytesMut::with_capacity(line.len() + 2);
+
+        if self.prefix_timestamp {
+            let timestamp = chrono::DateTime::from_timestamp_micros(timestamp_micros as i64)
+                .unwrap_or_default()
+                .to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+
+            bytes.extend_from_slice(format!("[{timestamp}] ").as_bytes());
+        }
+
+        if self.prefix_name {
+            bytes.extend_from_slice(format!("[{name}] ").as_bytes());
+        }
+
+        bytes.extend_from_slice(line);
+        bytes.extend_from_slice(self.line_ending.as_bytes());
+
+        bytes.freeze()
+    }
+}
+
+/// How a subscription forwards the data read from the master.
+#[derive(Debug, Clone, Default)]
+pub enum CoreSubscriptionMode {
+    /// Every read chunk as it is. Lines may be split across chunks.
+    #[default]
+    Raw,
+    /// Every decoded line.
+    Lines(CoreLineForwarding),
+}
+
+impl CoreSubscriptionMode {
+    /// The options lines are forwarded with, or `None` if chunks are forwarded as they are.
+    pub fn line_forwarding(&self) -> Option<CoreLineForwarding> {
+        match self {
+            Self::Raw => None,
+            Self::Lines(line_forwarding) => Some(line_forwarding.clone()),
+        }
+    }
+}
+
+/// Defines what happens to a frame exceeding a [`CoreSubscriptionRateLimit`].
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CoreRateLimitOverflow {
+    /// The frame is not forwarded.
+    #[default]
+    Drop,
+    /// The frame is forwarded as soon as the rate allows it. Frames are only dropped if [`CoreSubscriptionRateLimit::capacity`] frames are waiting.
+    Buffer,
+}
+
+/// Limits the data forwarded by a subscription, e.g. to a slower subscriber.
+///
+/// A burst of up to one second worth of the rate is forwarded at once.
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CoreSubscriptionRateLimit {
+    /// `0` means unlimited.
+    pub bytes_per_second: u64,
+    /// Read chunks or lines, depending on the [`CoreSubscriptionMode`]. `0` means unlimited.
+    pub frames_per_second: u64,
+    pub overflow: CoreRateLimitOverflow,
+    /// Fixed delay of every forwarded frame, e.g. to emulate the latency of a link.
+    pub latency: Duration,
+    /// Maximum number of frames waiting for the rate limit or for room in the write queue of the subscriber.
+    /// Further frames are dropped. At least one frame can wait. Only applies with a rate limit, see [`Self::is_limited`].
+    pub capacity: usize,
+}
+
+#[cfg(feature = "subscriptions")]
+impl CoreSubscriptionRateLimit {
+    /// Whether frames are delayed or dropped. Otherwise they are forwarded right away and [`Self::capacity`] does not apply.
+    pub fn is_limited(&self) -> bool {
+        self.bytes_per_second != 0 || self.frames_per_second != 0 || !self.latency.is_zero()
+    }
+}
+
+#[cfg(feature = "subscriptions")]
+impl Default for CoreSubscriptionRateLimit {
+    fn default() -> Self {
+        Self {
+            bytes_per_second: 0,
+            frames_per_second: 0,
+            overflow: Default::default(),
+            latency: Duration::ZERO,
+            capacity: 1024,
+        }
+    }
+}
+
+/// Given to [`StateInner::subscribe`](crate::app::serial_state::StateInner::subscribe). See [`CoreSubscription`].
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone)]
+pub struct CoreSubscriptionOptions {
+    pub filter: Option<CoreSubscriptionFilter>,
+    pub mode: CoreSubscriptionMode,
+    pub rate_limit: CoreSubscriptionRateLimit,
+    pub echo_guard: bool,
+}
+
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone)]
+pub struct CoreSubscription {
+    /// Name of the subscriber serial port.
+    pub name: String,
+    /// Only the matching lines are forwarded if set.
+    ///
+    /// Only applied with [`CoreSubscriptionMode::Lines`], since [`CoreSubscriptionMode::Raw`] forwards chunks that are not split into lines.
+    pub filter: Option<CoreSubscriptionFilter>,
+    /// How the data read from the master is forwarded.
+    pub mode: CoreSubscriptionMode,
+    pub rate_limit: CoreSubscriptionRateLimit,
+    /// Bytes read back from the subscriber right after they were forwarded to it are treated as an echo
+    /// and not forwarded back along the cycle the subscription is part of.
+    ///
+    /// Has no effect if the subscription is not part of a cycle. An identical reply of a device is dropped as well.
+    pub echo_guard: bool,
+    pub metrics: CoreSubscriptionMetrics,
+}
+
+/// Counted since the subscription was created.
+#[cfg(feature = "subscriptions")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoreSubscriptionMetrics {
+    pub bytes_forwarded: u64,
+    /// Read chunks or lines, depending on the [`CoreSubscriptionMode`].
+    pub frames_forwarded: u64,
+    /// Frames that could not be sent to the subscriber, e.g. it was closed or its write queue rejected them.
+    pub send_failures: u64,
+    /// Frames not forwarded due to [`CoreRateLimitOverflow::Drop`] or [`CoreSubscriptionRateLimit::capacity`].
+    pub frames_dropped: u64,
+    /// Frames waiting for the [`CoreSubscriptionRateLimit`] or for room in the write queue of the subscriber.
+    pub frames_queued: u64,
+    /// `None` if nothing was forwarded yet.
+    pub last_forward_timestamp_micros: Option<u64>,
+}
+
 #[derive(Debug)]
 pub struct CoreManagedSerialPort {
     pub name: String,
     pub status: Status,
     #[cfg(feature = "subscriptions")]
-    pub subscriptions: Vec<String>,
+    pub subscriptions: Vec<CoreSubscription>,
     #[cfg(feature = "subscriptions")]
     pub subscribed_to: Vec<String>,
+    /// Names of the serial ports bridged with this serial port.
+    ///
+    /// Bridges are not part of [`CoreManagedSerialPort::subscriptions`] and [`CoreManagedSerialPort::subscribed_to`].
+    #[cfg(feature = "subscriptions")]
+    pub bridged_with: Vec<String>,
 }
 
 impl CoreManagedSerialPort {
@@ -217,3 +709,348 @@ impl CoreManagedSerialPort {
         matches!(self.status, Status::Closed)
     }
 }
+
+/// Describes how a file is split into packets when sent to a serial port.
+#[derive(Debug, Clone)]
+pub enum CoreSendFilePacing {
+    /// Packets of `chunk_size` bytes. Waits `delay` after each packet.
+    Chunks { chunk_size: usize, delay: Duration },
+    /// A packet per line, including its line terminator. Waits `delay` after each packet.
+    Lines { delay: Duration },
+}
+
+impl Default for CoreSendFilePacing {
+    fn default() -> Self {
+        Self::Chunks {
+            chunk_size: 64,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+impl CoreSendFilePacing {
+    /// Reads the next packet from the `reader`, so that only one packet of the file is held in memory.
+    ///
+    /// - `Ok(Some(_))` => Packet.
+    /// - `Ok(None)` => End of the file.
+    pub async fn read_packet<R>(&self, reader: &mut R) -> std::io::Result<Option<Bytes>>
+    where
+        R: AsyncBufRead + Unpin,
+    {
+        let mut packet = Vec::new();
+
+        match self {
+            Self::Chunks { chunk_size, .. } => {
+                let chunk_size = (*chunk_size).max(1) as u64;
+
+                reader.take(chunk_size).read_to_end(&mut packet).await?;
+            }
+            Self::Lines { .. } => {
+                reader.read_until(b'\n', &mut packet).await?;
+            }
+        }
+
+        Ok((!packet.is_empty()).then(|| Bytes::from(packet)))
+    }
+
+    pub fn delay(&self) -> Duration {
+        match self {
+            Self::Chunks { delay, .. } => *delay,
+            Self::Lines { delay } => *delay,
+        }
+    }
+}
+
+/// Block based file transfer protocols.
+#[derive(Debug, Clone, Copy)]
+pub enum CoreXmodemProtocol {
+    /// XMODEM-CRC with 128 byte blocks. Falls back to the checksum variant if the receiver requests it.
+    Xmodem,
+    /// YMODEM batch with a single file and 1024 byte blocks.
+    Ymodem,
+}
+
+impl std::fmt::Display for CoreXmodemProtocol {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Xmodem => write!(f, "XMODEM"),
+            Self::Ymodem => write!(f, "YMODEM"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum CoreTransferDirection {
+    /// From the application to the open serial port.
+    Send,
+    /// From the open serial port to the application.
+    Receive,
+}
+
+#[derive(Debug, Clone)]
+pub enum CoreTransferKind {
+    File {
+        path: String,
+    },
+    Xmodem {
+        protocol: CoreXmodemProtocol,
+        direction: CoreTransferDirection,
+        path: String,
+    },
+}
+
+/// A running transfer to an open serial port.
+#[derive(Debug, Clone)]
+pub struct CoreTransfer {
+    pub id: u64,
+    /// The name of the serial port the transfer is running on.
+    pub port_name: String,
+    pub kind: CoreTransferKind,
+}
+
+/// Event emitted by [`CoreSerialState::send_file`](crate::app::serial_state::CoreSerialState::send_file),
+/// [`CoreSerialState::xmodem_send`](crate::app::serial_state::CoreSerialState::xmodem_send)
+/// and [`CoreSerialState::xmodem_receive`](crate::app::serial_state::CoreSerialState::xmodem_receive) through the channel.
+///
+/// The channel is closed after [`CoreTransferEvent::Finished`], [`CoreTransferEvent::Cancelled`], [`CoreTransferEvent::PortBusy`]
+/// or [`CoreTransferEvent::Failed`].
+#[derive(Debug)]
+pub enum CoreTransferEvent {
+    /// `bytes_total` is `None` if the size is not known in advance, e.g. when receiving via XMODEM.
+    Progress {
+        bytes_transferred: u64,
+        bytes_total: Option<u64>,
+    },
+    Finished,
+    Cancelled,
+    /// The serial port was taken by an XMODEM or YMODEM transfer while a file was being sent.
+    /// The last [`CoreTransferEvent::Progress`] tells how much of the file was written.
+    PortBusy,
+    Failed(CoreTransferError),
+}
+
+impl From<Result<(), CoreTransferError>> for CoreTransferEvent {
+    fn from(result: Result<(), CoreTransferError>) -> Self {
+        match result {
+            Ok(_) => Self::Finished,
+            Err(
+                CoreTransferError::Send(SendError::Exclusive)
+                | CoreTransferError::SerialWrite(CoreWriteError::Exclusive),
+            ) => Self::PortBusy,
+            Err(err) => Self::Failed(err),
+        }
+    }
+}
+
+/// Defines when a periodic send job sends its payload.
+#[derive(Debug, Clone)]
+pub enum CorePeriodicSchedule {
+    /// Every `Duration`, starting one interval after the job is started.
+    Interval(Duration),
+    /// Cron expression with a seconds field, e.g. `0/5 * * * * *`. Evaluated in UTC.
+    Cron(String),
+}
+
+/// A running periodic send job.
+#[derive(Debug, Clone)]
+pub struct CorePeriodicJob {
+    pub id: u64,
+    /// The name of the serial port the job is sending to.
+    pub port_name: String,
+    /// Sent with [`CorePacketOrigin::Periodic`], so the outgoing line ending of the serial port is appended.
+    pub bytes: Bytes,
+    pub schedule: CorePeriodicSchedule,
+}
+
+/// Matched against the lines of incoming packets.
+#[derive(Debug, Clone)]
+pub enum CoreExpectPattern {
+    /// The line contains the bytes.
+    Literal(Bytes),
+    /// The line starts with the bytes.
+    Prefix(Bytes),
+    Regex(regex::bytes::Regex),
+}
+
+impl CoreExpectPattern {
+    pub fn matches(&self, line: &[u8]) -> bool {
+        match self {
+            Self::Literal(literal) => {
+                literal.is_empty() || line.windows(literal.len()).any(|window| window == literal)
+            }
+            Self::Prefix(prefix) => line.starts_with(prefix),
+            Self::Regex(regex) => regex.is_match(line),
+        }
+    }
+}
+
+/// Replies to the incoming packets of a serial port with lines matching a pattern.
+#[derive(Debug, Clone)]
+pub struct CoreAutoResponder {
+    pub name: String,
+    /// The serial port whose incoming lines are matched.
+    pub port: String,
+    pub pattern: regex::bytes::Regex,
+    /// Capture groups of the [`CoreAutoResponder::pattern`] are substituted, e.g. `$1` or `${name}`.
+    /// See [`regex::bytes::Captures::expand`].
+    pub response: Bytes,
+    /// The serial port the response is sent to. [`CoreAutoResponder::port`] if `None`.
+    pub target: Option<String>,
+    /// Waited before sending the response.
+    pub delay: Duration,
+    /// Disabled auto-responders are kept but never reply.
+    pub enabled: bool,
+}
+
+impl CoreAutoResponder {
+    /// Returns the response to the `line` or `None` if the auto-responder is disabled or the `line` does not match.
+    pub fn respond(&self, line: &[u8]) -> Option<Bytes> {
+        if !self.enabled {
+            return None;
+        }
+
+        let captures = self.pattern.captures(line)?;
+
+        let mut response = Vec::new();
+        captures.expand(&self.response, &mut response);
+
+        Some(response.into())
+    }
+
+    pub fn target(&self) -> &str {
+        self.target.as_deref().unwrap_or(&self.port)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn auto_responder_respond() {
+        let mut auto_responder = CoreAutoResponder {
+            name: String::from("temp"),
+            port: String::from("COM1"),
+            pattern: regex::bytes::Regex::new(r"^GET (?<key>\w+) (\d+)$").unwrap(),
+            response: Bytes::from_static(b"${key}=$2"),
+            target: None,
+            delay: Duration::ZERO,
+            enabled: true,
+        };
+
+        assert_eq!(
+            auto_responder.respond(b"GET temp 21"),
+            Some(Bytes::from_static(b"temp=21"))
+        );
+        assert_eq!(auto_responder.respond(b"SET temp 21"), None);
+        assert_eq!(auto_responder.target(), "COM1");
+
+        auto_responder.enabled = false;
+        assert_eq!(auto_responder.respond(b"GET temp 21"), None);
+    }
+
+    #[test]
+    fn line_forwarding_format() {
+        let line_forwarding = CoreLineForwarding {
+            line_ending: CoreLineEnding::CrLf,
+            prefix_name: true,
+            prefix_timestamp: true,
+        };
+
+        assert_eq!(
+            line_forwarding.format("COM1", b"$GPGGA", 1_700_000_000_123_000),
+            "[2023-11-14T22:13:20.123Z] [COM1] $GPGGA\r\n"
+        );
+        assert_eq!(
+            CoreLineForwarding::default().format("COM1", b"$GPGGA", 0),
+            "$GPGGA\n"
+        );
+    }
+
+    #[test]
+    fn write_pacing_segments() {
+        let bytes = Bytes::from_static(b"ab\r\ncd\ref");
+        let millis = Duration::from_millis;
+
+        let lines = CoreWritePacing {
+            byte_delay: Duration::ZERO,
+            line_delay: millis(10),
+        };
+        assert_eq!(
+            lines.segments(&bytes),
+            vec![
+                (Bytes::from_static(b"ab\r\n"), millis(10)),
+                (Bytes::from_static(b"cd\r"), millis(10)),
+                (Bytes::from_static(b"ef"), Duration::ZERO),
+            ]
+        );
+
+        let bytes_and_lines = CoreWritePacing {
+            byte_delay: millis(1),
+            line_delay: millis(10),
+        };
+        let segments = bytes_and_lines.segments(&Bytes::from_static(b"a\nb"));
+        assert_eq!(
+            segments,
+            vec![
+                (Bytes::from_static(b"a"), millis(1)),
+                (Bytes::from_static(b"\n"), millis(11)),
+                (Bytes::from_static(b"b"), millis(1)),
+            ]
+        );
+
+        assert_eq!(
+            CoreWritePacing::default().segments(&bytes),
+            vec![(bytes.clone(), Duration::ZERO)]
+        );
+    }
+
+    #[tokio::test]
+    async fn send_file_pacing_read_packet() {
+        async fn packets(pacing: CoreSendFilePacing, mut file: &[u8]) -> Vec<Bytes> {
+            let mut packets = Vec::new();
+
+            while let Some(packet) = pacing.read_packet(&mut file).await.unwrap() {
+                packets.push(packet);
+            }
+
+            packets
+        }
+
+        let chunks = CoreSendFilePacing::Chunks {
+            chunk_size: 4,
+            delay: Duration::ZERO,
+        };
+        assert_eq!(
+            packets(chunks, b"ab\ncdefg").await,
+            vec![Bytes::from_static(b"ab\nc"), Bytes::from_static(b"defg")]
+        );
+
+        let lines = CoreSendFilePacing::Lines {
+            delay: Duration::ZERO,
+        };
+        assert_eq!(
+            packets(lines, b"ab\r\ncd\nef").await,
+            vec![
+                Bytes::from_static(b"ab\r\n"),
+                Bytes::from_static(b"cd\n"),
+                Bytes::from_static(b"ef"),
+            ]
+        );
+    }
+
+    #[test]
+    fn expect_pattern_matches() {
+        let literal = CoreExpectPattern::Literal(Bytes::from_static(b"OK"));
+        assert!(literal.matches(b"+CSQ: 20 OK"));
+        assert!(!literal.matches(b"ERROR"));
+
+        let prefix = CoreExpectPattern::Prefix(Bytes::from_static(b"+CSQ"));
+        assert!(prefix.matches(b"+CSQ: 20"));
+        assert!(!prefix.matches(b"OK +CSQ"));
+
+        let regex = CoreExpectPattern::Regex(regex::bytes::Regex::new(r"^\+CSQ: \d+$").unwrap());
+        assert!(regex.matches(b"+CSQ: 20"));
+        assert!(!regex.matches(b"+CSQ: x"));
+    }
+}
@@ -0,0 +1,55 @@
+//! Timing of periodic send jobs.
+
+use std::str::FromStr;
+
+use chrono::Utc;
+use cron::Schedule;
+use tokio::time::{Instant, Interval, MissedTickBehavior};
+
+use super::{error::CoreStartPeriodicJobError, model::CorePeriodicSchedule};
+
+/// Resolves when a periodic send job should send its payload.
+#[derive(Debug)]
+pub(super) enum Ticker {
+    Interval(Interval),
+    Cron(Box<Schedule>),
+}
+
+impl Ticker {
+    pub(super) fn new(schedule: &CorePeriodicSchedule) -> Result<Self, CoreStartPeriodicJobError> {
+        match schedule {
+            CorePeriodicSchedule::Interval(period) => {
+                if period.is_zero() {
+                    return Err(CoreStartPeriodicJobError::ZeroInterval);
+                }
+
+                let mut interval = tokio::time::interval_at(Instant::now() + *period, *period);
+                // A slow port should not cause a burst of packets.
+                interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+                Ok(Self::Interval(interval))
+            }
+            CorePeriodicSchedule::Cron(expression) => {
+                Ok(Self::Cron(Box::new(Schedule::from_str(expression)?)))
+            }
+        }
+    }
+
+    /// - `Some(())` => Time to send.
+    /// - `None` => The schedule has no upcoming times.
+    pub(super) async fn tick(&mut self) -> Option<()> {
+        match self {
+            Self::Interval(interval) => {
+                interval.tick().await;
+            }
+            Self::Cron(schedule) => {
+                let next = schedule.upcoming(Utc).next()?;
+                let delay = (next - Utc::now()).to_std().unwrap_or_default();
+
+                tokio::time::sleep(delay).await;
+            }
+        }
+
+        Some(())
+    }
+}
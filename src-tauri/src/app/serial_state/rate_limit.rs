@@ -0,0 +1,443 @@
+//! Forwards the frames of a subscription and applies its [`CoreSubscriptionRateLimit`].
+//!
+//! Without a rate limit, frames are sent to the subscriber by the read task of the master.
+//! With one, frames are scheduled in the read task of the master and forwarded in order by a task of the subscription.
+//! Waiting for the rate limit or for the write queue of the subscriber then only delays the frames of this subscription.
+//! Once [`CoreSubscriptionRateLimit::capacity`] frames are waiting, further frames are dropped.
+
+use std::{
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+    time::{Duration, Instant},
+};
+
+use tokio::sync::mpsc::{
+    unbounded_channel, UnboundedReceiver as MPSCUnboundedReceiver,
+    UnboundedSender as MPSCUnboundedSender,
+};
+
+use super::{
+    clock,
+    handle::{SendError, TxHandle},
+    model::{CoreOutgoingPacket, CoreRateLimitOverflow, CoreSubscriptionRateLimit},
+    subscription_metrics::SubscriptionCounters,
+};
+
+/// Up to one second worth of the rate is forwarded at once.
+const BURST: Duration = Duration::from_secs(1);
+
+/// Generic cell rate algorithm.
+#[derive(Debug)]
+struct Bucket {
+    /// Units per second. `0` means unlimited.
+    rate: u64,
+    /// Theoretical arrival time of the next unit.
+    tat: Option<Instant>,
+}
+
+impl Bucket {
+    fn new(rate: u64) -> Self {
+        Self { rate, tat: None }
+    }
+
+    fn interval(&self, cost: u64) -> Duration {
+        Duration::from_secs_f64(cost as f64 / self.rate as f64)
+    }
+
+    /// Earliest instant a frame of `cost` units conforms to the rate.
+    fn conforming_at(&self, now: Instant, cost: u64) -> Instant {
+        match self.tat {
+            Some(tat) if self.rate != 0 => (tat + self.interval(cost))
+                .checked_sub(BURST)
+                .map_or(now, |conforming_at| conforming_at.max(now)),
+            _ => now,
+        }
+    }
+
+    fn consume(&mut self, at: Instant, cost: u64) {
+        if self.rate == 0 {
+            return;
+        }
+
+        let tat = self.tat.map_or(at, |tat| tat.max(at));
+
+        self.tat = Some(tat + self.interval(cost));
+    }
+}
+
+#[derive(Debug)]
+struct Buckets {
+    bytes: Bucket,
+    frames: Bucket,
+}
+
+impl Buckets {
+    fn new(rate_limit: &CoreSubscriptionRateLimit) -> Self {
+        Self {
+            bytes: Bucket::new(rate_limit.bytes_per_second),
+            frames: Bucket::new(rate_limit.frames_per_second),
+        }
+    }
+
+    /// Returns the instant the frame of `len` bytes may be forwarded, or `None` if it is dropped.
+    fn schedule(
+        &mut self,
+        overflow: CoreRateLimitOverflow,
+        now: Instant,
+        len: usize,
+    ) -> Option<Instant> {
+        let at = self
+            .bytes
+            .conforming_at(now, len as u64)
+            .max(self.frames.conforming_at(now, 1));
+
+        if at > now && overflow == CoreRateLimitOverflow::Drop {
+            return None;
+        }
+
+        self.bytes.consume(at, len as u64);
+        self.frames.consume(at, 1);
+
+        Some(at)
+    }
+}
+
+#[derive(Debug)]
+struct Forward {
+    send_at: Instant,
+    subscriber_name: String,
+    tx_handle: TxHandle,
+    packet: CoreOutgoingPacket,
+    counters: Arc<SubscriptionCounters>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    rate_limit: CoreSubscriptionRateLimit,
+    buckets: Buckets,
+    /// `None` while no task is running. See [`SubscriptionForwarder::forward`].
+    tx: Option<MPSCUnboundedSender<Forward>>,
+}
+
+/// Dropping the forwarder stops its task after the scheduled frames are forwarded.
+#[derive(Debug)]
+pub struct SubscriptionForwarder {
+    inner: Mutex<Inner>,
+    /// Frames scheduled but not yet sent to the subscriber. Shared with the task.
+    pending: Arc<AtomicUsize>,
+}
+
+impl SubscriptionForwarder {
+    pub fn new(rate_limit: CoreSubscriptionRateLimit) -> Arc<Self> {
+        Arc::new(Self {
+            inner: Mutex::new(Inner {
+                rate_limit,
+                buckets: Buckets::new(&rate_limit),
+                tx: None,
+            }),
+            pending: Arc::new(AtomicUsize::new(0)),
+        })
+    }
+
+    /// The lock is never held across an await point. A poisoned lock still holds a valid value.
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    pub fn rate_limit(&self) -> CoreSubscriptionRateLimit {
+        self.lock().rate_limit
+    }
+
+    /// Applies the `rate_limit` to the frames forwarded from now on.
+    ///
+    /// Frames already waiting keep their schedule and are forwarded first.
+    pub fn set_rate_limit(&self, rate_limit: CoreSubscriptionRateLimit) {
+        let mut inner = self.lock();
+
+        inner.rate_limit = rate_limit;
+        inner.buckets = Buckets::new(&rate_limit);
+    }
+
+    /// Sends the `packet` to the subscriber.
+    ///
+    /// Without a rate limit, the `packet` is sent right away, waiting for room in the write queue of the subscriber.
+    /// Otherwise it is scheduled and forwarded by the task of the forwarder, which is spawned on demand, or dropped if it
+    /// exceeds the rate limit or the queue is full. Frames still waiting after the rate limit was removed are forwarded first.
+    pub async fn forward(
+        &self,
+        subscriber_name: &str,
+        tx_handle: TxHandle,
+        packet: CoreOutgoingPacket,
+        counters: Arc<SubscriptionCounters>,
+    ) {
+        {
+            let mut inner = self.lock();
+
+            if inner.rate_limit.is_limited() || self.pending.load(Ordering::Relaxed) != 0 {
+                self.schedule(&mut inner, subscriber_name, tx_handle, packet, counters);
+
+                return;
+            }
+
+            // Nothing is waiting. Stops the task of a removed rate limit.
+            inner.tx = None;
+        }
+
+        let len = packet.bytes.len();
+        let result = tx_handle.send(packet).await;
+
+        sent(subscriber_name, &counters, len, result);
+    }
+
+    /// Never waits.
+    fn schedule(
+        &self,
+        inner: &mut Inner,
+        subscriber_name: &str,
+        tx_handle: TxHandle,
+        packet: CoreOutgoingPacket,
+        counters: Arc<SubscriptionCounters>,
+    ) {
+        if self.pending.load(Ordering::Relaxed) >= inner.rate_limit.capacity.max(1) {
+            tracing::trace!(target: "serial_core::subscription::forward", subscriber=%subscriber_name, "Dropping frame, queue is full");
+
+            counters.dropped();
+
+            return;
+        }
+
+        let overflow = inner.rate_limit.overflow;
+        let scheduled_at = inner
+            .buckets
+            .schedule(overflow, Instant::now(), packet.bytes.len());
+
+        let Some(scheduled_at) = scheduled_at else {
+            tracing::trace!(target: "serial_core::subscription::rate_limit", subscriber=%subscriber_name, "Dropping frame exceeding the rate limit");
+
+            counters.dropped();
+
+            return;
+        };
+
+        self.pending.fetch_add(1, Ordering::Relaxed);
+        counters.queued();
+
+        let forward = Forward {
+            send_at: scheduled_at + inner.rate_limit.latency,
+            subscriber_name: subscriber_name.to_string(),
+            tx_handle,
+            packet,
+            counters,
+        };
+
+        let tx = inner.tx.get_or_insert_with(|| {
+            let (tx, rx) = unbounded_channel();
+
+            tokio::spawn(forward_scheduled(rx, self.pending.clone()));
+
+            tx
+        });
+
+        // Sent while locked, so the frames are queued in the order they were scheduled.
+        if let Err(err) = tx.send(forward) {
+            // Only if the task panicked. It runs until the forwarder is dropped or the rate limit is removed.
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+            err.0.counters.dequeued();
+            err.0.counters.failed();
+        }
+    }
+}
+
+/// Counts the frame sent to the subscriber.
+fn sent(
+    subscriber_name: &str,
+    counters: &SubscriptionCounters,
+    len: usize,
+    result: Result<(), SendError>,
+) {
+    match result {
+        Ok(()) => counters.forwarded(len, clock::now_micros()),
+        Err(err) => {
+            counters.failed();
+
+            tracing::error!(target: "serial_core::subscription::forward", subscriber=%subscriber_name, %err, "Failed to send frame to subscriber");
+        }
+    }
+}
+
+/// Forwards the frames in order, each at its scheduled instant.
+async fn forward_scheduled(mut rx: MPSCUnboundedReceiver<Forward>, pending: Arc<AtomicUsize>) {
+    while let Some(forward) = rx.recv().await {
+        tokio::time::sleep_until(forward.send_at.into()).await;
+
+        let len = forward.packet.bytes.len();
+        let result = forward.tx_handle.send(forward.packet).await;
+
+        pending.fetch_sub(1, Ordering::Relaxed);
+        forward.counters.dequeued();
+
+        sent(&forward.subscriber_name, &forward.counters, len, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::app::serial_state::{
+        model::{CorePacketOrigin, CoreWriteQueueOptions, CoreWriteQueueOverflow},
+        write_queue::write_queue,
+    };
+
+    use super::*;
+
+    fn buckets(bytes_per_second: u64, frames_per_second: u64) -> Buckets {
+        Buckets {
+            bytes: Bucket::new(bytes_per_second),
+            frames: Bucket::new(frames_per_second),
+        }
+    }
+
+    #[test]
+    fn drop_after_burst() {
+        let mut buckets = buckets(0, 2);
+        let now = Instant::now();
+
+        assert_eq!(
+            buckets.schedule(CoreRateLimitOverflow::Drop, now, 1),
+            Some(now)
+        );
+        assert_eq!(
+            buckets.schedule(CoreRateLimitOverflow::Drop, now, 1),
+            Some(now)
+        );
+        assert_eq!(buckets.schedule(CoreRateLimitOverflow::Drop, now, 1), None);
+
+        let later = now + Duration::from_millis(500);
+        assert_eq!(
+            buckets.schedule(CoreRateLimitOverflow::Drop, later, 1),
+            Some(later)
+        );
+    }
+
+    #[test]
+    fn buffer_after_burst() {
+        let mut buckets = buckets(10, 0);
+        let now = Instant::now();
+
+        assert_eq!(
+            buckets.schedule(CoreRateLimitOverflow::Buffer, now, 10),
+            Some(now)
+        );
+        assert_eq!(
+            buckets.schedule(CoreRateLimitOverflow::Buffer, now, 5),
+            Some(now + Duration::from_millis(500))
+        );
+        assert_eq!(
+            buckets.schedule(CoreRateLimitOverflow::Buffer, now, 5),
+            Some(now + Duration::from_secs(1))
+        );
+    }
+
+    fn packet(index: usize) -> CoreOutgoingPacket {
+        CoreOutgoingPacket {
+            bytes: vec![index as u8].into(),
+            packet_origin: CorePacketOrigin::default(),
+            ack: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn blocked_subscriber_does_not_delay_others() {
+        // Never drained.
+        let (blocked_tx, _blocked_rx) = write_queue(CoreWriteQueueOptions {
+            capacity: 1,
+            overflow: CoreWriteQueueOverflow::Block,
+        });
+        let (tx, mut rx) = write_queue(Default::default());
+
+        let blocked = SubscriptionForwarder::new(CoreSubscriptionRateLimit {
+            latency: Duration::from_millis(1),
+            capacity: 4,
+            ..Default::default()
+        });
+        let blocked_counters = Arc::new(SubscriptionCounters::default());
+        let blocked_tx_handle = TxHandle::detached(blocked_tx);
+
+        let forwarder = SubscriptionForwarder::new(Default::default());
+        let counters = Arc::new(SubscriptionCounters::default());
+        let tx_handle = TxHandle::detached(tx);
+
+        for index in 0..14 {
+            blocked
+                .forward(
+                    "blocked",
+                    blocked_tx_handle.clone(),
+                    packet(index),
+                    blocked_counters.clone(),
+                )
+                .await;
+        }
+
+        // Forwarded although the queue of the blocked subscriber is full.
+        for index in 0..10 {
+            forwarder
+                .forward(
+                    "subscriber",
+                    tx_handle.clone(),
+                    packet(index),
+                    counters.clone(),
+                )
+                .await;
+
+            let received = tokio::time::timeout(Duration::from_secs(1), rx.recv())
+                .await
+                .expect("Delayed by the blocked subscriber")
+                .unwrap();
+
+            assert_eq!(received.bytes.as_ref(), [index as u8]);
+        }
+
+        // Past the latency of the blocked subscriber.
+        tokio::time::sleep(Duration::from_millis(20)).await;
+
+        let metrics = blocked_counters.metrics();
+        assert_eq!(metrics.frames_forwarded, 1);
+        assert_eq!(metrics.frames_dropped, 10);
+        assert_eq!(metrics.frames_queued, 3);
+    }
+
+    #[tokio::test]
+    async fn rate_limit_change_keeps_order() {
+        let (tx, mut rx) = write_queue(Default::default());
+        let tx_handle = TxHandle::detached(tx);
+        let counters = Arc::new(SubscriptionCounters::default());
+
+        let forwarder = SubscriptionForwarder::new(CoreSubscriptionRateLimit {
+            latency: Duration::from_millis(200),
+            ..Default::default()
+        });
+
+        forwarder
+            .forward("subscriber", tx_handle.clone(), packet(0), counters.clone())
+            .await;
+        forwarder.set_rate_limit(Default::default());
+        // Waits for the first frame.
+        forwarder
+            .forward("subscriber", tx_handle.clone(), packet(1), counters.clone())
+            .await;
+
+        for index in 0..2 {
+            assert_eq!(rx.recv().await.unwrap().bytes.as_ref(), [index as u8]);
+        }
+
+        // Sent directly, stopping the task.
+        forwarder
+            .forward("subscriber", tx_handle, packet(2), counters)
+            .await;
+        assert!(forwarder.lock().tx.is_none());
+        assert_eq!(rx.recv().await.unwrap().bytes.as_ref(), [2]);
+    }
+}
@@ -0,0 +1,83 @@
+//! Counters of a subscription, updated by the read task of the master and the forwarder of the subscription.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use super::model::CoreSubscriptionMetrics;
+
+#[derive(Debug, Default)]
+pub struct SubscriptionCounters {
+    bytes_forwarded: AtomicU64,
+    frames_forwarded: AtomicU64,
+    send_failures: AtomicU64,
+    frames_dropped: AtomicU64,
+    frames_queued: AtomicU64,
+    /// `0` means nothing was forwarded yet.
+    last_forward_timestamp_micros: AtomicU64,
+}
+
+impl SubscriptionCounters {
+    /// Counts a frame of `len` bytes sent to the subscriber at `timestamp_micros`.
+    pub fn forwarded(&self, len: usize, timestamp_micros: u64) {
+        self.bytes_forwarded
+            .fetch_add(len as u64, Ordering::Relaxed);
+        self.frames_forwarded.fetch_add(1, Ordering::Relaxed);
+        self.last_forward_timestamp_micros
+            .fetch_max(timestamp_micros, Ordering::Relaxed);
+    }
+
+    /// Counts a frame that could not be sent to the subscriber.
+    pub fn failed(&self) {
+        self.send_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a frame dropped by the rate limit or because the queue is full.
+    pub fn dropped(&self) {
+        self.frames_dropped.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a frame waiting for the rate limit or for room in the write queue of the subscriber.
+    pub fn queued(&self) {
+        self.frames_queued.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts a frame no longer waiting.
+    pub fn dequeued(&self) {
+        self.frames_queued.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    pub fn metrics(&self) -> CoreSubscriptionMetrics {
+        let last_forward_timestamp_micros =
+            self.last_forward_timestamp_micros.load(Ordering::Relaxed);
+
+        CoreSubscriptionMetrics {
+            bytes_forwarded: self.bytes_forwarded.load(Ordering::Relaxed),
+            frames_forwarded: self.frames_forwarded.load(Ordering::Relaxed),
+            send_failures: self.send_failures.load(Ordering::Relaxed),
+            frames_dropped: self.frames_dropped.load(Ordering::Relaxed),
+            frames_queued: self.frames_queued.load(Ordering::Relaxed),
+            last_forward_timestamp_micros: (last_forward_timestamp_micros != 0)
+                .then_some(last_forward_timestamp_micros),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_forwarded_and_failed_frames() {
+        let counters = SubscriptionCounters::default();
+        assert_eq!(counters.metrics().last_forward_timestamp_micros, None);
+
+        counters.forwarded(5, 10);
+        counters.forwarded(3, 20);
+        counters.failed();
+
+        let metrics = counters.metrics();
+        assert_eq!(metrics.bytes_forwarded, 8);
+        assert_eq!(metrics.frames_forwarded, 2);
+        assert_eq!(metrics.send_failures, 1);
+        assert_eq!(metrics.last_forward_timestamp_micros, Some(20));
+    }
+}
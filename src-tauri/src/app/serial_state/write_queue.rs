@@ -0,0 +1,309 @@
+//! Queue of the packets waiting to be written to an open serial port.
+//!
+//! Unlike an unbounded channel, the queue can be bounded. See [`CoreWriteQueueOptions`].
+
+use std::{
+    collections::VecDeque,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, MutexGuard,
+    },
+};
+
+use tokio::sync::Notify;
+
+use super::model::{
+    CoreOutgoingPacket, CoreWriteQueueMetrics, CoreWriteQueueOptions, CoreWriteQueueOverflow,
+};
+
+/// Creates a queue with a single sender.
+pub fn write_queue(options: CoreWriteQueueOptions) -> (WriteQueueSender, WriteQueueReceiver) {
+    let shared = Arc::new(Shared {
+        inner: Mutex::new(Inner {
+            packets: VecDeque::new(),
+            senders: 1,
+            receiver_dropped: false,
+        }),
+        options,
+        pushed: Notify::new(),
+        popped: Notify::new(),
+        dropped: AtomicU64::new(0),
+    });
+
+    (
+        WriteQueueSender {
+            shared: shared.clone(),
+        },
+        WriteQueueReceiver { shared },
+    )
+}
+
+#[derive(Debug)]
+struct Shared {
+    inner: Mutex<Inner>,
+    options: CoreWriteQueueOptions,
+    /// Notified when a packet is pushed or the last sender is dropped.
+    pushed: Notify,
+    /// Notified when a packet is popped or the receiver is dropped.
+    popped: Notify,
+    /// Packets dropped due to [`CoreWriteQueueOverflow::DropOldest`] or [`CoreWriteQueueOverflow::DropNewest`]
+    /// or discarded with [`WriteQueueReceiver::discarded`].
+    dropped: AtomicU64,
+}
+
+#[derive(Debug)]
+struct Inner {
+    packets: VecDeque<CoreOutgoingPacket>,
+    senders: usize,
+    receiver_dropped: bool,
+}
+
+impl Shared {
+    /// The lock is never held across an await point. A poisoned lock still holds a valid value.
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn is_full(&self, inner: &Inner) -> bool {
+        self.options.capacity != 0 && inner.packets.len() >= self.options.capacity
+    }
+}
+
+/// Cloning the sender is cheap. The receiver is closed when all senders are dropped.
+#[derive(Debug)]
+pub struct WriteQueueSender {
+    shared: Arc<Shared>,
+}
+
+impl WriteQueueSender {
+    /// Pushes the `packet` to the queue, applying the [`CoreWriteQueueOverflow`] policy if the queue is full.
+    ///
+    /// Only waits with [`CoreWriteQueueOverflow::Block`].
+    /// Fails with [`WriteQueueError::Dropped`] if the `packet` itself is dropped.
+    pub async fn send(&self, packet: CoreOutgoingPacket) -> Result<(), WriteQueueError> {
+        loop {
+            let popped = self.shared.popped.notified();
+            tokio::pin!(popped);
+            // Register before checking the queue, so that a pop in between is not missed.
+            popped.as_mut().enable();
+
+            {
+                let mut inner = self.shared.lock();
+
+                if inner.receiver_dropped {
+                    return Err(WriteQueueError::Closed);
+                }
+
+                if self.shared.is_full(&inner) {
+                    match self.shared.options.overflow {
+                        CoreWriteQueueOverflow::Block => {}
+                        CoreWriteQueueOverflow::DropOldest => {
+                            inner.packets.pop_front();
+                            inner.packets.push_back(packet);
+                            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+
+                            return Ok(());
+                        }
+                        CoreWriteQueueOverflow::DropNewest => {
+                            self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+
+                            return Err(WriteQueueError::Dropped);
+                        }
+                        CoreWriteQueueOverflow::Error => return Err(WriteQueueError::Full),
+                    }
+                } else {
+                    inner.packets.push_back(packet);
+                    self.shared.pushed.notify_one();
+
+                    return Ok(());
+                }
+            }
+
+            popped.await;
+        }
+    }
+
+    pub fn metrics(&self) -> CoreWriteQueueMetrics {
+        CoreWriteQueueMetrics {
+            depth: self.shared.lock().packets.len(),
+            capacity: self.shared.options.capacity,
+            dropped: self.shared.dropped.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Clone for WriteQueueSender {
+    fn clone(&self) -> Self {
+        self.shared.lock().senders += 1;
+
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl Drop for WriteQueueSender {
+    fn drop(&mut self) {
+        let mut inner = self.shared.lock();
+
+        inner.senders -= 1;
+
+        if inner.senders == 0 {
+            self.shared.pushed.notify_one();
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct WriteQueueReceiver {
+    shared: Arc<Shared>,
+}
+
+impl WriteQueueReceiver {
+    /// Returns `None` once the queue is empty and all senders are dropped.
+    pub async fn recv(&mut self) -> Option<CoreOutgoingPacket> {
+        loop {
+            let pushed = self.shared.pushed.notified();
+            tokio::pin!(pushed);
+            pushed.as_mut().enable();
+
+            {
+                let mut inner = self.shared.lock();
+
+                if let Some(packet) = inner.packets.pop_front() {
+                    self.shared.popped.notify_waiters();
+
+                    return Some(packet);
+                }
+
+                if inner.senders == 0 {
+                    return None;
+                }
+            }
+
+            pushed.await;
+        }
+    }
+
+    /// Counts a received packet that is not written in the metrics.
+    pub fn discarded(&self) {
+        self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+impl Drop for WriteQueueReceiver {
+    /// Wakes up the blocked senders.
+    fn drop(&mut self) {
+        let mut inner = self.shared.lock();
+
+        inner.receiver_dropped = true;
+        inner.packets.clear();
+
+        self.shared.popped.notify_waiters();
+    }
+}
+
+/// Error returned by [`WriteQueueSender::send`].
+#[derive(Debug, thiserror::Error)]
+pub enum WriteQueueError {
+    #[error("Write queue closed")]
+    Closed,
+    #[error("Write queue full")]
+    Full,
+    #[error("Write queue full, packet dropped")]
+    Dropped,
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use tokio_util::bytes::Bytes;
+
+    use super::*;
+
+    fn packet(bytes: &'static [u8]) -> CoreOutgoingPacket {
+        CoreOutgoingPacket {
+            bytes: Bytes::from_static(bytes),
+            ..Default::default()
+        }
+    }
+
+    fn queue(overflow: CoreWriteQueueOverflow) -> (WriteQueueSender, WriteQueueReceiver) {
+        write_queue(CoreWriteQueueOptions {
+            capacity: 2,
+            overflow,
+        })
+    }
+
+    #[tokio::test]
+    async fn drop_oldest_and_newest() {
+        let (tx, mut rx) = queue(CoreWriteQueueOverflow::DropOldest);
+        for bytes in [b"a", b"b", b"c"] {
+            tx.send(packet(bytes)).await.unwrap();
+        }
+        assert_eq!(tx.metrics().dropped, 1);
+        assert_eq!(rx.recv().await.unwrap().bytes, "b");
+
+        let (tx, mut rx) = queue(CoreWriteQueueOverflow::DropNewest);
+        tx.send(packet(b"a")).await.unwrap();
+        tx.send(packet(b"b")).await.unwrap();
+        assert!(matches!(
+            tx.send(packet(b"c")).await,
+            Err(WriteQueueError::Dropped)
+        ));
+        assert_eq!(tx.metrics().dropped, 1);
+        assert_eq!(tx.metrics().depth, 2);
+        assert_eq!(rx.recv().await.unwrap().bytes, "a");
+        assert_eq!(rx.recv().await.unwrap().bytes, "b");
+    }
+
+    #[tokio::test]
+    async fn error_when_full() {
+        let (tx, _rx) = queue(CoreWriteQueueOverflow::Error);
+        tx.send(packet(b"a")).await.unwrap();
+        tx.send(packet(b"b")).await.unwrap();
+
+        assert!(matches!(
+            tx.send(packet(b"c")).await,
+            Err(WriteQueueError::Full)
+        ));
+    }
+
+    #[tokio::test]
+    async fn block_until_popped() {
+        let (tx, mut rx) = queue(CoreWriteQueueOverflow::Block);
+        tx.send(packet(b"a")).await.unwrap();
+        tx.send(packet(b"b")).await.unwrap();
+
+        let blocked = tokio::time::timeout(Duration::from_millis(10), tx.send(packet(b"c"))).await;
+        assert!(blocked.is_err());
+
+        let sender = tokio::spawn(async move { tx.send(packet(b"c")).await });
+        assert_eq!(rx.recv().await.unwrap().bytes, "a");
+        sender.await.unwrap().unwrap();
+
+        assert_eq!(rx.recv().await.unwrap().bytes, "b");
+        assert_eq!(rx.recv().await.unwrap().bytes, "c");
+        // All senders are dropped.
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn closed_wakes_blocked_sender() {
+        let (tx, rx) = queue(CoreWriteQueueOverflow::Block);
+        tx.send(packet(b"a")).await.unwrap();
+        tx.send(packet(b"b")).await.unwrap();
+
+        let sender = tokio::spawn(async move { tx.send(packet(b"c")).await });
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        drop(rx);
+
+        assert!(matches!(
+            sender.await.unwrap(),
+            Err(WriteQueueError::Closed)
+        ));
+    }
+}
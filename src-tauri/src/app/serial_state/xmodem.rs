@@ -0,0 +1,612 @@
+//! XMODEM-CRC and YMODEM transfers over a [`CoreExclusiveSerialPort`].
+
+use std::time::Duration;
+
+use tokio::{
+    io::{AsyncRead, AsyncReadExt},
+    sync::mpsc::UnboundedSender as MPSCUnboundedSender,
+    time::Instant,
+};
+
+use super::{
+    error::{CoreExclusiveReadError, CoreXmodemError},
+    handle::CoreExclusiveSerialPort,
+    model::{CoreTransferEvent, CoreXmodemProtocol},
+};
+
+const SOH: u8 = 0x01;
+const STX: u8 = 0x02;
+const EOT: u8 = 0x04;
+const ACK: u8 = 0x06;
+const NAK: u8 = 0x15;
+const CAN: u8 = 0x18;
+/// Sent by the receiver to request a transfer with CRC-16 blocks.
+const CRC_REQUEST: u8 = b'C';
+/// Pads the last block of a file.
+const SUB: u8 = 0x1A;
+
+const BLOCK_SIZE: usize = 128;
+const BLOCK_SIZE_1K: usize = 1024;
+const MAX_RETRIES: usize = 10;
+/// Time the sender waits for the receiver to request a transfer.
+const START_TIMEOUT: Duration = Duration::from_secs(60);
+/// Time the receiver waits before repeating a request for the first block.
+const START_REQUEST_INTERVAL: Duration = Duration::from_secs(3);
+/// Time to wait for the response to a block or for the next block.
+const RESPONSE_TIMEOUT: Duration = Duration::from_secs(10);
+/// Time to wait for the next byte of a block.
+const BYTE_TIMEOUT: Duration = Duration::from_secs(1);
+
+#[derive(Debug, Clone, Copy)]
+enum Checksum {
+    /// CRC-16/XMODEM. Requested with [`CRC_REQUEST`].
+    Crc,
+    /// Arithmetic sum of the bytes. Requested with [`NAK`].
+    Sum,
+}
+
+impl Checksum {
+    fn len(self) -> usize {
+        match self {
+            Self::Crc => 2,
+            Self::Sum => 1,
+        }
+    }
+
+    fn compute(self, data: &[u8]) -> Vec<u8> {
+        match self {
+            Self::Crc => crc16(data).to_be_bytes().to_vec(),
+            Self::Sum => vec![data.iter().fold(0u8, |sum, byte| sum.wrapping_add(*byte))],
+        }
+    }
+}
+
+/// CRC-16/XMODEM.
+fn crc16(data: &[u8]) -> u16 {
+    data.iter().fold(0, |crc, byte| {
+        let mut crc = crc ^ ((*byte as u16) << 8);
+
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+
+        crc
+    })
+}
+
+/// File received with [`receive`].
+#[derive(Debug)]
+pub struct CoreXmodemReceivedFile {
+    /// Only transmitted by YMODEM. Contains no path components.
+    pub name: Option<String>,
+    pub data: Vec<u8>,
+}
+
+/// Sends the `size` bytes read from `file` as the file `file_name`. The file name and the size are only transmitted by YMODEM.
+///
+/// The file is read block by block while sending.
+///
+/// Cancels the transfer on the remote if it fails locally.
+pub async fn send<R>(
+    port: &mut CoreExclusiveSerialPort,
+    protocol: CoreXmodemProtocol,
+    file_name: &str,
+    file: &mut R,
+    size: u64,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<(), CoreXmodemError>
+where
+    R: AsyncRead + Unpin,
+{
+    tracing::debug!(name=%port.name(), %protocol, %file_name, size, "Sending");
+
+    let result = match protocol {
+        CoreXmodemProtocol::Xmodem => send_xmodem(port, file, size, event_tx).await,
+        CoreXmodemProtocol::Ymodem => send_ymodem(port, file_name, file, size, event_tx).await,
+    };
+
+    cancel_on_error(port, result).await
+}
+
+/// Receives a single file.
+///
+/// Cancels the transfer on the remote if it fails locally.
+pub async fn receive(
+    port: &mut CoreExclusiveSerialPort,
+    protocol: CoreXmodemProtocol,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<CoreXmodemReceivedFile, CoreXmodemError> {
+    tracing::debug!(name=%port.name(), %protocol, "Receiving");
+
+    let result = match protocol {
+        CoreXmodemProtocol::Xmodem => receive_xmodem(port, event_tx).await,
+        CoreXmodemProtocol::Ymodem => receive_ymodem(port, event_tx).await,
+    };
+
+    cancel_on_error(port, result).await
+}
+
+/// Asks the remote to abort the transfer. Fails silently.
+pub async fn cancel(port: &CoreExclusiveSerialPort) {
+    tracing::debug!(name=%port.name(), "Cancelling remote transfer");
+
+    let _ = port.write(&[CAN, CAN, CAN]).await;
+}
+
+async fn cancel_on_error<T>(
+    port: &CoreExclusiveSerialPort,
+    result: Result<T, CoreXmodemError>,
+) -> Result<T, CoreXmodemError> {
+    if let Err(err) = &result {
+        tracing::warn!(name=%port.name(), %err, "Transfer failed");
+
+        if !matches!(err, CoreXmodemError::RemoteCancelled) {
+            cancel(port).await;
+        }
+    }
+
+    result
+}
+
+async fn send_xmodem<R>(
+    port: &mut CoreExclusiveSerialPort,
+    file: &mut R,
+    size: u64,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<(), CoreXmodemError>
+where
+    R: AsyncRead + Unpin,
+{
+    let checksum = wait_for_request(port).await?;
+
+    send_blocks(port, checksum, BLOCK_SIZE, file, size, event_tx).await?;
+    send_eot(port).await
+}
+
+async fn send_ymodem<R>(
+    port: &mut CoreExclusiveSerialPort,
+    file_name: &str,
+    file: &mut R,
+    size: u64,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<(), CoreXmodemError>
+where
+    R: AsyncRead + Unpin,
+{
+    let checksum = wait_for_request(port).await?;
+    send_block(port, checksum, 0, &header_block(file_name, size)).await?;
+
+    let checksum = wait_for_request(port).await?;
+    send_blocks(port, checksum, BLOCK_SIZE_1K, file, size, event_tx).await?;
+    send_eot(port).await?;
+
+    // An empty header ends the batch.
+    let checksum = wait_for_request(port).await?;
+    send_block(port, checksum, 0, &[0; BLOCK_SIZE]).await
+}
+
+/// `name NUL size NUL` padded with `NUL`.
+fn header_block(file_name: &str, size: u64) -> Vec<u8> {
+    let mut block = Vec::with_capacity(BLOCK_SIZE);
+
+    block.extend_from_slice(file_name.as_bytes());
+    block.push(0);
+    block.extend_from_slice(size.to_string().as_bytes());
+    block.push(0);
+
+    let block_size = if block.len() <= BLOCK_SIZE {
+        BLOCK_SIZE
+    } else {
+        BLOCK_SIZE_1K
+    };
+
+    block.resize(block_size, 0);
+
+    block
+}
+
+/// Waits for the receiver to request a transfer or the next file.
+async fn wait_for_request(port: &mut CoreExclusiveSerialPort) -> Result<Checksum, CoreXmodemError> {
+    let deadline = Instant::now() + START_TIMEOUT;
+
+    loop {
+        let timeout = deadline.saturating_duration_since(Instant::now());
+
+        let checksum = match port.read_byte(timeout).await? {
+            CRC_REQUEST => Checksum::Crc,
+            NAK => Checksum::Sum,
+            CAN => return Err(CoreXmodemError::RemoteCancelled),
+            _ => continue,
+        };
+
+        // Receivers repeat their request until the first block arrives.
+        port.clear_input();
+
+        return Ok(checksum);
+    }
+}
+
+/// - `Ok(true)` => Acknowledged.
+/// - `Ok(false)` => Not acknowledged or timed out. The block should be sent again.
+async fn wait_for_ack(port: &mut CoreExclusiveSerialPort) -> Result<bool, CoreXmodemError> {
+    loop {
+        match port.read_byte(RESPONSE_TIMEOUT).await {
+            Ok(ACK) => return Ok(true),
+            Ok(NAK | CRC_REQUEST) | Err(CoreExclusiveReadError::Timeout) => return Ok(false),
+            Ok(CAN) => return Err(CoreXmodemError::RemoteCancelled),
+            Ok(_) => {}
+            Err(err) => return Err(err.into()),
+        }
+    }
+}
+
+async fn send_block(
+    port: &mut CoreExclusiveSerialPort,
+    checksum: Checksum,
+    number: u8,
+    data: &[u8],
+) -> Result<(), CoreXmodemError> {
+    let mut block = Vec::with_capacity(data.len() + 3 + checksum.len());
+
+    block.push(if data.len() == BLOCK_SIZE_1K {
+        STX
+    } else {
+        SOH
+    });
+    block.push(number);
+    block.push(!number);
+    block.extend_from_slice(data);
+    block.extend_from_slice(&checksum.compute(data));
+
+    for _ in 0..MAX_RETRIES {
+        tracing::trace!(target: "serial_core::serial::xmodem", name=%port.name(), number, "Sending block");
+
+        port.write(&block).await?;
+
+        if wait_for_ack(port).await? {
+            return Ok(());
+        }
+    }
+
+    Err(CoreXmodemError::TooManyRetries)
+}
+
+async fn send_blocks<R>(
+    port: &mut CoreExclusiveSerialPort,
+    checksum: Checksum,
+    block_size: usize,
+    file: &mut R,
+    size: u64,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<(), CoreXmodemError>
+where
+    R: AsyncRead + Unpin,
+{
+    let mut bytes_transferred = 0;
+    // Block numbers start at 1 and wrap around.
+    let mut number: u8 = 1;
+
+    loop {
+        let mut block = Vec::with_capacity(block_size);
+
+        (&mut *file)
+            .take(block_size as u64)
+            .read_to_end(&mut block)
+            .await
+            .map_err(CoreXmodemError::File)?;
+
+        if block.is_empty() {
+            return Ok(());
+        }
+
+        let len = block.len() as u64;
+        block.resize(block_size, SUB);
+
+        send_block(port, checksum, number, &block).await?;
+
+        number = number.wrapping_add(1);
+        bytes_transferred += len;
+
+        let _ = event_tx.send(CoreTransferEvent::Progress {
+            bytes_transferred,
+            bytes_total: Some(size),
+        });
+    }
+}
+
+async fn send_eot(port: &mut CoreExclusiveSerialPort) -> Result<(), CoreXmodemError> {
+    // YMODEM receivers do not acknowledge the first EOT.
+    for _ in 0..MAX_RETRIES {
+        port.write(&[EOT]).await?;
+
+        if wait_for_ack(port).await? {
+            return Ok(());
+        }
+    }
+
+    Err(CoreXmodemError::TooManyRetries)
+}
+
+async fn receive_xmodem(
+    port: &mut CoreExclusiveSerialPort,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<CoreXmodemReceivedFile, CoreXmodemError> {
+    let mut data = receive_blocks(port, false, None, event_tx).await?;
+
+    // XMODEM does not transmit the size of the file.
+    strip_padding(&mut data);
+
+    Ok(CoreXmodemReceivedFile { name: None, data })
+}
+
+async fn receive_ymodem(
+    port: &mut CoreExclusiveSerialPort,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<CoreXmodemReceivedFile, CoreXmodemError> {
+    let (name, size) = parse_header_block(&receive_header_block(port).await?)?;
+
+    let mut data = receive_blocks(port, true, size, event_tx).await?;
+
+    match size {
+        Some(size) => data.truncate(size as usize),
+        None => strip_padding(&mut data),
+    }
+
+    // We only receive a single file. The sender ends the batch with an empty header.
+    receive_header_block(port).await?;
+
+    Ok(CoreXmodemReceivedFile {
+        name: Some(name),
+        data,
+    })
+}
+
+fn strip_padding(data: &mut Vec<u8>) {
+    let len = data
+        .iter()
+        .rposition(|byte| *byte != SUB)
+        .map_or(0, |position| position + 1);
+
+    data.truncate(len);
+}
+
+/// Returns the file name without path components and the size, if any.
+fn parse_header_block(block: &[u8]) -> Result<(String, Option<u64>), CoreXmodemError> {
+    let mut fields = block.split(|byte| *byte == 0);
+
+    let name = fields.next().unwrap_or_default();
+
+    if name.is_empty() {
+        return Err(CoreXmodemError::NoFile);
+    }
+
+    let name = String::from_utf8_lossy(name);
+    let name = name
+        .rsplit(['/', '\\'])
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    // The size may be followed by more fields separated by spaces.
+    let size = fields
+        .next()
+        .and_then(|field| std::str::from_utf8(field).ok())
+        .and_then(|field| field.split(' ').next())
+        .and_then(|size| size.parse().ok());
+
+    Ok((name, size))
+}
+
+enum Received {
+    Block { number: u8, data: Vec<u8> },
+    Eot,
+}
+
+/// - `Ok(Some(_))` => Received a valid block or an EOT.
+/// - `Ok(None)` => Received garbage or a corrupted block.
+async fn receive_block(
+    port: &mut CoreExclusiveSerialPort,
+    checksum: Checksum,
+    timeout: Duration,
+) -> Result<Option<Received>, CoreXmodemError> {
+    let block_size = match port.read_byte(timeout).await? {
+        SOH => BLOCK_SIZE,
+        STX => BLOCK_SIZE_1K,
+        EOT => return Ok(Some(Received::Eot)),
+        CAN => return Err(CoreXmodemError::RemoteCancelled),
+        _ => return Ok(None),
+    };
+
+    let number = port.read_byte(BYTE_TIMEOUT).await?;
+    let complement = port.read_byte(BYTE_TIMEOUT).await?;
+    let data = port.read_exact(block_size, BYTE_TIMEOUT).await?;
+    let received_checksum = port.read_exact(checksum.len(), BYTE_TIMEOUT).await?;
+
+    if number != !complement || received_checksum != checksum.compute(&data) {
+        tracing::trace!(target: "serial_core::serial::xmodem", name=%port.name(), number, "Received corrupted block");
+
+        return Ok(None);
+    }
+
+    Ok(Some(Received::Block { number, data }))
+}
+
+async fn receive_header_block(
+    port: &mut CoreExclusiveSerialPort,
+) -> Result<Vec<u8>, CoreXmodemError> {
+    for _ in 0..MAX_RETRIES {
+        port.write(&[CRC_REQUEST]).await?;
+
+        match receive_block(port, Checksum::Crc, START_REQUEST_INTERVAL).await {
+            Ok(Some(Received::Block { number: 0, data })) => {
+                port.write(&[ACK]).await?;
+
+                return Ok(data);
+            }
+            // Our acknowledgement of the EOT got lost.
+            Ok(Some(Received::Eot)) => port.write(&[ACK]).await?,
+            Ok(_) => port.clear_input(),
+            Err(CoreXmodemError::Read(CoreExclusiveReadError::Timeout)) => {}
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(CoreXmodemError::TooManyRetries)
+}
+
+/// Receives blocks starting at block number 1 until EOT.
+///
+/// YMODEM senders expect the first EOT to be answered with a NAK.
+async fn receive_blocks(
+    port: &mut CoreExclusiveSerialPort,
+    ymodem: bool,
+    bytes_total: Option<u64>,
+    event_tx: &MPSCUnboundedSender<CoreTransferEvent>,
+) -> Result<Vec<u8>, CoreXmodemError> {
+    let mut data = Vec::new();
+    let mut expected: u8 = 1;
+    let mut started = false;
+    let mut retries = 0;
+    let mut eot_received = false;
+
+    port.write(&[CRC_REQUEST]).await?;
+
+    loop {
+        let timeout = if started {
+            RESPONSE_TIMEOUT
+        } else {
+            START_REQUEST_INTERVAL
+        };
+
+        match receive_block(port, Checksum::Crc, timeout).await {
+            Ok(Some(Received::Block {
+                number,
+                data: block,
+            })) if number == expected => {
+                started = true;
+                retries = 0;
+
+                data.extend_from_slice(&block);
+                expected = expected.wrapping_add(1);
+
+                let bytes_transferred = bytes_total.map_or(data.len() as u64, |bytes_total| {
+                    bytes_total.min(data.len() as u64)
+                });
+
+                let _ = event_tx.send(CoreTransferEvent::Progress {
+                    bytes_transferred,
+                    bytes_total,
+                });
+
+                port.write(&[ACK]).await?;
+            }
+            // Our acknowledgement of the previous block got lost.
+            Ok(Some(Received::Block { number, .. })) if number == expected.wrapping_sub(1) => {
+                port.write(&[ACK]).await?;
+            }
+            Ok(Some(Received::Block { number, .. })) => {
+                return Err(CoreXmodemError::BlockOutOfSequence {
+                    expected,
+                    received: number,
+                });
+            }
+            Ok(Some(Received::Eot)) if ymodem && !eot_received => {
+                eot_received = true;
+
+                port.write(&[NAK]).await?;
+            }
+            Ok(Some(Received::Eot)) => {
+                port.write(&[ACK]).await?;
+
+                return Ok(data);
+            }
+            Ok(None) | Err(CoreXmodemError::Read(CoreExclusiveReadError::Timeout)) => {
+                retries += 1;
+
+                if retries > MAX_RETRIES {
+                    return Err(CoreXmodemError::TooManyRetries);
+                }
+
+                port.clear_input();
+                port.write(&[if started { NAK } else { CRC_REQUEST }])
+                    .await?;
+            }
+            Err(err) => return Err(err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crc16_check_value() {
+        assert_eq!(crc16(b"123456789"), 0x31C3);
+    }
+
+    #[test]
+    fn header_block_round_trip() {
+        let block = header_block("firmware.bin", 1234);
+
+        assert_eq!(block.len(), BLOCK_SIZE);
+        assert_eq!(
+            parse_header_block(&block).unwrap(),
+            ("firmware.bin".to_string(), Some(1234))
+        );
+        assert!(matches!(
+            parse_header_block(&[0; BLOCK_SIZE]),
+            Err(CoreXmodemError::NoFile)
+        ));
+    }
+
+    async fn round_trip(protocol: CoreXmodemProtocol, data: Vec<u8>) -> CoreXmodemReceivedFile {
+        let (mut sender, mut receiver) = CoreExclusiveSerialPort::connected_pair();
+        let (event_tx, _event_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        let receive_event_tx = event_tx.clone();
+        let received =
+            tokio::spawn(async move { receive(&mut receiver, protocol, &receive_event_tx).await });
+
+        send(
+            &mut sender,
+            protocol,
+            "firmware.bin",
+            &mut data.as_slice(),
+            data.len() as u64,
+            &event_tx,
+        )
+        .await
+        .unwrap();
+
+        received.await.unwrap().unwrap()
+    }
+
+    fn test_data() -> Vec<u8> {
+        (0..3000).map(|index| (index % 251) as u8).collect()
+    }
+
+    #[tokio::test]
+    async fn xmodem_round_trip() {
+        let received = round_trip(CoreXmodemProtocol::Xmodem, test_data()).await;
+
+        assert_eq!(received.name, None);
+        assert_eq!(received.data, test_data());
+    }
+
+    #[tokio::test]
+    async fn ymodem_round_trip() {
+        let mut data = test_data();
+        // Would be stripped as padding by XMODEM.
+        data.push(SUB);
+
+        let received = round_trip(CoreXmodemProtocol::Ymodem, data.clone()).await;
+
+        assert_eq!(received.name.as_deref(), Some("firmware.bin"));
+        assert_eq!(received.data, data);
+    }
+}
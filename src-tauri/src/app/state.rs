@@ -1,55 +1,125 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
 
+#[cfg(feature = "subscriptions")]
+use error::AppSubscriptionError;
 use error::{
-    AppAddPacketError, AppGetOpenSerialPortOptionsError, AppManagedSerialPortsError,
-    AppOpenSerialPortError, AppPacketError,
+    AppAutoResponderError, AppCommandMacroError, AppExecuteCommandMacroError,
+    AppGetDatabaseStatsError, AppGetOpenSerialPortOptionsError, AppGetPacketsError,
+    AppManagedSerialPortsError, AppOpenSerialPortError, AppPacketError, AppPortGroupError,
+    AppPortGroupOperationError, AppRetentionError, AppRunScriptError, AppScriptError,
 };
-use tokio::sync::mpsc::UnboundedReceiver as MPSCUnboundedReceiver;
+use tokio::sync::{mpsc::UnboundedReceiver as MPSCUnboundedReceiver, RwLock};
+use tokio_util::{bytes::Bytes, sync::CancellationToken};
 
 use crate::{app::database::database_service::DatabaseService, serial_manager::SerialManager};
 
 use super::{
     database::Database,
-    model::managed_serial_port::{AppManagedSerialPort, AppOpenSerialPortOptions},
-    serial_state::{model::CorePacket, CoreSerialState},
+    model::{
+        auto_responder::AppAutoResponder,
+        command_macro::{
+            AppCommandMacro, AppCommandMacroExecutionTarget, AppCommandMacroOutcome,
+            AppCommandMacroTarget,
+        },
+        managed_serial_port::{AppManagedSerialPort, AppOpenSerialPortOptions},
+        packet::{AppPacketPage, AppPacketQuery, AppPacketWriterMetrics},
+        port_group::{AppPortGroup, AppPortGroupOutcome},
+        retention::{AppDatabaseStats, AppPruneReport, AppRetentionPolicy, AppRetentionScope},
+        script::{AppScript, AppScriptEvent, AppScriptRun},
+    },
+    packet_writer::{AppPacketWriter, AppPacketWriterOptions},
+    script::{AppScriptHooks, AppScriptRunHandle},
+    serial_state::{
+        model::{
+            CoreGroupPacketOrigin, CoreMacroPacketOrigin, CoreOutgoingPacket, CorePacket,
+            CorePacketOrigin,
+        },
+        CoreSerialState,
+    },
+};
+
+#[cfg(feature = "subscriptions")]
+use super::{
+    model::subscription::{AppSubscription, AppSubscriptionFilter, AppSubscriptionRateLimit},
+    serial_state::{
+        error::CoreSubscribeError,
+        model::{CoreSubscriptionFilter, CoreSubscriptionMode, CoreSubscriptionOptions},
+    },
 };
 
 pub mod error;
 
+/// How often the background task prunes the stored packets.
+const PRUNE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
 /// Intended to save the packets and open options for serial ports.
 #[derive(Debug, Clone)]
 pub struct AppState {
     serial_state: CoreSerialState,
     db: Database,
+    /// Tags of the serial ports by name, as they were last opened.
+    ///
+    /// May contain closed serial ports.
+    tags: Arc<RwLock<HashMap<String, String>>>,
+    /// Script runs are removed when they finish, fail or are cancelled.
+    script_runs: Arc<RwLock<HashMap<u64, AppScriptRunHandle>>>,
+    next_script_run_id: Arc<AtomicU64>,
+    packet_writer: AppPacketWriter,
 }
 
 // TODO: do the chaching for the serial port ids
 impl AppState {
     pub fn new(db: Database, serial_manager: SerialManager) -> Self {
         let serial_state = CoreSerialState::new(serial_manager);
+        let packet_writer = AppPacketWriter::new(db.clone(), AppPacketWriterOptions::default());
 
-        Self { serial_state, db }
+        Self {
+            serial_state,
+            packet_writer,
+            db,
+            tags: Default::default(),
+            script_runs: Default::default(),
+            next_script_run_id: Default::default(),
+        }
     }
 
     pub fn serial_state(&self) -> &CoreSerialState {
         &self.serial_state
     }
 
-    /// Get the packets for the `port_name`.
-    pub async fn get_packets(&self, port_name: &str) -> Result<CorePacket, AppAddPacketError> {
-        // TODO: Implement this.
+    /// Returns a page of the stored packets matching the `query`, newest first.
+    pub async fn get_packets(
+        &self,
+        query: AppPacketQuery,
+    ) -> Result<AppPacketPage, AppGetPacketsError> {
+        tracing::debug!(?query, "Getting packets");
+
+        let page = self.db.get_packets(query).await?;
 
-        Ok(CorePacket::default())
+        Ok(page)
     }
 
     /// Get the open options for the `port_name`.
+    ///
+    /// Returns the default options if the serial port was never opened.
     pub async fn get_open_serial_port_options(
         &self,
         port_name: &str,
     ) -> Result<AppOpenSerialPortOptions, AppGetOpenSerialPortOptionsError> {
-        // TODO: Implement this.
+        let options = self
+            .db
+            .get_open_serial_port_options(port_name)
+            .await?
+            .unwrap_or_default();
 
-        Ok(AppOpenSerialPortOptions::default())
+        Ok(options)
     }
 
     /// Get all the open options for all serial ports.
@@ -58,9 +128,9 @@ impl AppState {
     pub async fn get_all_open_serial_port_options(
         &self,
     ) -> Result<HashMap<String, AppOpenSerialPortOptions>, AppGetOpenSerialPortOptionsError> {
-        // TODO: Implement this.
+        let options = self.db.get_all_open_serial_port_options().await?;
 
-        Ok(HashMap::new())
+        Ok(options)
     }
 
     pub async fn get_managed_serial_ports(
@@ -113,30 +183,22 @@ impl AppState {
             .open_serial_port(name, options.core_options)
             .await?;
 
+        self.tags
+            .write()
+            .await
+            .insert(name.to_string(), tag.clone());
+
         let (tx, rx) = tokio::sync::mpsc::unbounded_channel::<Result<CorePacket, AppPacketError>>();
 
         let name = name.to_string();
-        let db = self.db.clone();
+        let packet_writer = self.packet_writer.clone();
         tokio::spawn(async move {
             tracing::debug!(name=%name, "Read events task started");
 
             while let Some(packet) = core_rx.recv().await {
                 match packet {
                     Ok(packet) => {
-                        // TODO: maybe some buffering
-                        match db
-                            .insert_packet_returning_id(port_id, tag.clone(), packet.clone())
-                            .await
-                        {
-                            Ok(id) => {
-                                tracing::debug!(id, from=%name, "Packet saved");
-                            }
-                            Err(err) => {
-                                tracing::error!(%err, from=%name, "Error saving packet");
-
-                                let _ = tx.send(Err(AppPacketError::SavePacketError(err)));
-                            }
-                        }
+                        packet_writer.write(port_id, tag.clone(), packet.clone(), tx.clone());
 
                         let _ = tx.send(Ok(packet));
                     }
@@ -146,9 +208,992 @@ impl AppState {
                 }
             }
 
+            // The serial port is closed.
+            packet_writer.flush().await;
+
             tracing::debug!(name=%name, "Read events task terminated");
         });
 
         Ok(rx)
     }
+
+    /// Waits until every received packet is saved, e.g. before the application exits.
+    pub async fn flush_packets(&self) {
+        self.packet_writer.flush().await;
+    }
+
+    pub fn packet_writer_metrics(&self) -> AppPacketWriterMetrics {
+        self.packet_writer.metrics()
+    }
+
+    /// Ordered by the serial port and then by the tag, global policies first.
+    pub async fn get_retention_policies(
+        &self,
+    ) -> Result<Vec<AppRetentionPolicy>, AppRetentionError> {
+        Ok(self.db.get_retention_policies().await?)
+    }
+
+    /// Replaces the policy with the same scope. Takes effect on the next pruning.
+    pub async fn set_retention_policy(
+        &self,
+        policy: AppRetentionPolicy,
+    ) -> Result<(), AppRetentionError> {
+        tracing::debug!(?policy, "Setting retention policy");
+
+        self.db.upsert_retention_policy(policy).await?;
+
+        Ok(())
+    }
+
+    pub async fn delete_retention_policy(
+        &self,
+        scope: &AppRetentionScope,
+    ) -> Result<(), AppRetentionError> {
+        tracing::debug!(?scope, "Deleting retention policy");
+
+        if !self.db.delete_retention_policy(scope).await? {
+            return Err(AppRetentionError::NotFound);
+        }
+
+        Ok(())
+    }
+
+    /// Deletes the stored packets exceeding the limits of any retention policy.
+    ///
+    /// Received packets are saved first, so they are pruned as well.
+    /// A failing policy is logged and reported, the other policies are applied anyway.
+    ///
+    /// Deleted rows are only reused for new rows. If `vacuum` is set and packets were deleted,
+    /// the database is rebuilt to shrink the file, which may take a while for large databases.
+    pub async fn prune_packets(&self, vacuum: bool) -> Result<AppPruneReport, AppRetentionError> {
+        self.packet_writer.flush().await;
+
+        let mut report = AppPruneReport::default();
+
+        for policy in self.db.get_retention_policies().await? {
+            match self.db.prune_packets(&policy).await {
+                Ok(deleted_packets) => report.deleted_packets += deleted_packets,
+                Err(err) => {
+                    tracing::error!(%err, scope = ?policy.scope, "Error applying retention policy");
+
+                    report.failed_scopes.push(policy.scope);
+                }
+            }
+        }
+
+        if vacuum && report.deleted_packets > 0 {
+            match self.db.vacuum().await {
+                Ok(()) => report.vacuumed = true,
+                Err(err) => tracing::error!(%err, "Error vacuuming database"),
+            }
+        }
+
+        tracing::debug!(
+            deleted_packets = report.deleted_packets,
+            failed_policies = report.failed_scopes.len(),
+            vacuumed = report.vacuumed,
+            "Packets pruned"
+        );
+
+        Ok(report)
+    }
+
+    /// Prunes the stored packets every [`PRUNE_INTERVAL`], starting immediately.
+    ///
+    /// Does not vacuum, the space of the deleted packets is reused for new packets instead.
+    ///
+    /// Runs until the application exits.
+    pub async fn run_retention(&self) {
+        tracing::debug!("Retention task started");
+
+        let mut interval = tokio::time::interval(PRUNE_INTERVAL);
+        interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        loop {
+            interval.tick().await;
+
+            if let Err(err) = self.prune_packets(false).await {
+                tracing::error!(%err, "Error pruning packets");
+            }
+        }
+    }
+
+    pub async fn get_database_stats(&self) -> Result<AppDatabaseStats, AppGetDatabaseStatsError> {
+        Ok(self.db.get_database_stats().await?)
+    }
+
+    /// Returns the id and the macro, ordered by name.
+    pub async fn get_command_macros(
+        &self,
+    ) -> Result<Vec<(i32, AppCommandMacro)>, AppCommandMacroError> {
+        Ok(self.db.get_command_macros().await?)
+    }
+
+    /// Returns the id of the created macro.
+    pub async fn create_command_macro(
+        &self,
+        command_macro: AppCommandMacro,
+    ) -> Result<i32, AppCommandMacroError> {
+        tracing::debug!(?command_macro, "Creating command macro");
+
+        Ok(self
+            .db
+            .insert_command_macro_returning_id(command_macro)
+            .await?)
+    }
+
+    pub async fn update_command_macro(
+        &self,
+        id: i32,
+        command_macro: AppCommandMacro,
+    ) -> Result<(), AppCommandMacroError> {
+        tracing::debug!(id, ?command_macro, "Updating command macro");
+
+        match self.db.update_command_macro(id, command_macro).await? {
+            true => Ok(()),
+            false => Err(AppCommandMacroError::NotFound),
+        }
+    }
+
+    pub async fn delete_command_macro(&self, id: i32) -> Result<(), AppCommandMacroError> {
+        tracing::debug!(id, "Deleting command macro");
+
+        match self.db.delete_command_macro(id).await? {
+            true => Ok(()),
+            false => Err(AppCommandMacroError::NotFound),
+        }
+    }
+
+    /// Sends the macro with the given `id` to the serial ports described by `target`.
+    ///
+    /// The macro is sent to every resolved port, even if sending to some of them fails.
+    ///
+    /// Returns the serial ports the macro was sent to and the ones it failed for, in the order they were sent to.
+    pub async fn execute_command_macro(
+        &self,
+        id: i32,
+        target: AppCommandMacroExecutionTarget,
+    ) -> Result<AppCommandMacroOutcome, AppExecuteCommandMacroError> {
+        tracing::debug!(id, ?target, "Executing command macro");
+
+        let command_macro = self
+            .db
+            .get_command_macro(id)
+            .await?
+            .ok_or(AppExecuteCommandMacroError::NotFound)?;
+
+        let bytes = command_macro.input_mode.parse(&command_macro.payload)?;
+        let port_names = self
+            .resolve_command_macro_targets(&command_macro.target, target)
+            .await?;
+
+        let packet = CoreOutgoingPacket {
+            bytes,
+            packet_origin: CorePacketOrigin::Macro(CoreMacroPacketOrigin {
+                name: command_macro.name,
+                line_ending: command_macro.line_ending,
+            }),
+            ack: None,
+        };
+
+        let mut outcome = AppCommandMacroOutcome::default();
+
+        for name in port_names {
+            // Cheap clone
+            match self
+                .serial_state
+                .send_to_open_serial_port(&name, packet.clone())
+                .await
+            {
+                Some(Ok(())) => outcome.succeeded.push(name),
+                Some(Err(err)) => outcome.failed.push((name, err.to_string())),
+                None => outcome.failed.push((name, String::from("Port not open"))),
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// ## Locks
+    ///
+    /// - Read: [`Self::tags`].
+    /// - Read: [`StateInner::open_serial_ports`](crate::app::serial_state::StateInner::open_serial_ports).
+    async fn resolve_command_macro_targets(
+        &self,
+        default: &AppCommandMacroTarget,
+        target: AppCommandMacroExecutionTarget,
+    ) -> Result<Vec<String>, AppExecuteCommandMacroError> {
+        let port_names = match target {
+            AppCommandMacroExecutionTarget::Ports(names) => names,
+            AppCommandMacroExecutionTarget::AllOpen => {
+                self.serial_state.open_serial_port_names().await
+            }
+            AppCommandMacroExecutionTarget::Default => match default {
+                AppCommandMacroTarget::None => return Err(AppExecuteCommandMacroError::NoTarget),
+                AppCommandMacroTarget::Port(name) => vec![name.clone()],
+                AppCommandMacroTarget::Tag(tag) => {
+                    let tags = self.tags.read().await;
+
+                    self.serial_state
+                        .open_serial_port_names()
+                        .await
+                        .into_iter()
+                        .filter(|name| tags.get(name) == Some(tag))
+                        .collect()
+                }
+            },
+        };
+
+        if port_names.is_empty() {
+            return Err(AppExecuteCommandMacroError::NoTarget);
+        }
+
+        Ok(port_names)
+    }
+
+    /// Returns the id and the script, ordered by name.
+    pub async fn get_scripts(&self) -> Result<Vec<(i32, AppScript)>, AppScriptError> {
+        Ok(self.db.get_scripts().await?)
+    }
+
+    /// Returns the id of the created script.
+    pub async fn create_script(&self, script: AppScript) -> Result<i32, AppScriptError> {
+        tracing::debug!(name=%script.name, "Creating script");
+
+        Ok(self.db.insert_script_returning_id(script).await?)
+    }
+
+    pub async fn update_script(&self, id: i32, script: AppScript) -> Result<(), AppScriptError> {
+        tracing::debug!(id, name=%script.name, "Updating script");
+
+        match self.db.update_script(id, script).await? {
+            true => Ok(()),
+            false => Err(AppScriptError::NotFound),
+        }
+    }
+
+    pub async fn delete_script(&self, id: i32) -> Result<(), AppScriptError> {
+        tracing::debug!(id, "Deleting script");
+
+        match self.db.delete_script(id).await? {
+            true => Ok(()),
+            false => Err(AppScriptError::NotFound),
+        }
+    }
+
+    /// ## Locks
+    ///
+    /// - Read: [`Self::script_runs`].
+    pub async fn script_runs(&self) -> Vec<AppScriptRun> {
+        self.script_runs
+            .read()
+            .await
+            .values()
+            .map(|handle| handle.run().clone())
+            .collect()
+    }
+
+    /// Runs the script with the given `id` in the background.
+    ///
+    /// Returns the id of the run and a channel receiving its [`AppScriptEvent`]s.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::script_runs`].
+    pub async fn run_script(
+        &self,
+        id: i32,
+        hooks: impl AppScriptHooks,
+    ) -> Result<(u64, MPSCUnboundedReceiver<AppScriptEvent>), AppRunScriptError> {
+        let script = self
+            .db
+            .get_script(id)
+            .await?
+            .ok_or(AppRunScriptError::NotFound)?;
+
+        crate::app::script::check(&script.source)?;
+
+        let run = AppScriptRun {
+            id: self.next_script_run_id.fetch_add(1, Ordering::Relaxed),
+            script_id: id,
+            script_name: script.name,
+        };
+
+        tracing::debug!(?run, "Running script");
+
+        let run_id = run.id;
+        let cancellation_token = CancellationToken::new();
+        let (event_tx, event_rx) = tokio::sync::mpsc::unbounded_channel();
+
+        self.script_runs.write().await.insert(
+            run_id,
+            AppScriptRunHandle::new(run, cancellation_token.clone()),
+        );
+
+        let state = self.clone();
+        let hooks: Arc<dyn AppScriptHooks> = Arc::new(hooks);
+
+        tokio::spawn(async move {
+            let run_state = state.clone();
+            let run_event_tx = event_tx.clone();
+
+            let event = tokio::task::spawn_blocking(move || {
+                crate::app::script::run(
+                    run_state,
+                    script.source,
+                    hooks,
+                    run_event_tx,
+                    cancellation_token,
+                )
+            })
+            .await
+            .unwrap_or_else(|err| AppScriptEvent::Failed(err.to_string()));
+
+            tracing::debug!(run_id, ?event, "Script run terminated");
+
+            state.script_runs.write().await.remove(&run_id);
+
+            let _ = event_tx.send(event);
+        });
+
+        Ok((run_id, event_rx))
+    }
+
+    /// - `Some(())` => Cancelled.
+    /// - `None` => Script run not found.
+    ///
+    /// ## Locks
+    ///
+    /// - Write: [`Self::script_runs`].
+    pub async fn cancel_script_run(&self, run_id: u64) -> Option<()> {
+        tracing::debug!(run_id, "Cancelling script run");
+
+        self.script_runs
+            .write()
+            .await
+            .remove(&run_id)
+            .map(AppScriptRunHandle::cancelled)
+            .map(|_| ())
+    }
+
+    /// Returns the id and the port group, ordered by name.
+    pub async fn get_port_groups(&self) -> Result<Vec<(i32, AppPortGroup)>, AppPortGroupError> {
+        Ok(self.db.get_port_groups().await?)
+    }
+
+    /// Returns the id of the created port group.
+    pub async fn create_port_group(
+        &self,
+        port_group: AppPortGroup,
+    ) -> Result<i32, AppPortGroupError> {
+        tracing::debug!(?port_group, "Creating port group");
+
+        Ok(self.db.insert_port_group_returning_id(port_group).await?)
+    }
+
+    pub async fn update_port_group(
+        &self,
+        id: i32,
+        port_group: AppPortGroup,
+    ) -> Result<(), AppPortGroupError> {
+        tracing::debug!(id, ?port_group, "Updating port group");
+
+        match self.db.update_port_group(id, port_group).await? {
+            true => Ok(()),
+            false => Err(AppPortGroupError::NotFound),
+        }
+    }
+
+    pub async fn delete_port_group(&self, id: i32) -> Result<(), AppPortGroupError> {
+        tracing::debug!(id, "Deleting port group");
+
+        match self.db.delete_port_group(id).await? {
+            true => Ok(()),
+            false => Err(AppPortGroupError::NotFound),
+        }
+    }
+
+    async fn port_group(&self, id: i32) -> Result<AppPortGroup, AppPortGroupOperationError> {
+        self.db
+            .get_port_group(id)
+            .await?
+            .ok_or(AppPortGroupOperationError::NotFound)
+    }
+
+    /// Sends the `bytes` to every serial port of the group with the given `id`.
+    ///
+    /// The outgoing line ending of each serial port is appended.
+    pub async fn send_to_port_group(
+        &self,
+        id: i32,
+        bytes: Bytes,
+    ) -> Result<AppPortGroupOutcome<()>, AppPortGroupOperationError> {
+        let port_group = self.port_group(id).await?;
+
+        tracing::debug!(?port_group, "Sending to port group");
+
+        let packet = CoreOutgoingPacket {
+            bytes,
+            packet_origin: CorePacketOrigin::Group(CoreGroupPacketOrigin {
+                name: port_group.name,
+            }),
+            ack: None,
+        };
+
+        let mut outcome = AppPortGroupOutcome::default();
+
+        for name in port_group.ports {
+            // Cheap clone
+            match self
+                .serial_state
+                .send_to_open_serial_port(&name, packet.clone())
+                .await
+            {
+                Some(Ok(())) => outcome.succeeded.push((name, ())),
+                Some(Err(err)) => outcome.failed.push((name, err.to_string())),
+                None => outcome.failed.push((name, String::from("Port not open"))),
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Opens every serial port of the group with the given `id` with the same `options`.
+    ///
+    /// Each opened serial port comes with the channel returned by [`Self::open_serial_port`].
+    pub async fn open_port_group(
+        &self,
+        id: i32,
+        options: AppOpenSerialPortOptions,
+    ) -> Result<
+        AppPortGroupOutcome<MPSCUnboundedReceiver<Result<CorePacket, AppPacketError>>>,
+        AppPortGroupOperationError,
+    > {
+        let port_group = self.port_group(id).await?;
+
+        tracing::debug!(?port_group, ?options, "Opening port group");
+
+        let mut outcome = AppPortGroupOutcome::default();
+
+        for name in port_group.ports {
+            match self.open_serial_port(&name, options.clone()).await {
+                Ok(rx) => outcome.succeeded.push((name, rx)),
+                Err(err) => outcome.failed.push((name, err.to_string())),
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    pub async fn close_port_group(
+        &self,
+        id: i32,
+    ) -> Result<AppPortGroupOutcome<()>, AppPortGroupOperationError> {
+        let port_group = self.port_group(id).await?;
+
+        tracing::debug!(?port_group, "Closing port group");
+
+        let mut outcome = AppPortGroupOutcome::default();
+
+        for name in port_group.ports {
+            match self
+                .serial_state
+                .remove_and_cancel_open_serial_port(&name)
+                .await
+            {
+                Some(_) => outcome.succeeded.push((name, ())),
+                None => outcome.failed.push((name, String::from("Port not open"))),
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    pub async fn toggle_port_group_read_state(
+        &self,
+        id: i32,
+    ) -> Result<AppPortGroupOutcome<()>, AppPortGroupOperationError> {
+        let port_group = self.port_group(id).await?;
+
+        tracing::debug!(?port_group, "Toggling read state of port group");
+
+        let mut outcome = AppPortGroupOutcome::default();
+
+        for name in port_group.ports {
+            match self.serial_state.toggle_read_state(&name).await {
+                Some(()) => outcome.succeeded.push((name, ())),
+                None => outcome.failed.push((name, String::from("Port not open"))),
+            }
+        }
+
+        Ok(outcome)
+    }
+
+    /// Registers the stored auto-responders with the serial state.
+    ///
+    /// Invalid auto-responders are skipped.
+    pub async fn load_auto_responders(&self) -> Result<(), AppAutoResponderError> {
+        for (id, auto_responder) in self.db.get_auto_responders().await? {
+            match auto_responder.to_core() {
+                Ok(core_auto_responder) => {
+                    self.serial_state
+                        .set_auto_responder(id, core_auto_responder)
+                        .await;
+                }
+                Err(err) => {
+                    tracing::warn!(id, name=%auto_responder.name, %err, "Skipping invalid auto-responder");
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the id and the auto-responder, ordered by name.
+    pub async fn get_auto_responders(
+        &self,
+    ) -> Result<Vec<(i32, AppAutoResponder)>, AppAutoResponderError> {
+        Ok(self.db.get_auto_responders().await?)
+    }
+
+    /// Returns the id of the created auto-responder.
+    pub async fn create_auto_responder(
+        &self,
+        auto_responder: AppAutoResponder,
+    ) -> Result<i32, AppAutoResponderError> {
+        tracing::debug!(?auto_responder, "Creating auto-responder");
+
+        let core_auto_responder = auto_responder.to_core()?;
+
+        let id = self
+            .db
+            .insert_auto_responder_returning_id(auto_responder)
+            .await?;
+
+        self.serial_state
+            .set_auto_responder(id, core_auto_responder)
+            .await;
+
+        Ok(id)
+    }
+
+    pub async fn update_auto_responder(
+        &self,
+        id: i32,
+        auto_responder: AppAutoResponder,
+    ) -> Result<(), AppAutoResponderError> {
+        tracing::debug!(id, ?auto_responder, "Updating auto-responder");
+
+        let core_auto_responder = auto_responder.to_core()?;
+
+        if !self.db.update_auto_responder(id, auto_responder).await? {
+            return Err(AppAutoResponderError::NotFound);
+        }
+
+        self.serial_state
+            .set_auto_responder(id, core_auto_responder)
+            .await;
+
+        Ok(())
+    }
+
+    /// Takes effect immediately for open serial ports.
+    pub async fn set_auto_responder_enabled(
+        &self,
+        id: i32,
+        enabled: bool,
+    ) -> Result<(), AppAutoResponderError> {
+        tracing::debug!(id, enabled, "Setting auto-responder enabled");
+
+        if !self.db.update_auto_responder_enabled(id, enabled).await? {
+            return Err(AppAutoResponderError::NotFound);
+        }
+
+        // Invalid auto-responders are not registered.
+        let _ = self
+            .serial_state
+            .set_auto_responder_enabled(id, enabled)
+            .await;
+
+        Ok(())
+    }
+
+    pub async fn delete_auto_responder(&self, id: i32) -> Result<(), AppAutoResponderError> {
+        tracing::debug!(id, "Deleting auto-responder");
+
+        if !self.db.delete_auto_responder(id).await? {
+            return Err(AppAutoResponderError::NotFound);
+        }
+
+        self.serial_state.remove_auto_responder(id).await;
+
+        Ok(())
+    }
+
+    /// Registers the stored subscriptions with the serial state.
+    ///
+    /// Subscriptions with an invalid filter or a filter without the lines mode are skipped.
+    #[cfg(feature = "subscriptions")]
+    pub async fn load_subscriptions(&self) -> Result<(), AppSubscriptionError> {
+        for subscription in self.db.get_subscriptions().await? {
+            if !subscription.is_filter_applicable() {
+                tracing::warn!(from=%subscription.from, to=%subscription.to, "Skipping subscription with a filter in raw mode");
+
+                continue;
+            }
+
+            let filter = match subscription
+                .filter
+                .as_ref()
+                .map(AppSubscriptionFilter::to_core)
+                .transpose()
+            {
+                Ok(filter) => filter,
+                Err(err) => {
+                    tracing::warn!(from=%subscription.from, to=%subscription.to, %err, "Skipping subscription with invalid filter");
+
+                    continue;
+                }
+            };
+
+            // Stored subscriptions were accepted when they were created.
+            if let Err(err) = self.subscribe_core(&subscription, filter, true).await {
+                tracing::warn!(from=%subscription.from, to=%subscription.to, %err, "Skipping subscription");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Returns the stored subscription of `to` to `from`.
+    #[cfg(feature = "subscriptions")]
+    pub async fn get_subscription(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<Option<AppSubscription>, AppSubscriptionError> {
+        Ok(self.db.get_subscription(from, to).await?)
+    }
+
+    /// Subscribes and stores the subscription, so it is restored by [`AppState::load_subscriptions`].
+    ///
+    /// See [`StateInner::subscribe`](crate::app::serial_state::StateInner::subscribe) for `allow_cycle`.
+    /// Fails if `to` is subscribed to `from` as a direction of a bridge, or if a filter is set without the lines mode.
+    /// See [`AppSubscription::is_filter_applicable`].
+    ///
+    /// The previous subscription is restored if it can not be stored.
+    #[cfg(feature = "subscriptions")]
+    pub async fn subscribe(
+        &self,
+        subscription: AppSubscription,
+        allow_cycle: bool,
+    ) -> Result<(), AppSubscriptionError> {
+        tracing::debug!(?subscription, allow_cycle, "Subscribing");
+
+        let from = subscription.from.clone();
+        let to = subscription.to.clone();
+
+        if !subscription.is_filter_applicable() {
+            return Err(AppSubscriptionError::FilterWithoutLines);
+        }
+
+        let filter = subscription
+            .filter
+            .as_ref()
+            .map(AppSubscriptionFilter::to_core)
+            .transpose()?;
+
+        let previous = self.db.get_subscription(&from, &to).await?;
+
+        self.subscribe_core(&subscription, filter, allow_cycle)
+            .await
+            .map_err(|err| match err {
+                CoreSubscribeError::Bridge => AppSubscriptionError::Bridge,
+                err => err.into(),
+            })?;
+
+        if let Err(err) = self.db.upsert_subscription(subscription).await {
+            self.restore_subscription(&from, &to, previous).await;
+
+            return Err(err.into());
+        }
+
+        Ok(())
+    }
+
+    /// Replaces the subscription of `to` to `from` in the serial state with the `stored` one, or removes it if none is stored.
+    #[cfg(feature = "subscriptions")]
+    async fn restore_subscription(&self, from: &str, to: &str, stored: Option<AppSubscription>) {
+        tracing::debug!(%from, %to, ?stored, "Restoring subscription");
+
+        let Some(stored) = stored else {
+            self.serial_state.unsubscribe(from, to).await;
+
+            return;
+        };
+
+        let filter = match stored
+            .filter
+            .as_ref()
+            .map(AppSubscriptionFilter::to_core)
+            .transpose()
+        {
+            Ok(filter) => filter,
+            Err(err) => {
+                // Was skipped by `load_subscriptions`.
+                tracing::warn!(%from, %to, %err, "Removing subscription with invalid filter");
+
+                self.serial_state.unsubscribe(from, to).await;
+
+                return;
+            }
+        };
+
+        if let Err(err) = self.subscribe_core(&stored, filter, true).await {
+            tracing::warn!(%from, %to, %err, "Failed to restore subscription");
+        }
+    }
+
+    /// Subscribes in the serial state only.
+    #[cfg(feature = "subscriptions")]
+    async fn subscribe_core(
+        &self,
+        subscription: &AppSubscription,
+        filter: Option<CoreSubscriptionFilter>,
+        allow_cycle: bool,
+    ) -> Result<(), CoreSubscribeError> {
+        let options = CoreSubscriptionOptions {
+            filter,
+            mode: subscription.mode.clone(),
+            rate_limit: subscription.rate_limit.into(),
+            echo_guard: subscription.echo_guard,
+        };
+
+        self.serial_state
+            .subscribe(&subscription.from, &subscription.to, options, allow_cycle)
+            .await
+    }
+
+    /// Stores the new filter first, then applies it.
+    ///
+    /// Fails if a filter is set without the lines mode. See [`AppSubscription::is_filter_applicable`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_filter(
+        &self,
+        from: &str,
+        to: &str,
+        filter: Option<AppSubscriptionFilter>,
+    ) -> Result<(), AppSubscriptionError> {
+        tracing::debug!(%from, %to, ?filter, "Setting subscription filter");
+
+        let core_filter = filter
+            .as_ref()
+            .map(AppSubscriptionFilter::to_core)
+            .transpose()?;
+
+        let mut subscription = self.editable_subscription(from, to).await?;
+        subscription.filter = filter;
+
+        if !subscription.is_filter_applicable() {
+            return Err(AppSubscriptionError::FilterWithoutLines);
+        }
+
+        self.db.upsert_subscription(subscription).await?;
+
+        self.serial_state
+            .set_subscription_filter(from, to, core_filter)
+            .await
+            .ok_or(AppSubscriptionError::NotFound)
+    }
+
+    /// Stores the new mode first, then applies it.
+    ///
+    /// Fails if the subscription has a filter and the new mode is not the lines mode. See [`AppSubscription::is_filter_applicable`].
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_mode(
+        &self,
+        from: &str,
+        to: &str,
+        mode: CoreSubscriptionMode,
+    ) -> Result<(), AppSubscriptionError> {
+        tracing::debug!(%from, %to, ?mode, "Setting subscription mode");
+
+        let mut subscription = self.editable_subscription(from, to).await?;
+        subscription.mode = mode.clone();
+
+        if !subscription.is_filter_applicable() {
+            return Err(AppSubscriptionError::FilterWithoutLines);
+        }
+
+        self.db.upsert_subscription(subscription).await?;
+
+        self.serial_state
+            .set_subscription_mode(from, to, mode)
+            .await
+            .ok_or(AppSubscriptionError::NotFound)
+    }
+
+    /// Stores the new rate limit first, then applies it.
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_rate_limit(
+        &self,
+        from: &str,
+        to: &str,
+        rate_limit: AppSubscriptionRateLimit,
+    ) -> Result<(), AppSubscriptionError> {
+        tracing::debug!(%from, %to, ?rate_limit, "Setting subscription rate limit");
+
+        let mut subscription = self.editable_subscription(from, to).await?;
+        subscription.rate_limit = rate_limit;
+
+        self.db.upsert_subscription(subscription).await?;
+
+        self.serial_state
+            .set_subscription_rate_limit(from, to, rate_limit.into())
+            .await
+            .ok_or(AppSubscriptionError::NotFound)
+    }
+
+    /// Stores the new echo guard first, then applies it.
+    #[cfg(feature = "subscriptions")]
+    pub async fn set_subscription_echo_guard(
+        &self,
+        from: &str,
+        to: &str,
+        echo_guard: bool,
+    ) -> Result<(), AppSubscriptionError> {
+        tracing::debug!(%from, %to, echo_guard, "Setting subscription echo guard");
+
+        let mut subscription = self.editable_subscription(from, to).await?;
+        subscription.echo_guard = echo_guard;
+
+        self.db.upsert_subscription(subscription).await?;
+
+        self.serial_state
+            .set_subscription_echo_guard(from, to, echo_guard)
+            .await
+            .ok_or(AppSubscriptionError::NotFound)
+    }
+
+    /// Returns the stored subscription of `to` to `from`, if it is active in the serial state and not a direction of a bridge.
+    #[cfg(feature = "subscriptions")]
+    async fn editable_subscription(
+        &self,
+        from: &str,
+        to: &str,
+    ) -> Result<AppSubscription, AppSubscriptionError> {
+        match self.serial_state.is_bridge(from, to).await {
+            None => return Err(AppSubscriptionError::NotFound),
+            Some(true) => return Err(AppSubscriptionError::Bridge),
+            Some(false) => {}
+        }
+
+        self.db
+            .get_subscription(from, to)
+            .await?
+            .ok_or(AppSubscriptionError::NotFound)
+    }
+
+    /// Unsubscribes and removes the stored subscription.
+    ///
+    /// Fails if `to` is subscribed to `from` as a direction of a bridge. Bridges are removed as a whole.
+    #[cfg(feature = "subscriptions")]
+    pub async fn unsubscribe(&self, from: &str, to: &str) -> Result<(), AppSubscriptionError> {
+        tracing::debug!(%from, %to, "Unsubscribing");
+
+        if self.serial_state.is_bridge(from, to).await == Some(true) {
+            return Err(AppSubscriptionError::Bridge);
+        }
+
+        self.db.delete_subscription(from, to).await?;
+
+        self.serial_state.unsubscribe(from, to).await;
+
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "subscriptions"))]
+mod tests {
+    use crate::{
+        app::{
+            database::database_impl::sqlite_database_service::SqliteDatabase,
+            serial_state::error::CoreBridgeError,
+        },
+        serial_manager::serial_manager_impl::dummy_serial_manager::DummySerialManager,
+    };
+
+    use super::*;
+
+    async fn database() -> Database {
+        let db = SqliteDatabase::new("sqlite::memory:").await.unwrap();
+        db.migrate().await.unwrap();
+
+        db.into()
+    }
+
+    fn app_state(db: Database) -> AppState {
+        AppState::new(db, DummySerialManager::new().into())
+    }
+
+    #[tokio::test]
+    async fn bridge_keeps_stored_subscription_in_sync() {
+        let db = database().await;
+        let state = app_state(db.clone());
+
+        state
+            .subscribe(
+                AppSubscription {
+                    from: String::from("A"),
+                    to: String::from("B"),
+                    filter: None,
+                    mode: CoreSubscriptionMode::Raw,
+                    rate_limit: Default::default(),
+                    echo_guard: true,
+                },
+                false,
+            )
+            .await
+            .unwrap();
+
+        assert!(matches!(
+            state.serial_state().create_bridge("B", "A", true).await,
+            Err(CoreBridgeError::Subscribed { .. })
+        ));
+        assert!(state.serial_state().remove_bridge("A", "B").await.is_none());
+
+        state
+            .set_subscription_echo_guard("A", "B", false)
+            .await
+            .unwrap();
+
+        let reloaded = app_state(db.clone());
+        reloaded.load_subscriptions().await.unwrap();
+
+        assert_eq!(
+            reloaded.serial_state().is_bridge("A", "B").await,
+            Some(false)
+        );
+        assert!(
+            !db.get_subscription("A", "B")
+                .await
+                .unwrap()
+                .unwrap()
+                .echo_guard
+        );
+
+        state.unsubscribe("A", "B").await.unwrap();
+        state
+            .serial_state()
+            .create_bridge("A", "B", true)
+            .await
+            .unwrap();
+        state.serial_state().remove_bridge("A", "B").await.unwrap();
+
+        let reloaded = app_state(db);
+        reloaded.load_subscriptions().await.unwrap();
+
+        assert_eq!(reloaded.serial_state().is_bridge("A", "B").await, None);
+        assert_eq!(reloaded.serial_state().is_bridge("B", "A").await, None);
+    }
 }
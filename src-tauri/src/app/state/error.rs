@@ -1,18 +1,45 @@
+use std::sync::Arc;
+
+#[cfg(feature = "subscriptions")]
+use crate::app::serial_state::error::CoreSubscribeError;
 use crate::app::{
     database::error::{
-        GetOrInsertSerialPortError, InsertPacketError, UpdateOrInsertOpenSerialPortOptionsError,
+        DeleteAutoResponderError, DeleteCommandMacroError, DeletePortGroupError,
+        DeleteRetentionPolicyError, DeleteScriptError, DeleteSubscriptionError,
+        GetAutoRespondersError, GetCommandMacroError, GetCommandMacrosError, GetDatabaseStatsError,
+        GetOpenSerialPortOptionsError, GetOrInsertSerialPortError, GetPacketsError,
+        GetPortGroupError, GetPortGroupsError, GetRetentionPoliciesError, GetScriptError,
+        GetScriptsError, GetSubscriptionError, GetSubscriptionsError, InsertAutoResponderError,
+        InsertCommandMacroError, InsertPacketsError, InsertPortGroupError, InsertScriptError,
+        PrunePacketsError, UpdateAutoResponderError, UpdateCommandMacroError,
+        UpdateOrInsertOpenSerialPortOptionsError, UpdatePortGroupError, UpdateScriptError,
+        UpsertRetentionPolicyError, UpsertSubscriptionError,
+    },
+    serial_state::{
+        error::{CoreManagedSerialPortsError, CoreOpenSerialPortError, CorePacketError},
+        input::CoreInputParseError,
     },
-    serial_state::error::{CoreManagedSerialPortsError, CoreOpenSerialPortError, CorePacketError},
 };
 
 #[derive(Debug, thiserror::Error)]
-pub enum AppAddPacketError {}
-
-#[derive(Debug, thiserror::Error)]
-pub enum AppAddOrUpdateOpenSerialPortOptionsError {}
+pub enum AppGetPacketsError {
+    #[error("Failed to get packets: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetPacketsError,
+    ),
+}
 
 #[derive(Debug, thiserror::Error)]
-pub enum AppGetOpenSerialPortOptionsError {}
+pub enum AppGetOpenSerialPortOptionsError {
+    #[error("Failed to get open serial port options: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetOpenSerialPortOptionsError,
+    ),
+}
 
 #[derive(Debug, thiserror::Error)]
 pub enum AppManagedSerialPortsError {
@@ -60,10 +87,290 @@ pub enum AppPacketError {
         #[from]
         CorePacketError,
     ),
-    #[error("Failed to save packet: {0}")]
+    /// Shared by the packets of a batch.
+    #[error("Failed to save packets: {0}")]
     SavePacketError(
         #[source]
         #[from]
-        InsertPacketError,
+        Arc<InsertPacketsError>,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppCommandMacroError {
+    #[error("Command macro not found")]
+    NotFound,
+    #[error("Failed to get command macros: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetCommandMacrosError,
+    ),
+    #[error("Failed to create command macro: {0}")]
+    Insert(
+        #[source]
+        #[from]
+        InsertCommandMacroError,
+    ),
+    #[error("Failed to update command macro: {0}")]
+    Update(
+        #[source]
+        #[from]
+        UpdateCommandMacroError,
+    ),
+    #[error("Failed to delete command macro: {0}")]
+    Delete(
+        #[source]
+        #[from]
+        DeleteCommandMacroError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppExecuteCommandMacroError {
+    #[error("Command macro not found")]
+    NotFound,
+    #[error("Failed to get command macro: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetCommandMacroError,
+    ),
+    #[error("Failed to parse payload: {0}")]
+    Parse(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
+    #[error("No open serial port to send the command macro to")]
+    NoTarget,
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppScriptError {
+    #[error("Script not found")]
+    NotFound,
+    #[error("Failed to get scripts: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetScriptsError,
+    ),
+    #[error("Failed to create script: {0}")]
+    Insert(
+        #[source]
+        #[from]
+        InsertScriptError,
+    ),
+    #[error("Failed to update script: {0}")]
+    Update(
+        #[source]
+        #[from]
+        UpdateScriptError,
+    ),
+    #[error("Failed to delete script: {0}")]
+    Delete(
+        #[source]
+        #[from]
+        DeleteScriptError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppRunScriptError {
+    #[error("Script not found")]
+    NotFound,
+    #[error("Failed to get script: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetScriptError,
+    ),
+    #[error("Failed to compile script: {0}")]
+    Compile(
+        #[source]
+        #[from]
+        rhai::ParseError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppPortGroupError {
+    #[error("Port group not found")]
+    NotFound,
+    #[error("Failed to get port groups: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetPortGroupsError,
+    ),
+    #[error("Failed to create port group: {0}")]
+    Insert(
+        #[source]
+        #[from]
+        InsertPortGroupError,
+    ),
+    #[error("Failed to update port group: {0}")]
+    Update(
+        #[source]
+        #[from]
+        UpdatePortGroupError,
+    ),
+    #[error("Failed to delete port group: {0}")]
+    Delete(
+        #[source]
+        #[from]
+        DeletePortGroupError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppPortGroupOperationError {
+    #[error("Port group not found")]
+    NotFound,
+    #[error("Failed to get port group: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetPortGroupError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppAutoResponderParseError {
+    #[error("Invalid pattern: {0}")]
+    Pattern(
+        #[source]
+        #[from]
+        regex::Error,
+    ),
+    #[error("Invalid response: {0}")]
+    Response(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppAutoResponderError {
+    #[error("Auto-responder not found")]
+    NotFound,
+    #[error("Invalid auto-responder: {0}")]
+    Parse(
+        #[source]
+        #[from]
+        AppAutoResponderParseError,
+    ),
+    #[error("Failed to get auto-responders: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetAutoRespondersError,
+    ),
+    #[error("Failed to create auto-responder: {0}")]
+    Insert(
+        #[source]
+        #[from]
+        InsertAutoResponderError,
+    ),
+    #[error("Failed to update auto-responder: {0}")]
+    Update(
+        #[source]
+        #[from]
+        UpdateAutoResponderError,
+    ),
+    #[error("Failed to delete auto-responder: {0}")]
+    Delete(
+        #[source]
+        #[from]
+        DeleteAutoResponderError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppSubscriptionError {
+    #[error("Subscription not found")]
+    NotFound,
+    #[error("Subscription is a direction of a bridge")]
+    Bridge,
+    #[error("A filter requires the lines mode")]
+    FilterWithoutLines,
+    #[error("Invalid filter pattern: {0}")]
+    Pattern(
+        #[source]
+        #[from]
+        regex::Error,
+    ),
+    #[cfg(feature = "subscriptions")]
+    #[error("Failed to subscribe: {0}")]
+    Subscribe(
+        #[source]
+        #[from]
+        CoreSubscribeError,
+    ),
+    #[error("Failed to get subscriptions: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetSubscriptionsError,
+    ),
+    #[error("Failed to get subscription: {0}")]
+    GetOne(
+        #[source]
+        #[from]
+        GetSubscriptionError,
+    ),
+    #[error("Failed to save subscription: {0}")]
+    Upsert(
+        #[source]
+        #[from]
+        UpsertSubscriptionError,
+    ),
+    #[error("Failed to delete subscription: {0}")]
+    Delete(
+        #[source]
+        #[from]
+        DeleteSubscriptionError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppRetentionError {
+    #[error("Retention policy not found")]
+    NotFound,
+    #[error("Failed to get retention policies: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetRetentionPoliciesError,
+    ),
+    #[error("Failed to save retention policy: {0}")]
+    Upsert(
+        #[source]
+        #[from]
+        UpsertRetentionPolicyError,
+    ),
+    #[error("Failed to delete retention policy: {0}")]
+    Delete(
+        #[source]
+        #[from]
+        DeleteRetentionPolicyError,
+    ),
+    #[error("Failed to prune packets: {0}")]
+    Prune(
+        #[source]
+        #[from]
+        PrunePacketsError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AppGetDatabaseStatsError {
+    #[error("Failed to get database stats: {0}")]
+    Get(
+        #[source]
+        #[from]
+        GetDatabaseStatsError,
     ),
 }
@@ -1,19 +1,31 @@
 use anyhow::Context;
 use command::{
+    auto_responder::{create_auto_responder_intern, delete_auto_responder_intern, get_auto_responders_intern, set_auto_responder_enabled_intern, update_auto_responder_intern},
+    bridge::{create_bridge_intern, remove_bridge_intern},
     close_serial_port::close_serial_port_intern,
+    command_macro::{create_command_macro_intern, delete_command_macro_intern, execute_command_macro_intern, get_command_macros_intern, update_command_macro_intern},
+    get_packets::get_packets_intern,
     get_serial_ports::get_serial_ports_intern,
     open_serial_port::open_serial_port_intern,
+    packet_writer::get_packet_writer_metrics_intern,
+    periodic_job::{get_periodic_jobs_intern, start_periodic_job_intern, stop_periodic_job_intern},
+    port_group::{close_port_group_intern, create_port_group_intern, delete_port_group_intern, get_port_groups_intern, open_port_group_intern, send_to_port_group_intern, toggle_port_group_read_state_intern, update_port_group_intern},
+    retention::{delete_retention_policy_intern, get_database_stats_intern, get_retention_policies_intern, prune_packets_intern, set_retention_policy_intern},
+    script::{cancel_script_run_intern, create_script_intern, delete_script_intern, get_script_runs_intern, get_scripts_intern, run_script_intern, update_script_intern},
+    send_and_expect::send_and_expect_intern,
+    send_file::{cancel_transfer_intern, send_file_to_serial_port_intern},
     send_to_all_serial_ports::send_to_all_serial_ports_intern,
     send_to_serial_port::send_to_serial_port_intern,
-    subscribe::{subscribe_intern, unsubscribe_intern},
+    subscribe::{get_subscription_metrics_intern, set_subscription_filter_intern, set_subscription_mode_intern, set_subscription_rate_limit_intern, set_subscription_echo_guard_intern, subscribe_intern, unsubscribe_intern},
     toggle_read_state::toggle_read_state_intern,
+    xmodem::{xmodem_receive_intern, xmodem_send_intern},
 };
 use futures::StreamExt;
 use error::AppError;
 use event::{emit_error::emit_error_event, emit_managed_serial_ports::emit_managed_serial_ports_event, model::error::ErrorEvent};
-use model::{managed_serial_port::ManagedSerialPort, open_options::OpenSerialPortOptions};
+use model::{auto_responder::{AutoResponder, StoredAutoResponder}, command_macro::{CommandMacro, CommandMacroExecutionTarget, CommandMacroOutcome, StoredCommandMacro}, expect::ExpectPattern, input_mode::InputMode, managed_serial_port::ManagedSerialPort, open_options::{Duration, OpenSerialPortOptions}, packet::{Packet, PacketPage, PacketQuery, PacketWriterMetrics, WriteAck}, periodic_job::{PeriodicJob, PeriodicSchedule}, port_group::{PortGroup, PortGroupOutcome, StoredPortGroup}, retention::{DatabaseStats, PruneReport, RetentionPolicy, RetentionScope}, script::{Script, ScriptRun, StoredScript}, subscription::{SubscriptionFilter, SubscriptionMetrics, SubscriptionMode, SubscriptionRateLimit}, transfer::{SendFilePacing, XmodemProtocol}};
 use state::TauriAppState as TauriAppState;
-use tauri::{AppHandle, Manager, State};
+use tauri::{AppHandle, Manager, RunEvent, State};
 
 use crate::{app::{database::database_impl::sqlite_database_service::SqliteDatabase, state::AppState}, serial_manager::serial_manager_impl::tokio_serial_manager::TokioSerialManager, watcher::{model::WatcherEventType, watcher_impl::watcher::WatcherImpl, watcher_service::WatcherService, Watcher}};
 
@@ -33,6 +45,77 @@ pub async fn get_serial_ports(
         .map_err(Into::into)
 }
 
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_packets(
+    query: Option<PacketQuery>,
+    state: State<'_, TauriAppState>,
+) -> Result<PacketPage, AppError> {
+    get_packets_intern(query.unwrap_or_default(), &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_packet_writer_metrics(
+    state: State<'_, TauriAppState>,
+) -> Result<PacketWriterMetrics, AppError> {
+    Ok(get_packet_writer_metrics_intern(&state))
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_retention_policies(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<RetentionPolicy>, AppError> {
+    get_retention_policies_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn set_retention_policy(
+    policy: RetentionPolicy,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    set_retention_policy_intern(policy, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn delete_retention_policy(
+    scope: RetentionScope,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    delete_retention_policy_intern(scope, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn prune_packets(
+    state: State<'_, TauriAppState>,
+) -> Result<PruneReport, AppError> {
+    prune_packets_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_database_stats(
+    state: State<'_, TauriAppState>,
+) -> Result<DatabaseStats, AppError> {
+    get_database_stats_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
 #[tauri::command]
 #[tracing::instrument(skip_all)]
 pub async fn open_serial_port(
@@ -62,9 +145,10 @@ pub async fn close_serial_port(
 pub async fn send_to_serial_port(
     name: String,
     value: String,
+    input_mode: Option<InputMode>,
     state: State<'_, TauriAppState>,
-) -> Result<(), AppError> {
-    send_to_serial_port_intern(name, value.into(), state.serial_state())
+) -> Result<WriteAck, AppError> {
+    send_to_serial_port_intern(name, value, input_mode.unwrap_or_default().into(), state.serial_state())
         .await
         .map_err(Into::into)
 }
@@ -73,10 +157,394 @@ pub async fn send_to_serial_port(
 #[tracing::instrument(skip_all)]
 pub async fn send_to_all_serial_ports(
     value: String,
+    input_mode: Option<InputMode>,
     state: State<'_, TauriAppState>,
 ) -> Result<(), AppError> {
-    send_to_all_serial_ports_intern(value.into(), state.serial_state()).await;
-    Ok(())
+    send_to_all_serial_ports_intern(value, input_mode.unwrap_or_default().into(), state.serial_state())
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn send_and_expect(
+    name: String,
+    value: String,
+    input_mode: Option<InputMode>,
+    pattern: ExpectPattern,
+    timeout: Duration,
+    state: State<'_, TauriAppState>,
+) -> Result<Packet, AppError> {
+    send_and_expect_intern(name, value, input_mode.unwrap_or_default().into(), pattern, timeout.into(), state.serial_state())
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn send_file_to_serial_port(
+    name: String,
+    path: String,
+    pacing: SendFilePacing,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<u64, AppError> {
+    send_file_to_serial_port_intern(name, path, pacing.into(), &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn xmodem_send(
+    name: String,
+    path: String,
+    protocol: XmodemProtocol,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<u64, AppError> {
+    xmodem_send_intern(name, path, protocol.into(), &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn xmodem_receive(
+    name: String,
+    path: String,
+    protocol: XmodemProtocol,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<u64, AppError> {
+    xmodem_receive_intern(name, path, protocol.into(), &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn cancel_transfer(
+    transfer_id: u64,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    cancel_transfer_intern(transfer_id, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn start_periodic_job(
+    name: String,
+    value: String,
+    input_mode: Option<InputMode>,
+    schedule: PeriodicSchedule,
+    state: State<'_, TauriAppState>,
+) -> Result<u64, AppError> {
+    start_periodic_job_intern(name, value, input_mode.unwrap_or_default().into(), schedule.into(), state.serial_state())
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn stop_periodic_job(
+    id: u64,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    stop_periodic_job_intern(id, state.serial_state())
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_periodic_jobs(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<PeriodicJob>, AppError> {
+    Ok(get_periodic_jobs_intern(state.serial_state()).await)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_command_macros(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<StoredCommandMacro>, AppError> {
+    get_command_macros_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn create_command_macro(
+    command_macro: CommandMacro,
+    state: State<'_, TauriAppState>,
+) -> Result<i32, AppError> {
+    create_command_macro_intern(command_macro, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn update_command_macro(
+    id: i32,
+    command_macro: CommandMacro,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    update_command_macro_intern(id, command_macro, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn delete_command_macro(
+    id: i32,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    delete_command_macro_intern(id, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn execute_command_macro(
+    id: i32,
+    target: Option<CommandMacroExecutionTarget>,
+    state: State<'_, TauriAppState>,
+) -> Result<CommandMacroOutcome, AppError> {
+    execute_command_macro_intern(id, target.unwrap_or_default(), &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_scripts(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<StoredScript>, AppError> {
+    get_scripts_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn create_script(
+    script: Script,
+    state: State<'_, TauriAppState>,
+) -> Result<i32, AppError> {
+    create_script_intern(script, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn update_script(
+    id: i32,
+    script: Script,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    update_script_intern(id, script, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn delete_script(
+    id: i32,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    delete_script_intern(id, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn run_script(
+    id: i32,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<u64, AppError> {
+    run_script_intern(id, &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn cancel_script_run(
+    run_id: u64,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    cancel_script_run_intern(run_id, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_script_runs(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ScriptRun>, AppError> {
+    Ok(get_script_runs_intern(&state).await)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_port_groups(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<StoredPortGroup>, AppError> {
+    get_port_groups_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn create_port_group(
+    port_group: PortGroup,
+    state: State<'_, TauriAppState>,
+) -> Result<i32, AppError> {
+    create_port_group_intern(port_group, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn update_port_group(
+    id: i32,
+    port_group: PortGroup,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    update_port_group_intern(id, port_group, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn delete_port_group(
+    id: i32,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    delete_port_group_intern(id, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn send_to_port_group(
+    id: i32,
+    value: String,
+    input_mode: Option<InputMode>,
+    state: State<'_, TauriAppState>,
+) -> Result<PortGroupOutcome, AppError> {
+    send_to_port_group_intern(id, value, input_mode.unwrap_or_default().into(), &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn open_port_group(
+    id: i32,
+    options: OpenSerialPortOptions,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<PortGroupOutcome, AppError> {
+    open_port_group_intern(id, options, &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn close_port_group(
+    id: i32,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<PortGroupOutcome, AppError> {
+    close_port_group_intern(id, &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn toggle_port_group_read_state(
+    id: i32,
+    app: AppHandle,
+    state: State<'_, TauriAppState>,
+) -> Result<PortGroupOutcome, AppError> {
+    toggle_port_group_read_state_intern(id, &app, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_auto_responders(
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<StoredAutoResponder>, AppError> {
+    get_auto_responders_intern(&state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn create_auto_responder(
+    auto_responder: AutoResponder,
+    state: State<'_, TauriAppState>,
+) -> Result<i32, AppError> {
+    create_auto_responder_intern(auto_responder, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn update_auto_responder(
+    id: i32,
+    auto_responder: AutoResponder,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    update_auto_responder_intern(id, auto_responder, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn set_auto_responder_enabled(
+    id: i32,
+    enabled: bool,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    set_auto_responder_enabled_intern(id, enabled, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn delete_auto_responder(
+    id: i32,
+    state: State<'_, TauriAppState>,
+) -> Result<(), AppError> {
+    delete_auto_responder_intern(id, &state)
+        .await
+        .map_err(Into::into)
 }
 
 #[tauri::command]
@@ -84,9 +552,77 @@ pub async fn send_to_all_serial_ports(
 pub async fn subscribe(
     from: &str,
     to: &str,
+    filter: Option<SubscriptionFilter>,
+    mode: Option<SubscriptionMode>,
+    rate_limit: Option<SubscriptionRateLimit>,
+    allow_cycle: Option<bool>,
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ManagedSerialPort>, AppError> {
+    subscribe_intern(from, to, filter, mode.unwrap_or_default(), rate_limit.unwrap_or_default(), allow_cycle.unwrap_or_default(), &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn set_subscription_filter(
+    from: &str,
+    to: &str,
+    filter: Option<SubscriptionFilter>,
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ManagedSerialPort>, AppError> {
+    set_subscription_filter_intern(from, to, filter, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn set_subscription_mode(
+    from: &str,
+    to: &str,
+    mode: SubscriptionMode,
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ManagedSerialPort>, AppError> {
+    set_subscription_mode_intern(from, to, mode, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn set_subscription_rate_limit(
+    from: &str,
+    to: &str,
+    rate_limit: SubscriptionRateLimit,
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ManagedSerialPort>, AppError> {
+    set_subscription_rate_limit_intern(from, to, rate_limit, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn set_subscription_echo_guard(
+    from: &str,
+    to: &str,
+    echo_guard: bool,
     state: State<'_, TauriAppState>,
 ) -> Result<Vec<ManagedSerialPort>, AppError> {
-    subscribe_intern(from, to, &state)
+    set_subscription_echo_guard_intern(from, to, echo_guard, &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn get_subscription_metrics(
+    from: &str,
+    to: &str,
+    state: State<'_, TauriAppState>,
+) -> Result<SubscriptionMetrics, AppError> {
+    get_subscription_metrics_intern(from, to, &state)
         .await
         .map_err(Into::into)
 }
@@ -105,19 +641,38 @@ pub async fn unsubscribe(
 
 #[tauri::command]
 #[tracing::instrument(skip_all)]
-pub async fn toggle_read_state(
-    name: &str,
+pub async fn create_bridge(
+    a: &str,
+    b: &str,
+    echo_guard: Option<bool>,
     state: State<'_, TauriAppState>,
 ) -> Result<Vec<ManagedSerialPort>, AppError> {
-    toggle_read_state_intern(name, &state)
+    create_bridge_intern(a, b, echo_guard.unwrap_or(true), &state)
+        .await
+        .map_err(Into::into)
+}
+
+#[tauri::command]
+#[tracing::instrument(skip_all)]
+pub async fn remove_bridge(
+    a: &str,
+    b: &str,
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ManagedSerialPort>, AppError> {
+    remove_bridge_intern(a, b, &state)
         .await
         .map_err(Into::into)
 }
 
 #[tauri::command]
 #[tracing::instrument(skip_all)]
-fn do_error() -> Result<(), AppError> {
-    return Err(anyhow::anyhow!("Oops!").into());
+pub async fn toggle_read_state(
+    name: &str,
+    state: State<'_, TauriAppState>,
+) -> Result<Vec<ManagedSerialPort>, AppError> {
+    toggle_read_state_intern(name, &state)
+        .await
+        .map_err(Into::into)
 }
 
 fn create_db_blocking() -> Result<SqliteDatabase, anyhow::Error> {
@@ -160,12 +715,22 @@ pub fn run() -> anyhow::Result<()> {
     // TODO: we have to find a way to load the app, show in ui that we are still loading.
     let app_state = AppState::new(db.into(), serial_manager.into());
 
+    tauri::async_runtime::block_on(app_state.load_auto_responders()).context("Error loading auto-responders")?;
+
+    #[cfg(feature = "subscriptions")]
+    tauri::async_runtime::block_on(app_state.load_subscriptions()).context("Error loading subscriptions")?;
+
     let tauri_app_state = TauriAppState::new(app_state);
     
     let tauri_app_state_wachter = tauri_app_state.clone();
+    let tauri_app_state_retention = tauri_app_state.clone();
     tauri::Builder::default()
         .manage(tauri_app_state)
         .setup(|app| {
+                tauri::async_runtime::spawn(async move {
+                    tauri_app_state_retention.app_state().run_retention().await;
+                });
+
                 let app_handle = app.app_handle().clone();
                 tauri::async_runtime::spawn(async move {
                     let pool = tokio_util::task::LocalPoolHandle::new(1);
@@ -213,16 +778,74 @@ pub fn run() -> anyhow::Result<()> {
         })
         .invoke_handler(tauri::generate_handler![
             get_serial_ports,
+            get_packets,
+            get_packet_writer_metrics,
+            get_retention_policies,
+            set_retention_policy,
+            delete_retention_policy,
+            prune_packets,
+            get_database_stats,
             open_serial_port,
             close_serial_port,
             send_to_serial_port,
             send_to_all_serial_ports,
+            send_and_expect,
+            send_file_to_serial_port,
+            xmodem_send,
+            xmodem_receive,
+            cancel_transfer,
+            start_periodic_job,
+            stop_periodic_job,
+            get_periodic_jobs,
+            get_command_macros,
+            create_command_macro,
+            update_command_macro,
+            delete_command_macro,
+            execute_command_macro,
+            get_scripts,
+            create_script,
+            update_script,
+            delete_script,
+            run_script,
+            cancel_script_run,
+            get_script_runs,
+            get_port_groups,
+            create_port_group,
+            update_port_group,
+            delete_port_group,
+            send_to_port_group,
+            open_port_group,
+            close_port_group,
+            toggle_port_group_read_state,
+            get_auto_responders,
+            create_auto_responder,
+            update_auto_responder,
+            set_auto_responder_enabled,
+            delete_auto_responder,
             subscribe,
+            set_subscription_filter,
+            set_subscription_mode,
+            set_subscription_rate_limit,
+            set_subscription_echo_guard,
+            get_subscription_metrics,
             unsubscribe,
+            create_bridge,
+            remove_bridge,
             toggle_read_state,
             do_error
         ])
-        .run(tauri::generate_context!())
-        .context("Error while running tauri application")
+        .build(tauri::generate_context!())
+        .context("Error while building tauri application")?
+        .run(|app_handle, event| {
+            if let RunEvent::Exit = event {
+                tracing::debug!("Saving remaining packets before exiting");
+
+                let state = app_handle.state::<TauriAppState>();
+
+                tauri::async_runtime::block_on(state.app_state().flush_packets());
+            }
+        });
+
+    Ok(())
 }
 
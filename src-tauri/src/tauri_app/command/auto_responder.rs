@@ -0,0 +1,79 @@
+use crate::{
+    app::state::error::AppAutoResponderError,
+    tauri_app::{
+        model::auto_responder::{AutoResponder, StoredAutoResponder},
+        state::TauriAppState,
+    },
+};
+
+pub async fn get_auto_responders_intern(
+    state: &TauriAppState,
+) -> Result<Vec<StoredAutoResponder>, AutoResponderError> {
+    let auto_responders = state.app_state().get_auto_responders().await?;
+
+    Ok(auto_responders
+        .into_iter()
+        .map(|(id, auto_responder)| StoredAutoResponder {
+            id,
+            auto_responder: auto_responder.into(),
+        })
+        .collect())
+}
+
+/// Returns the id of the created auto-responder.
+pub async fn create_auto_responder_intern(
+    auto_responder: AutoResponder,
+    state: &TauriAppState,
+) -> Result<i32, AutoResponderError> {
+    tracing::info!(name=%auto_responder.name, port=%auto_responder.port, "Creating auto-responder");
+
+    Ok(state
+        .app_state()
+        .create_auto_responder(auto_responder.into())
+        .await?)
+}
+
+pub async fn update_auto_responder_intern(
+    id: i32,
+    auto_responder: AutoResponder,
+    state: &TauriAppState,
+) -> Result<(), AutoResponderError> {
+    tracing::info!(id, name=%auto_responder.name, "Updating auto-responder");
+
+    Ok(state
+        .app_state()
+        .update_auto_responder(id, auto_responder.into())
+        .await?)
+}
+
+pub async fn set_auto_responder_enabled_intern(
+    id: i32,
+    enabled: bool,
+    state: &TauriAppState,
+) -> Result<(), AutoResponderError> {
+    tracing::info!(id, enabled, "Setting auto-responder enabled");
+
+    Ok(state
+        .app_state()
+        .set_auto_responder_enabled(id, enabled)
+        .await?)
+}
+
+pub async fn delete_auto_responder_intern(
+    id: i32,
+    state: &TauriAppState,
+) -> Result<(), AutoResponderError> {
+    tracing::info!(id, "Deleting auto-responder");
+
+    Ok(state.app_state().delete_auto_responder(id).await?)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum AutoResponderError {
+    #[error("Failed to manage auto-responder: {0}")]
+    AppAutoResponderError(
+        #[source]
+        #[from]
+        AppAutoResponderError,
+    ),
+}
@@ -0,0 +1,82 @@
+#[cfg(feature = "subscriptions")]
+use crate::app::serial_state::error::CoreBridgeError;
+use crate::{
+    app::state::error::AppManagedSerialPortsError,
+    tauri_app::{model::managed_serial_port::ManagedSerialPort, state::TauriAppState},
+};
+
+/// Bridges are not stored and must be created again after a restart.
+///
+/// Fails if the serial ports are subscribed to each other. The subscriptions must be removed first.
+pub async fn create_bridge_intern(
+    a: &str,
+    b: &str,
+    _echo_guard: bool,
+    _state: &TauriAppState,
+) -> Result<Vec<ManagedSerialPort>, BridgeError> {
+    tracing::info!(%a, %b, echo_guard=%_echo_guard, "Creating bridge");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        _state
+            .serial_state()
+            .create_bridge(a, b, _echo_guard)
+            .await?;
+
+        let managed_serial_ports = _state.get_managed_serial_ports().await?;
+
+        Ok(managed_serial_ports)
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(BridgeError::Disabled)
+}
+
+pub async fn remove_bridge_intern(
+    a: &str,
+    b: &str,
+    _state: &TauriAppState,
+) -> Result<Vec<ManagedSerialPort>, BridgeError> {
+    tracing::info!(%a, %b, "Removing bridge");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        _state
+            .serial_state()
+            .remove_bridge(a, b)
+            .await
+            .ok_or(BridgeError::NotFound)?;
+
+        let managed_serial_ports = _state.get_managed_serial_ports().await?;
+
+        Ok(managed_serial_ports)
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(BridgeError::Disabled)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum BridgeError {
+    #[cfg(not(feature = "subscriptions"))]
+    #[error(
+        "Subscriptions feature is not enabled, turn on the `subscriptions` feature to enable it"
+    )]
+    Disabled,
+    #[cfg(feature = "subscriptions")]
+    #[error("Bridge not found")]
+    NotFound,
+    #[cfg(feature = "subscriptions")]
+    #[error("Failed to create bridge: {0}")]
+    Bridge(
+        #[source]
+        #[from]
+        CoreBridgeError,
+    ),
+    #[error("Failed to get managed ports: {0}")]
+    ManagedSerialPortsError(
+        #[source]
+        #[from]
+        AppManagedSerialPortsError,
+    ),
+}
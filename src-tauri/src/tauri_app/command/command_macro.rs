@@ -0,0 +1,94 @@
+use crate::{
+    app::state::error::{AppCommandMacroError, AppExecuteCommandMacroError},
+    tauri_app::{
+        model::command_macro::{
+            CommandMacro, CommandMacroExecutionTarget, CommandMacroOutcome, StoredCommandMacro,
+        },
+        state::TauriAppState,
+    },
+};
+
+pub async fn get_command_macros_intern(
+    state: &TauriAppState,
+) -> Result<Vec<StoredCommandMacro>, CommandMacroError> {
+    let command_macros = state.app_state().get_command_macros().await?;
+
+    Ok(command_macros
+        .into_iter()
+        .map(|(id, command_macro)| StoredCommandMacro {
+            id,
+            command_macro: command_macro.into(),
+        })
+        .collect())
+}
+
+/// Returns the id of the created macro.
+pub async fn create_command_macro_intern(
+    command_macro: CommandMacro,
+    state: &TauriAppState,
+) -> Result<i32, CommandMacroError> {
+    tracing::info!(name=%command_macro.name, "Creating command macro");
+
+    Ok(state
+        .app_state()
+        .create_command_macro(command_macro.into())
+        .await?)
+}
+
+pub async fn update_command_macro_intern(
+    id: i32,
+    command_macro: CommandMacro,
+    state: &TauriAppState,
+) -> Result<(), CommandMacroError> {
+    tracing::info!(id, name=%command_macro.name, "Updating command macro");
+
+    Ok(state
+        .app_state()
+        .update_command_macro(id, command_macro.into())
+        .await?)
+}
+
+pub async fn delete_command_macro_intern(
+    id: i32,
+    state: &TauriAppState,
+) -> Result<(), CommandMacroError> {
+    tracing::info!(id, "Deleting command macro");
+
+    Ok(state.app_state().delete_command_macro(id).await?)
+}
+
+/// Returns the serial ports the macro was sent to and the ones it failed for.
+pub async fn execute_command_macro_intern(
+    id: i32,
+    target: CommandMacroExecutionTarget,
+    state: &TauriAppState,
+) -> Result<CommandMacroOutcome, ExecuteCommandMacroError> {
+    tracing::info!(id, ?target, "Executing command macro");
+
+    let outcome = state
+        .app_state()
+        .execute_command_macro(id, target.into())
+        .await?;
+
+    Ok(outcome.into())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CommandMacroError {
+    #[error("Failed to manage command macro: {0}")]
+    AppCommandMacroError(
+        #[source]
+        #[from]
+        AppCommandMacroError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ExecuteCommandMacroError {
+    #[error("Failed to execute command macro: {0}")]
+    AppExecuteCommandMacroError(
+        #[source]
+        #[from]
+        AppExecuteCommandMacroError,
+    ),
+}
@@ -0,0 +1,28 @@
+use crate::{
+    app::state::error::AppGetPacketsError,
+    tauri_app::{
+        model::packet::{PacketPage, PacketQuery},
+        state::TauriAppState,
+    },
+};
+
+pub async fn get_packets_intern(
+    query: PacketQuery,
+    state: &TauriAppState,
+) -> Result<PacketPage, GetPacketsError> {
+    tracing::info!(?query, "Getting packets");
+
+    let page = state.app_state().get_packets(query.into()).await?;
+
+    Ok(page.into())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetPacketsError {
+    #[error("Failed to get packets: {0}")]
+    AppGetPacketsError(
+        #[source]
+        #[from]
+        AppGetPacketsError,
+    ),
+}
@@ -1,7 +1,19 @@
+pub mod auto_responder;
+pub mod bridge;
 pub mod close_serial_port;
+pub mod command_macro;
+pub mod get_packets;
 pub mod get_serial_ports;
 pub mod open_serial_port;
+pub mod packet_writer;
+pub mod periodic_job;
+pub mod port_group;
+pub mod retention;
+pub mod script;
+pub mod send_and_expect;
+pub mod send_file;
 pub mod send_to_all_serial_ports;
 pub mod send_to_serial_port;
 pub mod subscribe;
 pub mod toggle_read_state;
+pub mod xmodem;
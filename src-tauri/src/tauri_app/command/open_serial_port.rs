@@ -1,9 +1,13 @@
 use tauri::AppHandle;
+use tokio::sync::mpsc::UnboundedReceiver as MPSCUnboundedReceiver;
 
 use crate::{
     app::{
         model::managed_serial_port::AppOpenSerialPortOptions,
-        serial_state::error::{CoreIncomingPacketError, CorePacketError},
+        serial_state::{
+            error::{CoreIncomingPacketError, CorePacketError},
+            model::CorePacket,
+        },
         state::error::{AppManagedSerialPortsError, AppOpenSerialPortError, AppPacketError},
     },
     tauri_app::{
@@ -28,11 +32,25 @@ pub async fn open_serial_port_intern(
 
     let app_options: AppOpenSerialPortOptions = options.into();
 
-    let mut rx = state
+    let rx = state
         .app_state()
         .open_serial_port(&name, app_options)
         .await?;
 
+    spawn_emit_packet_events(name, rx, app, state);
+
+    let managed_serial_ports = state.get_managed_serial_ports().await?;
+
+    Ok(managed_serial_ports)
+}
+
+/// Emits the packets and errors of the open serial port until its channel is closed.
+pub(super) fn spawn_emit_packet_events(
+    name: String,
+    mut rx: MPSCUnboundedReceiver<Result<CorePacket, AppPacketError>>,
+    app: &AppHandle,
+    state: &TauriAppState,
+) {
     let app = app.clone();
 
     let tauri_app_state = state.clone();
@@ -76,10 +94,6 @@ pub async fn open_serial_port_intern(
 
         tracing::debug!(name=%name, "Read events task terminated");
     });
-
-    let managed_serial_ports = state.get_managed_serial_ports().await?;
-
-    Ok(managed_serial_ports)
 }
 
 #[derive(Debug, thiserror::Error)]
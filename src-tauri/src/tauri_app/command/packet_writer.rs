@@ -0,0 +1,5 @@
+use crate::tauri_app::{model::packet::PacketWriterMetrics, state::TauriAppState};
+
+pub fn get_packet_writer_metrics_intern(state: &TauriAppState) -> PacketWriterMetrics {
+    state.app_state().packet_writer_metrics().into()
+}
@@ -0,0 +1,68 @@
+use crate::{
+    app::serial_state::{
+        error::CoreStartPeriodicJobError,
+        input::{CoreInputMode, CoreInputParseError},
+        model::CorePeriodicSchedule,
+        CoreSerialState,
+    },
+    tauri_app::model::periodic_job::PeriodicJob,
+};
+
+/// Returns the id of the periodic job.
+pub async fn start_periodic_job_intern(
+    name: String,
+    value: String,
+    input_mode: CoreInputMode,
+    schedule: CorePeriodicSchedule,
+    state: &CoreSerialState,
+) -> Result<u64, StartPeriodicJobError> {
+    tracing::info!(name=%name, ?input_mode, ?schedule, "Starting periodic job");
+
+    let bytes = input_mode.parse(&value)?;
+
+    Ok(state.start_periodic_job(&name, bytes, schedule).await?)
+}
+
+pub async fn stop_periodic_job_intern(
+    id: u64,
+    state: &CoreSerialState,
+) -> Result<(), StopPeriodicJobError> {
+    tracing::info!(id, "Stopping periodic job");
+
+    state
+        .stop_periodic_job(id)
+        .await
+        .map(|_| ())
+        .ok_or(StopPeriodicJobError::NotFound)
+}
+
+pub async fn get_periodic_jobs_intern(state: &CoreSerialState) -> Vec<PeriodicJob> {
+    state
+        .periodic_jobs()
+        .await
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StartPeriodicJobError {
+    #[error("Failed to parse value: {0}")]
+    ParseError(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
+    #[error("Failed to start periodic job: {0}")]
+    StartError(
+        #[source]
+        #[from]
+        CoreStartPeriodicJobError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum StopPeriodicJobError {
+    #[error("Periodic job not found")]
+    NotFound,
+}
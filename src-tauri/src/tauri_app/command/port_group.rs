@@ -0,0 +1,182 @@
+use tauri::AppHandle;
+
+use crate::{
+    app::{
+        model::port_group::AppPortGroupOutcome,
+        serial_state::input::{CoreInputMode, CoreInputParseError},
+        state::error::{AppPortGroupError, AppPortGroupOperationError},
+    },
+    tauri_app::{
+        event::emit_managed_serial_ports::emit_managed_serial_ports_event,
+        model::{
+            open_options::OpenSerialPortOptions,
+            port_group::{PortGroup, PortGroupOutcome, StoredPortGroup},
+        },
+        state::TauriAppState,
+    },
+};
+
+use super::open_serial_port::spawn_emit_packet_events;
+
+pub async fn get_port_groups_intern(
+    state: &TauriAppState,
+) -> Result<Vec<StoredPortGroup>, PortGroupError> {
+    let port_groups = state.app_state().get_port_groups().await?;
+
+    Ok(port_groups
+        .into_iter()
+        .map(|(id, port_group)| StoredPortGroup {
+            id,
+            port_group: port_group.into(),
+        })
+        .collect())
+}
+
+/// Returns the id of the created port group.
+pub async fn create_port_group_intern(
+    port_group: PortGroup,
+    state: &TauriAppState,
+) -> Result<i32, PortGroupError> {
+    tracing::info!(name=%port_group.name, "Creating port group");
+
+    Ok(state
+        .app_state()
+        .create_port_group(port_group.into())
+        .await?)
+}
+
+pub async fn update_port_group_intern(
+    id: i32,
+    port_group: PortGroup,
+    state: &TauriAppState,
+) -> Result<(), PortGroupError> {
+    tracing::info!(id, name=%port_group.name, "Updating port group");
+
+    Ok(state
+        .app_state()
+        .update_port_group(id, port_group.into())
+        .await?)
+}
+
+pub async fn delete_port_group_intern(
+    id: i32,
+    state: &TauriAppState,
+) -> Result<(), PortGroupError> {
+    tracing::info!(id, "Deleting port group");
+
+    Ok(state.app_state().delete_port_group(id).await?)
+}
+
+pub async fn send_to_port_group_intern(
+    id: i32,
+    value: String,
+    input_mode: CoreInputMode,
+    state: &TauriAppState,
+) -> Result<PortGroupOutcome, SendToPortGroupError> {
+    tracing::info!(id, ?input_mode, "Sending to port group");
+
+    let bytes = input_mode.parse(&value)?;
+
+    Ok(state
+        .app_state()
+        .send_to_port_group(id, bytes)
+        .await?
+        .into())
+}
+
+/// Emits the packets of every opened serial port and the managed serial ports.
+pub async fn open_port_group_intern(
+    id: i32,
+    options: OpenSerialPortOptions,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<PortGroupOutcome, PortGroupOperationError> {
+    tracing::info!(id, ?options, "Opening port group");
+
+    let AppPortGroupOutcome { succeeded, failed } = state
+        .app_state()
+        .open_port_group(id, options.into())
+        .await?;
+
+    let mut opened = Vec::with_capacity(succeeded.len());
+
+    for (name, rx) in succeeded {
+        spawn_emit_packet_events(name.clone(), rx, app, state);
+
+        opened.push((name, ()));
+    }
+
+    let _ = emit_managed_serial_ports_event(app, state).await;
+
+    Ok(AppPortGroupOutcome {
+        succeeded: opened,
+        failed,
+    }
+    .into())
+}
+
+/// Emits the managed serial ports.
+pub async fn close_port_group_intern(
+    id: i32,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<PortGroupOutcome, PortGroupOperationError> {
+    tracing::info!(id, "Closing port group");
+
+    let outcome = state.app_state().close_port_group(id).await?;
+
+    let _ = emit_managed_serial_ports_event(app, state).await;
+
+    Ok(outcome.into())
+}
+
+/// Emits the managed serial ports.
+pub async fn toggle_port_group_read_state_intern(
+    id: i32,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<PortGroupOutcome, PortGroupOperationError> {
+    tracing::info!(id, "Toggling read state of port group");
+
+    let outcome = state.app_state().toggle_port_group_read_state(id).await?;
+
+    let _ = emit_managed_serial_ports_event(app, state).await;
+
+    Ok(outcome.into())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PortGroupError {
+    #[error("Failed to manage port group: {0}")]
+    AppPortGroupError(
+        #[source]
+        #[from]
+        AppPortGroupError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum PortGroupOperationError {
+    #[error("Failed to operate on port group: {0}")]
+    AppPortGroupOperationError(
+        #[source]
+        #[from]
+        AppPortGroupOperationError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendToPortGroupError {
+    #[error("Failed to parse value: {0}")]
+    ParseError(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
+    #[error("Failed to send to port group: {0}")]
+    AppPortGroupOperationError(
+        #[source]
+        #[from]
+        AppPortGroupOperationError,
+    ),
+}
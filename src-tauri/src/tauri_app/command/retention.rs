@@ -0,0 +1,76 @@
+use crate::{
+    app::state::error::{AppGetDatabaseStatsError, AppRetentionError},
+    tauri_app::{
+        model::retention::{DatabaseStats, PruneReport, RetentionPolicy, RetentionScope},
+        state::TauriAppState,
+    },
+};
+
+pub async fn get_retention_policies_intern(
+    state: &TauriAppState,
+) -> Result<Vec<RetentionPolicy>, RetentionError> {
+    let policies = state.app_state().get_retention_policies().await?;
+
+    Ok(policies.into_iter().map(Into::into).collect())
+}
+
+pub async fn set_retention_policy_intern(
+    policy: RetentionPolicy,
+    state: &TauriAppState,
+) -> Result<(), RetentionError> {
+    tracing::info!(?policy, "Setting retention policy");
+
+    Ok(state
+        .app_state()
+        .set_retention_policy(policy.into())
+        .await?)
+}
+
+pub async fn delete_retention_policy_intern(
+    scope: RetentionScope,
+    state: &TauriAppState,
+) -> Result<(), RetentionError> {
+    tracing::info!(?scope, "Deleting retention policy");
+
+    Ok(state
+        .app_state()
+        .delete_retention_policy(&scope.into())
+        .await?)
+}
+
+/// Vacuums the database if packets were deleted.
+pub async fn prune_packets_intern(state: &TauriAppState) -> Result<PruneReport, RetentionError> {
+    tracing::info!("Pruning packets");
+
+    let report = state.app_state().prune_packets(true).await?;
+
+    Ok(report.into())
+}
+
+pub async fn get_database_stats_intern(
+    state: &TauriAppState,
+) -> Result<DatabaseStats, GetDatabaseStatsError> {
+    let stats = state.app_state().get_database_stats().await?;
+
+    Ok(stats.into())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RetentionError {
+    #[error("Failed to manage retention: {0}")]
+    AppRetentionError(
+        #[source]
+        #[from]
+        AppRetentionError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum GetDatabaseStatsError {
+    #[error("Failed to get database stats: {0}")]
+    AppGetDatabaseStatsError(
+        #[source]
+        #[from]
+        AppGetDatabaseStatsError,
+    ),
+}
@@ -0,0 +1,172 @@
+use tauri::AppHandle;
+use tokio::sync::mpsc::UnboundedReceiver as MPSCUnboundedReceiver;
+
+use crate::{
+    app::{
+        script::AppScriptHooks,
+        serial_state::model::CorePacket,
+        state::error::{AppPacketError, AppRunScriptError, AppScriptError},
+    },
+    tauri_app::{
+        event::{
+            emit_managed_serial_ports::emit_managed_serial_ports_event,
+            emit_script::emit_script_event, model::script::ScriptEvent,
+        },
+        model::script::{Script, ScriptRun, StoredScript},
+        state::TauriAppState,
+    },
+};
+
+use super::open_serial_port::spawn_emit_packet_events;
+
+/// Keeps the ui up to date with the serial ports opened and closed by scripts.
+struct TauriScriptHooks {
+    app: AppHandle,
+    state: TauriAppState,
+}
+
+impl TauriScriptHooks {
+    fn spawn_emit_managed_serial_ports_event(&self) {
+        let app = self.app.clone();
+        let state = self.state.clone();
+
+        tokio::spawn(async move {
+            let _ = emit_managed_serial_ports_event(&app, &state).await;
+        });
+    }
+}
+
+impl AppScriptHooks for TauriScriptHooks {
+    fn opened(&self, name: String, rx: MPSCUnboundedReceiver<Result<CorePacket, AppPacketError>>) {
+        spawn_emit_packet_events(name, rx, &self.app, &self.state);
+
+        self.spawn_emit_managed_serial_ports_event();
+    }
+
+    fn closed(&self, _name: String) {
+        self.spawn_emit_managed_serial_ports_event();
+    }
+}
+
+pub async fn get_scripts_intern(state: &TauriAppState) -> Result<Vec<StoredScript>, ScriptError> {
+    let scripts = state.app_state().get_scripts().await?;
+
+    Ok(scripts
+        .into_iter()
+        .map(|(id, script)| StoredScript {
+            id,
+            script: script.into(),
+        })
+        .collect())
+}
+
+/// Returns the id of the created script.
+pub async fn create_script_intern(
+    script: Script,
+    state: &TauriAppState,
+) -> Result<i32, ScriptError> {
+    tracing::info!(name=%script.name, "Creating script");
+
+    Ok(state.app_state().create_script(script.into()).await?)
+}
+
+pub async fn update_script_intern(
+    id: i32,
+    script: Script,
+    state: &TauriAppState,
+) -> Result<(), ScriptError> {
+    tracing::info!(id, name=%script.name, "Updating script");
+
+    Ok(state.app_state().update_script(id, script.into()).await?)
+}
+
+pub async fn delete_script_intern(id: i32, state: &TauriAppState) -> Result<(), ScriptError> {
+    tracing::info!(id, "Deleting script");
+
+    Ok(state.app_state().delete_script(id).await?)
+}
+
+/// Returns the id of the script run.
+pub async fn run_script_intern(
+    id: i32,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<u64, RunScriptError> {
+    tracing::info!(id, "Running script");
+
+    let hooks = TauriScriptHooks {
+        app: app.clone(),
+        state: state.clone(),
+    };
+
+    let (run_id, mut rx) = state.app_state().run_script(id, hooks).await?;
+
+    let app = app.clone();
+
+    tokio::spawn(async move {
+        tracing::debug!(run_id, "Script events task started");
+
+        while let Some(event) = rx.recv().await {
+            let event = ScriptEvent {
+                run_id,
+                script_id: id,
+                status: event.into(),
+            };
+
+            let _ = emit_script_event(&app, &event);
+        }
+
+        tracing::debug!(run_id, "Script events task terminated");
+    });
+
+    Ok(run_id)
+}
+
+pub async fn cancel_script_run_intern(
+    run_id: u64,
+    state: &TauriAppState,
+) -> Result<(), CancelScriptRunError> {
+    tracing::info!(run_id, "Cancelling script run");
+
+    state
+        .app_state()
+        .cancel_script_run(run_id)
+        .await
+        .ok_or(CancelScriptRunError::NotFound)
+}
+
+pub async fn get_script_runs_intern(state: &TauriAppState) -> Vec<ScriptRun> {
+    state
+        .app_state()
+        .script_runs()
+        .await
+        .into_iter()
+        .map(Into::into)
+        .collect()
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum ScriptError {
+    #[error("Failed to manage script: {0}")]
+    AppScriptError(
+        #[source]
+        #[from]
+        AppScriptError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum RunScriptError {
+    #[error("Failed to run script: {0}")]
+    AppRunScriptError(
+        #[source]
+        #[from]
+        AppRunScriptError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CancelScriptRunError {
+    #[error("Script run not found")]
+    NotFound,
+}
@@ -0,0 +1,59 @@
+use std::time::Duration;
+
+use crate::{
+    app::serial_state::{
+        error::CoreSendAndExpectError,
+        input::{CoreInputMode, CoreInputParseError},
+        model::{CoreExpectPattern, CoreOutgoingPacket, CorePacketOrigin},
+        CoreSerialState,
+    },
+    tauri_app::model::{expect::ExpectPattern, packet::Packet},
+};
+
+/// Returns the first incoming packet matching the `pattern`.
+pub async fn send_and_expect_intern(
+    name: String,
+    value: String,
+    input_mode: CoreInputMode,
+    pattern: ExpectPattern,
+    timeout: Duration,
+    state: &CoreSerialState,
+) -> Result<Packet, SendAndExpectError> {
+    tracing::info!(name=%name, ?input_mode, ?pattern, ?timeout, "Sending and expecting");
+
+    let pattern = CoreExpectPattern::try_from(pattern)?;
+
+    let packet = CoreOutgoingPacket {
+        bytes: input_mode.parse(&value)?,
+        packet_origin: CorePacketOrigin::Direct,
+        ack: None,
+    };
+
+    let packet = state
+        .send_and_expect(&name, packet, &pattern, timeout)
+        .await?;
+
+    Ok(packet.into())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendAndExpectError {
+    #[error("Failed to parse value: {0}")]
+    Parse(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
+    #[error("Invalid pattern: {0}")]
+    Pattern(
+        #[source]
+        #[from]
+        regex::Error,
+    ),
+    #[error("Failed to send and expect: {0}")]
+    SendAndExpect(
+        #[source]
+        #[from]
+        CoreSendAndExpectError,
+    ),
+}
@@ -0,0 +1,85 @@
+use tauri::AppHandle;
+use tokio::sync::mpsc::UnboundedReceiver as MPSCUnboundedReceiver;
+
+use crate::{
+    app::serial_state::{
+        error::CoreSendFileError,
+        model::{CoreSendFilePacing, CoreTransferEvent},
+    },
+    tauri_app::{
+        event::{emit_transfer::emit_transfer_event, model::transfer::TransferEvent},
+        state::TauriAppState,
+    },
+};
+
+/// Returns the id of the transfer.
+pub async fn send_file_to_serial_port_intern(
+    name: String,
+    path: String,
+    pacing: CoreSendFilePacing,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<u64, SendFileToSerialPortError> {
+    tracing::info!(name=%name, path=%path, ?pacing, "Sending file to serial port");
+
+    let (transfer_id, rx) = state.serial_state().send_file(&name, &path, pacing).await?;
+
+    spawn_emit_transfer_events(name, transfer_id, rx, app);
+
+    Ok(transfer_id)
+}
+
+/// Emits the events of the transfer until its channel is closed.
+pub(super) fn spawn_emit_transfer_events(
+    name: String,
+    transfer_id: u64,
+    mut rx: MPSCUnboundedReceiver<CoreTransferEvent>,
+    app: &AppHandle,
+) {
+    let app = app.clone();
+
+    tokio::spawn(async move {
+        tracing::debug!(name=%name, transfer_id, "Transfer events task started");
+
+        while let Some(event) = rx.recv().await {
+            let event = TransferEvent {
+                transfer_id,
+                port_name: name.clone(),
+                status: event.into(),
+            };
+
+            let _ = emit_transfer_event(&app, &event);
+        }
+
+        tracing::debug!(name=%name, transfer_id, "Transfer events task terminated");
+    });
+}
+
+pub async fn cancel_transfer_intern(
+    transfer_id: u64,
+    state: &TauriAppState,
+) -> Result<(), CancelTransferError> {
+    tracing::info!(transfer_id, "Cancelling transfer");
+
+    state
+        .serial_state()
+        .cancel_transfer(transfer_id)
+        .await
+        .ok_or(CancelTransferError::NotFound)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendFileToSerialPortError {
+    #[error("Failed to send file: {0}")]
+    SendFileError(
+        #[source]
+        #[from]
+        CoreSendFileError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum CancelTransferError {
+    #[error("Transfer not found")]
+    NotFound,
+}
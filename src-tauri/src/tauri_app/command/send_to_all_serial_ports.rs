@@ -1,17 +1,33 @@
-use tokio_util::bytes::Bytes;
-
 use crate::app::serial_state::{
+    input::{CoreInputMode, CoreInputParseError},
     model::{CoreOutgoingPacket, CorePacketOrigin},
     CoreSerialState,
 };
 
-pub async fn send_to_all_serial_ports_intern(bytes: Bytes, state: &CoreSerialState) {
-    tracing::info!("Sending to all serial ports");
+pub async fn send_to_all_serial_ports_intern(
+    value: String,
+    input_mode: CoreInputMode,
+    state: &CoreSerialState,
+) -> Result<(), SendToAllSerialPortsError> {
+    tracing::info!(?input_mode, "Sending to all serial ports");
 
     let packet = CoreOutgoingPacket {
-        bytes,
+        bytes: input_mode.parse(&value)?,
         packet_origin: CorePacketOrigin::Broadcast,
+        ack: None,
     };
 
-    state.send_to_all_open_serial_ports(packet).await
+    state.send_to_all_open_serial_ports(packet).await;
+
+    Ok(())
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum SendToAllSerialPortsError {
+    #[error("Failed to parse value: {0}")]
+    ParseError(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
 }
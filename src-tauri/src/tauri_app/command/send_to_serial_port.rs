@@ -1,37 +1,46 @@
-use tokio_util::bytes::Bytes;
-
-use crate::app::serial_state::{
-    handle::SendError,
-    model::{CoreOutgoingPacket, CorePacketOrigin},
-    CoreSerialState,
+use crate::{
+    app::serial_state::{
+        error::CoreSendAcknowledgedError,
+        input::{CoreInputMode, CoreInputParseError},
+        model::{CoreOutgoingPacket, CorePacketOrigin},
+        CoreSerialState,
+    },
+    tauri_app::model::packet::WriteAck,
 };
 
+/// Resolves once the value is written to the serial port.
 pub async fn send_to_serial_port_intern(
     name: String,
-    bytes: Bytes,
+    value: String,
+    input_mode: CoreInputMode,
     state: &CoreSerialState,
-) -> Result<(), SendToSerialPortError> {
-    tracing::info!(name=%name, "Sending to serial port");
+) -> Result<WriteAck, SendToSerialPortError> {
+    tracing::info!(name=%name, ?input_mode, "Sending to serial port");
 
     let packet = CoreOutgoingPacket {
-        bytes,
+        bytes: input_mode.parse(&value)?,
         packet_origin: CorePacketOrigin::Direct,
+        ack: None,
     };
 
     Ok(state
-        .send_to_open_serial_port(&name, packet)
-        .await
-        .ok_or(SendToSerialPortError::NotOpen)??)
+        .send_to_open_serial_port_acknowledged(&name, packet)
+        .await?
+        .into())
 }
 
 #[derive(Debug, thiserror::Error)]
 pub enum SendToSerialPortError {
-    #[error("Port not open")]
-    NotOpen,
+    #[error("Failed to parse value: {0}")]
+    ParseError(
+        #[source]
+        #[from]
+        CoreInputParseError,
+    ),
     #[error("Failed to send: {0}")]
     SendError(
         #[source]
         #[from]
-        SendError,
+        CoreSendAcknowledgedError,
     ),
 }
@@ -1,18 +1,51 @@
+#[cfg(feature = "subscriptions")]
+use crate::app::{model::subscription::AppSubscription, state::error::AppSubscriptionError};
 use crate::{
     app::state::error::AppManagedSerialPortsError,
-    tauri_app::{model::managed_serial_port::ManagedSerialPort, state::TauriAppState},
+    tauri_app::{
+        model::{
+            managed_serial_port::ManagedSerialPort,
+            subscription::{
+                SubscriptionFilter, SubscriptionMetrics, SubscriptionMode, SubscriptionRateLimit,
+            },
+        },
+        state::TauriAppState,
+    },
 };
 
 pub async fn subscribe_intern(
     from: &str,
     to: &str,
+    _filter: Option<SubscriptionFilter>,
+    _mode: SubscriptionMode,
+    _rate_limit: SubscriptionRateLimit,
+    _allow_cycle: bool,
     _state: &TauriAppState,
 ) -> Result<Vec<ManagedSerialPort>, SubscribeError> {
-    tracing::info!(from=%from, to=%to, "Subscribing");
+    tracing::info!(from=%from, to=%to, filter=?_filter, mode=?_mode, rate_limit=?_rate_limit, allow_cycle=%_allow_cycle, "Subscribing");
 
     #[cfg(feature = "subscriptions")]
     return {
-        _state.serial_state().subscribe(from, to).await;
+        // Kept when resubscribing. Changed with `set_subscription_echo_guard`.
+        let echo_guard = _state
+            .app_state()
+            .get_subscription(from, to)
+            .await?
+            .map_or(true, |subscription| subscription.echo_guard);
+
+        let subscription = AppSubscription {
+            from: from.to_string(),
+            to: to.to_string(),
+            filter: _filter.map(Into::into),
+            mode: _mode.into(),
+            rate_limit: _rate_limit.into(),
+            echo_guard,
+        };
+
+        _state
+            .app_state()
+            .subscribe(subscription, _allow_cycle)
+            .await?;
 
         let managed_serial_ports = _state.get_managed_serial_ports().await?;
 
@@ -32,7 +65,107 @@ pub async fn unsubscribe_intern(
 
     #[cfg(feature = "subscriptions")]
     return {
-        _state.serial_state().unsubscribe(from, to).await;
+        _state.app_state().unsubscribe(from, to).await?;
+
+        let managed_serial_ports = _state.get_managed_serial_ports().await?;
+
+        Ok(managed_serial_ports)
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(SubscribeError::Disabled)
+}
+
+/// Edits the filter of an existing subscription.
+pub async fn set_subscription_filter_intern(
+    from: &str,
+    to: &str,
+    _filter: Option<SubscriptionFilter>,
+    _state: &TauriAppState,
+) -> Result<Vec<ManagedSerialPort>, SubscribeError> {
+    tracing::info!(from=%from, to=%to, filter=?_filter, "Setting subscription filter");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        _state
+            .app_state()
+            .set_subscription_filter(from, to, _filter.map(Into::into))
+            .await?;
+
+        let managed_serial_ports = _state.get_managed_serial_ports().await?;
+
+        Ok(managed_serial_ports)
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(SubscribeError::Disabled)
+}
+
+/// Edits the mode of an existing subscription.
+pub async fn set_subscription_mode_intern(
+    from: &str,
+    to: &str,
+    _mode: SubscriptionMode,
+    _state: &TauriAppState,
+) -> Result<Vec<ManagedSerialPort>, SubscribeError> {
+    tracing::info!(from=%from, to=%to, mode=?_mode, "Setting subscription mode");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        _state
+            .app_state()
+            .set_subscription_mode(from, to, _mode.into())
+            .await?;
+
+        let managed_serial_ports = _state.get_managed_serial_ports().await?;
+
+        Ok(managed_serial_ports)
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(SubscribeError::Disabled)
+}
+
+/// Edits the rate limit of an existing subscription.
+pub async fn set_subscription_rate_limit_intern(
+    from: &str,
+    to: &str,
+    _rate_limit: SubscriptionRateLimit,
+    _state: &TauriAppState,
+) -> Result<Vec<ManagedSerialPort>, SubscribeError> {
+    tracing::info!(from=%from, to=%to, rate_limit=?_rate_limit, "Setting subscription rate limit");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        _state
+            .app_state()
+            .set_subscription_rate_limit(from, to, _rate_limit.into())
+            .await?;
+
+        let managed_serial_ports = _state.get_managed_serial_ports().await?;
+
+        Ok(managed_serial_ports)
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(SubscribeError::Disabled)
+}
+
+/// Enables or disables the echo guard of an existing subscription.
+pub async fn set_subscription_echo_guard_intern(
+    from: &str,
+    to: &str,
+    _echo_guard: bool,
+    _state: &TauriAppState,
+) -> Result<Vec<ManagedSerialPort>, SubscribeError> {
+    tracing::info!(from=%from, to=%to, echo_guard=%_echo_guard, "Setting subscription echo guard");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        _state
+            .app_state()
+            .set_subscription_echo_guard(from, to, _echo_guard)
+            .await?;
 
         let managed_serial_ports = _state.get_managed_serial_ports().await?;
 
@@ -43,6 +176,29 @@ pub async fn unsubscribe_intern(
     Err(SubscribeError::Disabled)
 }
 
+/// Returns the metrics of a subscription or a direction of a bridge.
+pub async fn get_subscription_metrics_intern(
+    from: &str,
+    to: &str,
+    _state: &TauriAppState,
+) -> Result<SubscriptionMetrics, SubscribeError> {
+    tracing::debug!(from=%from, to=%to, "Getting subscription metrics");
+
+    #[cfg(feature = "subscriptions")]
+    return {
+        let metrics = _state
+            .serial_state()
+            .subscription_metrics(from, to)
+            .await
+            .ok_or(SubscribeError::NotFound)?;
+
+        Ok(metrics.into())
+    };
+
+    #[cfg(not(feature = "subscriptions"))]
+    Err(SubscribeError::Disabled)
+}
+
 #[derive(Debug, thiserror::Error)]
 pub enum SubscribeError {
     #[cfg(not(feature = "subscriptions"))]
@@ -50,6 +206,16 @@ pub enum SubscribeError {
         "Subscriptions feature is not enabled, turn on the `subscriptions` feature to enable it"
     )]
     Disabled,
+    #[cfg(feature = "subscriptions")]
+    #[error("Subscription not found")]
+    NotFound,
+    #[cfg(feature = "subscriptions")]
+    #[error("Failed to manage subscription: {0}")]
+    AppSubscriptionError(
+        #[source]
+        #[from]
+        AppSubscriptionError,
+    ),
     #[error("Failed to get managed ports: {0}")]
     ManagedSerialPortsError(
         #[source]
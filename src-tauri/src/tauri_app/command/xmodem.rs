@@ -0,0 +1,68 @@
+use tauri::AppHandle;
+
+use crate::{
+    app::serial_state::{error::CoreStartXmodemError, model::CoreXmodemProtocol},
+    tauri_app::state::TauriAppState,
+};
+
+use super::send_file::spawn_emit_transfer_events;
+
+/// Returns the id of the transfer.
+pub async fn xmodem_send_intern(
+    name: String,
+    path: String,
+    protocol: CoreXmodemProtocol,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<u64, XmodemSendError> {
+    tracing::info!(name=%name, path=%path, %protocol, "Sending file via XMODEM");
+
+    let (transfer_id, rx) = state
+        .serial_state()
+        .xmodem_send(&name, &path, protocol)
+        .await?;
+
+    spawn_emit_transfer_events(name, transfer_id, rx, app);
+
+    Ok(transfer_id)
+}
+
+/// Returns the id of the transfer.
+pub async fn xmodem_receive_intern(
+    name: String,
+    path: String,
+    protocol: CoreXmodemProtocol,
+    app: &AppHandle,
+    state: &TauriAppState,
+) -> Result<u64, XmodemReceiveError> {
+    tracing::info!(name=%name, path=%path, %protocol, "Receiving file via XMODEM");
+
+    let (transfer_id, rx) = state
+        .serial_state()
+        .xmodem_receive(&name, &path, protocol)
+        .await?;
+
+    spawn_emit_transfer_events(name, transfer_id, rx, app);
+
+    Ok(transfer_id)
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum XmodemSendError {
+    #[error("Failed to start transfer: {0}")]
+    StartError(
+        #[source]
+        #[from]
+        CoreStartXmodemError,
+    ),
+}
+
+#[derive(Debug, thiserror::Error)]
+pub enum XmodemReceiveError {
+    #[error("Failed to start transfer: {0}")]
+    StartError(
+        #[source]
+        #[from]
+        CoreStartXmodemError,
+    ),
+}
@@ -0,0 +1,11 @@
+use tauri::{AppHandle, Manager};
+
+use crate::tauri_app::event::events::SCRIPT_EVENT;
+
+use super::model::script::ScriptEvent;
+
+pub fn emit_script_event(app: &AppHandle, event: &ScriptEvent) -> Result<(), tauri::Error> {
+    tracing::debug!("Emitting script");
+
+    app.emit_all(SCRIPT_EVENT, &event)
+}
@@ -0,0 +1,11 @@
+use tauri::{AppHandle, Manager};
+
+use crate::tauri_app::event::events::TRANSFER_EVENT;
+
+use super::model::transfer::TransferEvent;
+
+pub fn emit_transfer_event(app: &AppHandle, event: &TransferEvent) -> Result<(), tauri::Error> {
+    tracing::debug!("Emitting transfer");
+
+    app.emit_all(TRANSFER_EVENT, &event)
+}
@@ -1,3 +1,5 @@
 pub const SERIAL_PORTS_EVENT: &str = "serial_ports_event";
 pub const SERIAL_PACKET_EVENT: &str = "serial_packet_event";
 pub const ERROR_EVENT: &str = "error_event";
+pub const TRANSFER_EVENT: &str = "transfer_event";
+pub const SCRIPT_EVENT: &str = "script_event";
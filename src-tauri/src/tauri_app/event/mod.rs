@@ -1,5 +1,7 @@
 pub mod emit_error;
 pub mod emit_managed_serial_ports;
 pub mod emit_packet;
+pub mod emit_script;
+pub mod emit_transfer;
 pub mod events;
 pub mod model;
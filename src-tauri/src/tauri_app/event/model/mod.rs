@@ -1,3 +1,5 @@
 pub mod error;
 pub mod managed_serial_ports;
 pub mod packet;
+pub mod script;
+pub mod transfer;
@@ -0,0 +1,47 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptOutput {
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptFailed {
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum ScriptStatus {
+    Output(ScriptOutput),
+    Finished,
+    Cancelled,
+    Failed(ScriptFailed),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptEvent {
+    pub run_id: u64,
+    pub script_id: i32,
+    pub status: ScriptStatus,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::model::script::AppScriptEvent;
+
+    impl From<AppScriptEvent> for ScriptStatus {
+        fn from(value: AppScriptEvent) -> Self {
+            match value {
+                AppScriptEvent::Output(message) => Self::Output(ScriptOutput { message }),
+                AppScriptEvent::Finished => Self::Finished,
+                AppScriptEvent::Cancelled => Self::Cancelled,
+                AppScriptEvent::Failed(error) => Self::Failed(ScriptFailed { error }),
+            }
+        }
+    }
+}
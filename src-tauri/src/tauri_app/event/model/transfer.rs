@@ -0,0 +1,60 @@
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub bytes_transferred: u64,
+    /// `None` if the size is not known in advance.
+    pub bytes_total: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFailed {
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum TransferStatus {
+    Progress(TransferProgress),
+    Finished,
+    Cancelled,
+    /// The serial port was taken by an XMODEM or YMODEM transfer while a file was being sent.
+    PortBusy,
+    Failed(TransferFailed),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferEvent {
+    pub transfer_id: u64,
+    pub port_name: String,
+    pub status: TransferStatus,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::serial_state::model::CoreTransferEvent;
+
+    impl From<CoreTransferEvent> for TransferStatus {
+        fn from(value: CoreTransferEvent) -> Self {
+            match value {
+                CoreTransferEvent::Progress {
+                    bytes_transferred,
+                    bytes_total,
+                } => Self::Progress(TransferProgress {
+                    bytes_transferred,
+                    bytes_total,
+                }),
+                CoreTransferEvent::Finished => Self::Finished,
+                CoreTransferEvent::Cancelled => Self::Cancelled,
+                CoreTransferEvent::PortBusy => Self::PortBusy,
+                CoreTransferEvent::Failed(err) => Self::Failed(TransferFailed {
+                    error: err.to_string(),
+                }),
+            }
+        }
+    }
+}
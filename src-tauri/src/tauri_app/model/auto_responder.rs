@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+
+use super::{input_mode::InputMode, open_options::Duration};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoResponder {
+    pub name: String,
+    /// Name of the serial port whose incoming lines are matched.
+    pub port: String,
+    /// Regular expression.
+    pub pattern: String,
+    /// Capture groups of the pattern are substituted, e.g. `$1` or `${name}`.
+    pub response: String,
+    #[serde(default)]
+    pub input_mode: InputMode,
+    /// Name of the serial port the response is sent to. The matched serial port if not set.
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub delay: Duration,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredAutoResponder {
+    pub id: i32,
+    #[serde(flatten)]
+    pub auto_responder: AutoResponder,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::model::auto_responder::AppAutoResponder;
+
+    impl From<AutoResponder> for AppAutoResponder {
+        fn from(value: AutoResponder) -> Self {
+            Self {
+                name: value.name,
+                port: value.port,
+                pattern: value.pattern,
+                response: value.response,
+                input_mode: value.input_mode.into(),
+                target: value.target,
+                delay: value.delay.into(),
+                enabled: value.enabled,
+            }
+        }
+    }
+
+    impl From<AppAutoResponder> for AutoResponder {
+        fn from(value: AppAutoResponder) -> Self {
+            Self {
+                name: value.name,
+                port: value.port,
+                pattern: value.pattern,
+                response: value.response,
+                input_mode: value.input_mode.into(),
+                target: value.target,
+                delay: value.delay.into(),
+                enabled: value.enabled,
+            }
+        }
+    }
+}
@@ -0,0 +1,140 @@
+use serde::{Deserialize, Serialize};
+
+use super::{input_mode::InputMode, open_options::LineEnding};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum CommandMacroTarget {
+    #[default]
+    None,
+    /// Name of the serial port.
+    Port(String),
+    /// Tag the serial ports were opened with.
+    Tag(String),
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMacro {
+    pub name: String,
+    pub payload: String,
+    #[serde(default)]
+    pub input_mode: InputMode,
+    /// Overrides the outgoing line ending of the serial port if set.
+    #[serde(default)]
+    pub line_ending: Option<LineEnding>,
+    #[serde(default)]
+    pub target: CommandMacroTarget,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredCommandMacro {
+    pub id: i32,
+    #[serde(flatten)]
+    pub command_macro: CommandMacro,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum CommandMacroExecutionTarget {
+    /// The target stored with the macro.
+    #[default]
+    Default,
+    /// Names of the serial ports.
+    Ports(Vec<String>),
+    AllOpen,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMacroFailure {
+    /// Name of the serial port.
+    pub name: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandMacroOutcome {
+    /// Names of the serial ports the macro was sent to.
+    pub succeeded: Vec<String>,
+    pub failed: Vec<CommandMacroFailure>,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::model::command_macro::{
+        AppCommandMacro, AppCommandMacroExecutionTarget, AppCommandMacroOutcome,
+        AppCommandMacroTarget,
+    };
+
+    impl From<CommandMacroTarget> for AppCommandMacroTarget {
+        fn from(value: CommandMacroTarget) -> Self {
+            match value {
+                CommandMacroTarget::None => Self::None,
+                CommandMacroTarget::Port(name) => Self::Port(name),
+                CommandMacroTarget::Tag(tag) => Self::Tag(tag),
+            }
+        }
+    }
+
+    impl From<AppCommandMacroTarget> for CommandMacroTarget {
+        fn from(value: AppCommandMacroTarget) -> Self {
+            match value {
+                AppCommandMacroTarget::None => Self::None,
+                AppCommandMacroTarget::Port(name) => Self::Port(name),
+                AppCommandMacroTarget::Tag(tag) => Self::Tag(tag),
+            }
+        }
+    }
+
+    impl From<CommandMacro> for AppCommandMacro {
+        fn from(value: CommandMacro) -> Self {
+            Self {
+                name: value.name,
+                payload: value.payload,
+                input_mode: value.input_mode.into(),
+                line_ending: value.line_ending.map(Into::into),
+                target: value.target.into(),
+            }
+        }
+    }
+
+    impl From<AppCommandMacro> for CommandMacro {
+        fn from(value: AppCommandMacro) -> Self {
+            Self {
+                name: value.name,
+                payload: value.payload,
+                input_mode: value.input_mode.into(),
+                line_ending: value.line_ending.map(Into::into),
+                target: value.target.into(),
+            }
+        }
+    }
+
+    impl From<CommandMacroExecutionTarget> for AppCommandMacroExecutionTarget {
+        fn from(value: CommandMacroExecutionTarget) -> Self {
+            match value {
+                CommandMacroExecutionTarget::Default => Self::Default,
+                CommandMacroExecutionTarget::Ports(names) => Self::Ports(names),
+                CommandMacroExecutionTarget::AllOpen => Self::AllOpen,
+            }
+        }
+    }
+
+    impl From<AppCommandMacroOutcome> for CommandMacroOutcome {
+        fn from(value: AppCommandMacroOutcome) -> Self {
+            Self {
+                succeeded: value.succeeded,
+                failed: value
+                    .failed
+                    .into_iter()
+                    .map(|(name, error)| CommandMacroFailure { name, error })
+                    .collect(),
+            }
+        }
+    }
+}
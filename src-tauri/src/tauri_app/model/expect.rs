@@ -0,0 +1,29 @@
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum ExpectPattern {
+    /// The line contains the text.
+    Literal(String),
+    /// The line starts with the text.
+    Prefix(String),
+    Regex(String),
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::serial_state::model::CoreExpectPattern;
+
+    impl TryFrom<ExpectPattern> for CoreExpectPattern {
+        type Error = regex::Error;
+
+        fn try_from(value: ExpectPattern) -> Result<Self, Self::Error> {
+            Ok(match value {
+                ExpectPattern::Literal(literal) => Self::Literal(literal.into()),
+                ExpectPattern::Prefix(prefix) => Self::Prefix(prefix.into()),
+                ExpectPattern::Regex(regex) => Self::Regex(regex::bytes::Regex::new(&regex)?),
+            })
+        }
+    }
+}
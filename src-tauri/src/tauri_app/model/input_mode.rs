@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum InputMode {
+    #[default]
+    Text,
+    Escaped,
+    Hex,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::serial_state::input::CoreInputMode;
+
+    impl From<InputMode> for CoreInputMode {
+        fn from(value: InputMode) -> Self {
+            match value {
+                InputMode::Text => Self::Text,
+                InputMode::Escaped => Self::Escaped,
+                InputMode::Hex => Self::Hex,
+            }
+        }
+    }
+
+    impl From<CoreInputMode> for InputMode {
+        fn from(value: CoreInputMode) -> Self {
+            match value {
+                CoreInputMode::Text => Self::Text,
+                CoreInputMode::Escaped => Self::Escaped,
+                CoreInputMode::Hex => Self::Hex,
+            }
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use super::open_options::OpenSerialPortOptions;
+#[cfg(feature = "subscriptions")]
+use super::subscription::Subscription;
 
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -9,10 +11,22 @@ pub enum ReadState {
     Stop,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteQueueMetrics {
+    /// Values waiting to be written.
+    pub depth: usize,
+    /// `0` means unbounded.
+    pub capacity: usize,
+    /// Values dropped by the overflow policy or discarded by a transfer since the port was opened.
+    pub dropped: u64,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenStatus {
     pub read_state: ReadState,
+    pub write_queue: WriteQueueMetrics,
 }
 
 #[derive(Debug, Serialize)]
@@ -29,9 +43,12 @@ pub struct ManagedSerialPort {
     pub name: String,
     pub status: Status,
     #[cfg(feature = "subscriptions")]
-    pub subscriptions: Vec<String>,
+    pub subscriptions: Vec<Subscription>,
     #[cfg(feature = "subscriptions")]
     pub subscribed_to: Vec<String>,
+    /// Names of the serial ports bridged with this serial port.
+    #[cfg(feature = "subscriptions")]
+    pub bridged_with: Vec<String>,
     /// The last known used open options for the serial port.
     ///
     /// If not known, then default options are used.
@@ -42,13 +59,16 @@ mod core_impl {
     use super::*;
     use crate::{
         app::model::managed_serial_port::AppManagedSerialPort,
-        app::serial_state::model::{CoreOpenStatus, CoreReadState, Status as CoreStatus},
+        app::serial_state::model::{
+            CoreOpenStatus, CoreReadState, CoreWriteQueueMetrics, Status as CoreStatus,
+        },
     };
 
     impl From<CoreOpenStatus> for OpenStatus {
         fn from(value: CoreOpenStatus) -> Self {
             Self {
                 read_state: value.read_state.into(),
+                write_queue: value.write_queue.into(),
             }
         }
     }
@@ -57,6 +77,27 @@ mod core_impl {
         fn from(value: OpenStatus) -> Self {
             Self {
                 read_state: value.read_state.into(),
+                write_queue: value.write_queue.into(),
+            }
+        }
+    }
+
+    impl From<CoreWriteQueueMetrics> for WriteQueueMetrics {
+        fn from(value: CoreWriteQueueMetrics) -> Self {
+            Self {
+                depth: value.depth,
+                capacity: value.capacity,
+                dropped: value.dropped,
+            }
+        }
+    }
+
+    impl From<WriteQueueMetrics> for CoreWriteQueueMetrics {
+        fn from(value: WriteQueueMetrics) -> Self {
+            Self {
+                depth: value.depth,
+                capacity: value.capacity,
+                dropped: value.dropped,
             }
         }
     }
@@ -103,9 +144,16 @@ mod core_impl {
                 name: value.managed_serial_port.name,
                 status: value.managed_serial_port.status.into(),
                 #[cfg(feature = "subscriptions")]
-                subscriptions: value.managed_serial_port.subscriptions,
+                subscriptions: value
+                    .managed_serial_port
+                    .subscriptions
+                    .into_iter()
+                    .map(Into::into)
+                    .collect(),
                 #[cfg(feature = "subscriptions")]
                 subscribed_to: value.managed_serial_port.subscribed_to,
+                #[cfg(feature = "subscriptions")]
+                bridged_with: value.managed_serial_port.bridged_with,
                 last_used_open_options: value.last_used_open_options.into(),
             }
         }
@@ -128,11 +176,25 @@ mod tests {
             name: "COM1".to_string(),
             status: Status::Open(OpenStatus {
                 read_state: ReadState::Read,
+                write_queue: WriteQueueMetrics {
+                    depth: 2,
+                    capacity: 16,
+                    dropped: 0,
+                },
             }),
             #[cfg(feature = "subscriptions")]
-            subscriptions: vec!["COM2".to_string()],
+            subscriptions: vec![Subscription {
+                name: "COM2".to_string(),
+                filter: None,
+                mode: Default::default(),
+                rate_limit: Default::default(),
+                echo_guard: true,
+                metrics: Default::default(),
+            }],
             #[cfg(feature = "subscriptions")]
             subscribed_to: vec!["COM3".to_string()],
+            #[cfg(feature = "subscriptions")]
+            bridged_with: vec!["COM4".to_string()],
             last_used_open_options: AppOpenSerialPortOptions {
                 tag: "tag".to_string(),
                 core_options: CoreOpenSerialPortOptions::default(),
@@ -152,9 +214,18 @@ mod tests {
             name: "COM1".to_string(),
             status: Status::Closed,
             #[cfg(feature = "subscriptions")]
-            subscriptions: vec!["COM2".to_string()],
+            subscriptions: vec![Subscription {
+                name: "COM2".to_string(),
+                filter: None,
+                mode: Default::default(),
+                rate_limit: Default::default(),
+                echo_guard: true,
+                metrics: Default::default(),
+            }],
             #[cfg(feature = "subscriptions")]
             subscribed_to: vec!["COM3".to_string()],
+            #[cfg(feature = "subscriptions")]
+            bridged_with: vec!["COM4".to_string()],
             last_used_open_options: AppOpenSerialPortOptions {
                 tag: "tag".to_string(),
                 core_options: CoreOpenSerialPortOptions::default(),
@@ -1,3 +1,13 @@
+pub mod auto_responder;
+pub mod command_macro;
+pub mod expect;
+pub mod input_mode;
 pub mod managed_serial_port;
 pub mod open_options;
 pub mod packet;
+pub mod periodic_job;
+pub mod port_group;
+pub mod retention;
+pub mod script;
+pub mod subscription;
+pub mod transfer;
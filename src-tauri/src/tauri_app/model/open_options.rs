@@ -34,18 +34,69 @@ pub enum StopBits {
     Two,
 }
 
-#[derive(Debug, Deserialize, Serialize)]
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum LineEnding {
+    #[default]
+    None,
+    Lf,
+    Cr,
+    CrLf,
+    Custom(Vec<u8>),
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Duration {
     pub secs: u64,
     pub nanos: u32,
 }
 
+/// A line terminator is a `\n` or a `\r` not followed by a `\n`.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WritePacing {
+    /// Delay after each written byte.
+    pub byte_delay: Duration,
+    /// Delay after each written line terminator. Added to the byte delay.
+    pub line_delay: Duration,
+}
+
+/// What happens to a value sent to a full write queue.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WriteQueueOverflow {
+    /// The sender waits until there is room.
+    #[default]
+    Block,
+    DropOldest,
+    /// The sent value is dropped and the send fails.
+    DropNewest,
+    /// The send fails.
+    Error,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteQueueOptions {
+    /// Maximum number of values waiting to be written. `0` means unbounded.
+    pub capacity: usize,
+    pub overflow: WriteQueueOverflow,
+}
+
 #[derive(Debug, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenSerialPortOptions {
     pub tag: String,
     pub initial_read_state: ReadState,
+    /// Appended to every value sent directly, via a broadcast, periodically or via a macro without its own line ending.
+    #[serde(default)]
+    pub outgoing_line_ending: LineEnding,
+    #[serde(default)]
+    pub write_pacing: WritePacing,
+    #[serde(default)]
+    pub write_queue: WriteQueueOptions,
     pub baud_rate: u32,
     pub data_bits: DataBits,
     pub flow_control: FlowControl,
@@ -60,7 +111,8 @@ mod core_impl {
     use crate::{
         app::model::managed_serial_port::AppOpenSerialPortOptions,
         app::serial_state::model::{
-            CoreDataBits, CoreFlowControl, CoreOpenSerialPortOptions, CoreParity, CoreStopBits,
+            CoreDataBits, CoreFlowControl, CoreLineEnding, CoreOpenSerialPortOptions, CoreParity,
+            CoreStopBits, CoreWritePacing, CoreWriteQueueOptions, CoreWriteQueueOverflow,
         },
     };
 
@@ -146,6 +198,30 @@ mod core_impl {
         }
     }
 
+    impl From<LineEnding> for CoreLineEnding {
+        fn from(value: LineEnding) -> Self {
+            match value {
+                LineEnding::None => Self::None,
+                LineEnding::Lf => Self::Lf,
+                LineEnding::Cr => Self::Cr,
+                LineEnding::CrLf => Self::CrLf,
+                LineEnding::Custom(bytes) => Self::Custom(bytes.into()),
+            }
+        }
+    }
+
+    impl From<CoreLineEnding> for LineEnding {
+        fn from(value: CoreLineEnding) -> Self {
+            match value {
+                CoreLineEnding::None => Self::None,
+                CoreLineEnding::Lf => Self::Lf,
+                CoreLineEnding::Cr => Self::Cr,
+                CoreLineEnding::CrLf => Self::CrLf,
+                CoreLineEnding::Custom(bytes) => Self::Custom(bytes.to_vec()),
+            }
+        }
+    }
+
     impl From<Duration> for CoreDuration {
         fn from(value: Duration) -> Self {
             Self::new(value.secs, value.nanos)
@@ -161,12 +237,73 @@ mod core_impl {
         }
     }
 
+    impl From<WritePacing> for CoreWritePacing {
+        fn from(value: WritePacing) -> Self {
+            Self {
+                byte_delay: value.byte_delay.into(),
+                line_delay: value.line_delay.into(),
+            }
+        }
+    }
+
+    impl From<CoreWritePacing> for WritePacing {
+        fn from(value: CoreWritePacing) -> Self {
+            Self {
+                byte_delay: value.byte_delay.into(),
+                line_delay: value.line_delay.into(),
+            }
+        }
+    }
+
+    impl From<WriteQueueOverflow> for CoreWriteQueueOverflow {
+        fn from(value: WriteQueueOverflow) -> Self {
+            match value {
+                WriteQueueOverflow::Block => Self::Block,
+                WriteQueueOverflow::DropOldest => Self::DropOldest,
+                WriteQueueOverflow::DropNewest => Self::DropNewest,
+                WriteQueueOverflow::Error => Self::Error,
+            }
+        }
+    }
+
+    impl From<CoreWriteQueueOverflow> for WriteQueueOverflow {
+        fn from(value: CoreWriteQueueOverflow) -> Self {
+            match value {
+                CoreWriteQueueOverflow::Block => Self::Block,
+                CoreWriteQueueOverflow::DropOldest => Self::DropOldest,
+                CoreWriteQueueOverflow::DropNewest => Self::DropNewest,
+                CoreWriteQueueOverflow::Error => Self::Error,
+            }
+        }
+    }
+
+    impl From<WriteQueueOptions> for CoreWriteQueueOptions {
+        fn from(value: WriteQueueOptions) -> Self {
+            Self {
+                capacity: value.capacity,
+                overflow: value.overflow.into(),
+            }
+        }
+    }
+
+    impl From<CoreWriteQueueOptions> for WriteQueueOptions {
+        fn from(value: CoreWriteQueueOptions) -> Self {
+            Self {
+                capacity: value.capacity,
+                overflow: value.overflow.into(),
+            }
+        }
+    }
+
     impl From<OpenSerialPortOptions> for AppOpenSerialPortOptions {
         fn from(value: OpenSerialPortOptions) -> Self {
             Self {
                 tag: value.tag,
                 core_options: CoreOpenSerialPortOptions {
                     initial_read_state: value.initial_read_state.into(),
+                    outgoing_line_ending: value.outgoing_line_ending.into(),
+                    write_pacing: value.write_pacing.into(),
+                    write_queue: value.write_queue.into(),
                     baud_rate: value.baud_rate,
                     data_bits: value.data_bits.into(),
                     flow_control: value.flow_control.into(),
@@ -183,6 +320,9 @@ mod core_impl {
             Self {
                 tag: value.tag,
                 initial_read_state: value.core_options.initial_read_state.into(),
+                outgoing_line_ending: value.core_options.outgoing_line_ending.into(),
+                write_pacing: value.core_options.write_pacing.into(),
+                write_queue: value.core_options.write_queue.into(),
                 baud_rate: value.core_options.baud_rate,
                 data_bits: value.core_options.data_bits.into(),
                 flow_control: value.core_options.flow_control.into(),
@@ -1,4 +1,4 @@
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
@@ -12,6 +12,42 @@ pub struct SubscriptionPacketOrigin {
     pub name: String,
 }
 
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilePacketOrigin {
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferPacketOrigin {
+    pub path: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicPacketOrigin {
+    pub job_id: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MacroPacketOrigin {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GroupPacketOrigin {
+    pub name: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AutoResponderPacketOrigin {
+    pub name: String,
+}
+
 #[derive(Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 #[serde(tag = "type", content = "content")]
@@ -20,6 +56,13 @@ pub enum PacketOrigin {
     Broadcast,
     #[cfg(feature = "subscriptions")]
     Subscription(SubscriptionPacketOrigin),
+    File(FilePacketOrigin),
+    /// XMODEM or YMODEM transfer.
+    Transfer(TransferPacketOrigin),
+    Periodic(PeriodicPacketOrigin),
+    Macro(MacroPacketOrigin),
+    Group(GroupPacketOrigin),
+    AutoResponder(AutoResponderPacketOrigin),
 }
 
 #[derive(Debug, Serialize)]
@@ -45,15 +88,112 @@ pub enum PacketDirection {
 pub struct Packet {
     pub packet_direction: PacketDirection,
     pub port_name: String,
+    /// Timestamp of the last byte in milliseconds.
     pub timestamp_millis: u64,
+    pub first_byte_timestamp_micros: u64,
+    pub last_byte_timestamp_micros: u64,
+}
+
+/// Returned once a value is written to the serial port.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteAck {
+    pub first_byte_timestamp_micros: u64,
+    /// Completion of the write.
+    pub last_byte_timestamp_micros: u64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PacketDirectionFilter {
+    Incoming,
+    Outgoing,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum PacketOriginFilter {
+    Direct,
+    Broadcast,
+    Subscription,
+    File,
+    Transfer,
+    Periodic,
+    Macro,
+    Group,
+    AutoResponder,
+}
+
+/// See [`AppPacketQuery`](crate::app::model::packet::AppPacketQuery).
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketQuery {
+    #[serde(default)]
+    pub port: Option<String>,
+    #[serde(default)]
+    pub tag: Option<String>,
+    #[serde(default)]
+    pub direction: Option<PacketDirectionFilter>,
+    #[serde(default)]
+    pub origin: Option<PacketOriginFilter>,
+    /// Inclusive.
+    #[serde(default)]
+    pub from_timestamp_micros: Option<u64>,
+    /// Exclusive.
+    #[serde(default)]
+    pub to_timestamp_micros: Option<u64>,
+    /// `nextCursor` of the previous page.
+    #[serde(default)]
+    pub cursor: Option<i32>,
+    /// Defaults to 100, at most 1000.
+    #[serde(default)]
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredPacket {
+    pub id: i32,
+    pub tag: String,
+    pub packet: Packet,
+}
+
+/// See [`AppPacketWriterMetrics`](crate::app::model::packet::AppPacketWriterMetrics).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketWriterMetrics {
+    /// Packets waiting to be saved.
+    pub backlog: u64,
+    pub written: u64,
+    /// Failed attempts, including the retried ones.
+    pub failed: u64,
+    /// Packets given up on. They are not saved.
+    pub dropped: u64,
+    pub last_error: Option<String>,
+}
+
+/// Packets, newest first.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PacketPage {
+    pub packets: Vec<StoredPacket>,
+    /// Not set if this is the last page.
+    pub next_cursor: Option<i32>,
 }
 
 mod core_impl {
     use super::*;
+    use crate::app::model::packet::{
+        AppPacketDirectionFilter, AppPacketOriginFilter, AppPacketPage, AppPacketQuery,
+        AppPacketWriterMetrics, AppStoredPacket,
+    };
     #[cfg(feature = "subscriptions")]
     use crate::app::serial_state::model::CoreSubscriptionPacketOrigin;
     use crate::app::serial_state::model::{
-        CoreIncomingPacket, CoreOutgoingPacket, CorePacket, CorePacketDirection, CorePacketOrigin,
+        CoreAutoResponderPacketOrigin, CoreFilePacketOrigin, CoreGroupPacketOrigin,
+        CoreIncomingPacket, CoreMacroPacketOrigin, CoreOutgoingPacket, CorePacket,
+        CorePacketDirection, CorePacketOrigin, CorePeriodicPacketOrigin, CoreTransferPacketOrigin,
+        CoreWriteAck,
     };
 
     impl From<CoreIncomingPacket> for IncomingPacket {
@@ -71,6 +211,44 @@ mod core_impl {
         }
     }
 
+    impl From<CoreFilePacketOrigin> for FilePacketOrigin {
+        fn from(value: CoreFilePacketOrigin) -> Self {
+            Self { path: value.path }
+        }
+    }
+
+    impl From<CorePeriodicPacketOrigin> for PeriodicPacketOrigin {
+        fn from(value: CorePeriodicPacketOrigin) -> Self {
+            Self {
+                job_id: value.job_id,
+            }
+        }
+    }
+
+    impl From<CoreMacroPacketOrigin> for MacroPacketOrigin {
+        fn from(value: CoreMacroPacketOrigin) -> Self {
+            Self { name: value.name }
+        }
+    }
+
+    impl From<CoreGroupPacketOrigin> for GroupPacketOrigin {
+        fn from(value: CoreGroupPacketOrigin) -> Self {
+            Self { name: value.name }
+        }
+    }
+
+    impl From<CoreAutoResponderPacketOrigin> for AutoResponderPacketOrigin {
+        fn from(value: CoreAutoResponderPacketOrigin) -> Self {
+            Self { name: value.name }
+        }
+    }
+
+    impl From<CoreTransferPacketOrigin> for TransferPacketOrigin {
+        fn from(value: CoreTransferPacketOrigin) -> Self {
+            Self { path: value.path }
+        }
+    }
+
     impl From<CorePacketOrigin> for PacketOrigin {
         fn from(value: CorePacketOrigin) -> Self {
             match value {
@@ -78,6 +256,12 @@ mod core_impl {
                 CorePacketOrigin::Broadcast => Self::Broadcast,
                 #[cfg(feature = "subscriptions")]
                 CorePacketOrigin::Subscription(origin) => Self::Subscription(origin.into()),
+                CorePacketOrigin::File(origin) => Self::File(origin.into()),
+                CorePacketOrigin::Transfer(origin) => Self::Transfer(origin.into()),
+                CorePacketOrigin::Periodic(origin) => Self::Periodic(origin.into()),
+                CorePacketOrigin::Macro(origin) => Self::Macro(origin.into()),
+                CorePacketOrigin::Group(origin) => Self::Group(origin.into()),
+                CorePacketOrigin::AutoResponder(origin) => Self::AutoResponder(origin.into()),
             }
         }
     }
@@ -103,9 +287,91 @@ mod core_impl {
     impl From<CorePacket> for Packet {
         fn from(value: CorePacket) -> Self {
             Self {
+                timestamp_millis: value.timestamp_millis(),
                 packet_direction: value.packet_direction.into(),
                 port_name: value.port_name,
-                timestamp_millis: value.timestamp_millis,
+                first_byte_timestamp_micros: value.first_byte_timestamp_micros,
+                last_byte_timestamp_micros: value.last_byte_timestamp_micros,
+            }
+        }
+    }
+
+    impl From<PacketDirectionFilter> for AppPacketDirectionFilter {
+        fn from(value: PacketDirectionFilter) -> Self {
+            match value {
+                PacketDirectionFilter::Incoming => Self::Incoming,
+                PacketDirectionFilter::Outgoing => Self::Outgoing,
+            }
+        }
+    }
+
+    impl From<PacketOriginFilter> for AppPacketOriginFilter {
+        fn from(value: PacketOriginFilter) -> Self {
+            match value {
+                PacketOriginFilter::Direct => Self::Direct,
+                PacketOriginFilter::Broadcast => Self::Broadcast,
+                PacketOriginFilter::Subscription => Self::Subscription,
+                PacketOriginFilter::File => Self::File,
+                PacketOriginFilter::Transfer => Self::Transfer,
+                PacketOriginFilter::Periodic => Self::Periodic,
+                PacketOriginFilter::Macro => Self::Macro,
+                PacketOriginFilter::Group => Self::Group,
+                PacketOriginFilter::AutoResponder => Self::AutoResponder,
+            }
+        }
+    }
+
+    impl From<PacketQuery> for AppPacketQuery {
+        fn from(value: PacketQuery) -> Self {
+            Self {
+                port: value.port,
+                tag: value.tag,
+                direction: value.direction.map(Into::into),
+                origin: value.origin.map(Into::into),
+                from_timestamp_micros: value.from_timestamp_micros,
+                to_timestamp_micros: value.to_timestamp_micros,
+                cursor: value.cursor,
+                limit: value.limit.unwrap_or(AppPacketQuery::DEFAULT_LIMIT),
+            }
+        }
+    }
+
+    impl From<AppStoredPacket> for StoredPacket {
+        fn from(value: AppStoredPacket) -> Self {
+            Self {
+                id: value.id,
+                tag: value.tag,
+                packet: value.packet.into(),
+            }
+        }
+    }
+
+    impl From<AppPacketPage> for PacketPage {
+        fn from(value: AppPacketPage) -> Self {
+            Self {
+                packets: value.packets.into_iter().map(Into::into).collect(),
+                next_cursor: value.next_cursor,
+            }
+        }
+    }
+
+    impl From<AppPacketWriterMetrics> for PacketWriterMetrics {
+        fn from(value: AppPacketWriterMetrics) -> Self {
+            Self {
+                backlog: value.backlog,
+                written: value.written,
+                failed: value.failed,
+                dropped: value.dropped,
+                last_error: value.last_error,
+            }
+        }
+    }
+
+    impl From<CoreWriteAck> for WriteAck {
+        fn from(value: CoreWriteAck) -> Self {
+            Self {
+                first_byte_timestamp_micros: value.first_byte_timestamp_micros,
+                last_byte_timestamp_micros: value.last_byte_timestamp_micros,
             }
         }
     }
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+use super::open_options::Duration;
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum PeriodicSchedule {
+    Interval(Duration),
+    /// Cron expression with a seconds field, e.g. `0/5 * * * * *`. Evaluated in UTC.
+    Cron(String),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PeriodicJob {
+    pub id: u64,
+    pub port_name: String,
+    /// Lossy UTF-8 string representation of the sent bytes.
+    pub value: String,
+    pub schedule: PeriodicSchedule,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::serial_state::model::{CorePeriodicJob, CorePeriodicSchedule};
+
+    impl From<PeriodicSchedule> for CorePeriodicSchedule {
+        fn from(value: PeriodicSchedule) -> Self {
+            match value {
+                PeriodicSchedule::Interval(interval) => Self::Interval(interval.into()),
+                PeriodicSchedule::Cron(expression) => Self::Cron(expression),
+            }
+        }
+    }
+
+    impl From<CorePeriodicSchedule> for PeriodicSchedule {
+        fn from(value: CorePeriodicSchedule) -> Self {
+            match value {
+                CorePeriodicSchedule::Interval(interval) => Self::Interval(interval.into()),
+                CorePeriodicSchedule::Cron(expression) => Self::Cron(expression),
+            }
+        }
+    }
+
+    impl From<CorePeriodicJob> for PeriodicJob {
+        fn from(value: CorePeriodicJob) -> Self {
+            Self {
+                id: value.id,
+                port_name: value.port_name,
+                value: String::from_utf8_lossy(&value.bytes).to_string(),
+                schedule: value.schedule.into(),
+            }
+        }
+    }
+}
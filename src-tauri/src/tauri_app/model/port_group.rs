@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortGroup {
+    pub name: String,
+    /// Names of the serial ports.
+    pub ports: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredPortGroup {
+    pub id: i32,
+    #[serde(flatten)]
+    pub port_group: PortGroup,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortGroupFailure {
+    pub name: String,
+    pub error: String,
+}
+
+/// Result of an operation applied to every serial port of a group.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortGroupOutcome {
+    /// Names of the serial ports the operation succeeded for.
+    pub succeeded: Vec<String>,
+    pub failed: Vec<PortGroupFailure>,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::model::port_group::{AppPortGroup, AppPortGroupOutcome};
+
+    impl From<PortGroup> for AppPortGroup {
+        fn from(value: PortGroup) -> Self {
+            Self {
+                name: value.name,
+                ports: value.ports,
+            }
+        }
+    }
+
+    impl From<AppPortGroup> for PortGroup {
+        fn from(value: AppPortGroup) -> Self {
+            Self {
+                name: value.name,
+                ports: value.ports,
+            }
+        }
+    }
+
+    /// The outputs of the operation are discarded.
+    impl<T> From<AppPortGroupOutcome<T>> for PortGroupOutcome {
+        fn from(value: AppPortGroupOutcome<T>) -> Self {
+            Self {
+                succeeded: value.succeeded.into_iter().map(|(name, _)| name).collect(),
+                failed: value
+                    .failed
+                    .into_iter()
+                    .map(|(name, error)| PortGroupFailure { name, error })
+                    .collect(),
+            }
+        }
+    }
+}
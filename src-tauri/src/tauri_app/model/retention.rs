@@ -0,0 +1,131 @@
+use serde::{Deserialize, Serialize};
+
+use super::open_options::Duration;
+
+/// See [`AppRetentionScope`](crate::app::model::retention::AppRetentionScope).
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionScope {
+    /// Every serial port if not set.
+    pub port: Option<String>,
+    /// Every tag if not set.
+    pub tag: Option<String>,
+}
+
+/// See [`AppRetentionPolicy`](crate::app::model::retention::AppRetentionPolicy).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RetentionPolicy {
+    /// All packets if not set.
+    #[serde(default)]
+    pub scope: RetentionScope,
+    pub max_age: Option<Duration>,
+    pub max_rows: Option<u64>,
+    /// Counts the data of the packets only.
+    pub max_bytes: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PruneReport {
+    pub deleted_packets: u64,
+    pub failed_scopes: Vec<RetentionScope>,
+    pub vacuumed: bool,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortPacketStats {
+    /// Name of the serial port.
+    pub port: String,
+    pub packets: u64,
+    /// Data of the packets.
+    pub bytes: u64,
+}
+
+/// See [`AppDatabaseStats`](crate::app::model::retention::AppDatabaseStats).
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DatabaseStats {
+    pub size_bytes: u64,
+    /// Space of deleted packets, reused before the database grows.
+    pub free_bytes: u64,
+    pub ports: Vec<PortPacketStats>,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::model::retention::{
+        AppDatabaseStats, AppPortPacketStats, AppPruneReport, AppRetentionPolicy, AppRetentionScope,
+    };
+
+    impl From<RetentionScope> for AppRetentionScope {
+        fn from(value: RetentionScope) -> Self {
+            Self {
+                port: value.port,
+                tag: value.tag,
+            }
+        }
+    }
+
+    impl From<AppRetentionScope> for RetentionScope {
+        fn from(value: AppRetentionScope) -> Self {
+            Self {
+                port: value.port,
+                tag: value.tag,
+            }
+        }
+    }
+
+    impl From<RetentionPolicy> for AppRetentionPolicy {
+        fn from(value: RetentionPolicy) -> Self {
+            Self {
+                scope: value.scope.into(),
+                max_age: value.max_age.map(Into::into),
+                max_rows: value.max_rows,
+                max_bytes: value.max_bytes,
+            }
+        }
+    }
+
+    impl From<AppRetentionPolicy> for RetentionPolicy {
+        fn from(value: AppRetentionPolicy) -> Self {
+            Self {
+                scope: value.scope.into(),
+                max_age: value.max_age.map(Into::into),
+                max_rows: value.max_rows,
+                max_bytes: value.max_bytes,
+            }
+        }
+    }
+
+    impl From<AppPruneReport> for PruneReport {
+        fn from(value: AppPruneReport) -> Self {
+            Self {
+                deleted_packets: value.deleted_packets,
+                failed_scopes: value.failed_scopes.into_iter().map(Into::into).collect(),
+                vacuumed: value.vacuumed,
+            }
+        }
+    }
+
+    impl From<AppPortPacketStats> for PortPacketStats {
+        fn from(value: AppPortPacketStats) -> Self {
+            Self {
+                port: value.port,
+                packets: value.packets,
+                bytes: value.bytes,
+            }
+        }
+    }
+
+    impl From<AppDatabaseStats> for DatabaseStats {
+        fn from(value: AppDatabaseStats) -> Self {
+            Self {
+                size_bytes: value.size_bytes,
+                free_bytes: value.free_bytes,
+                ports: value.ports.into_iter().map(Into::into).collect(),
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Script {
+    pub name: String,
+    /// Rhai source code.
+    pub source: String,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StoredScript {
+    pub id: i32,
+    #[serde(flatten)]
+    pub script: Script,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScriptRun {
+    pub id: u64,
+    pub script_id: i32,
+    pub script_name: String,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::model::script::{AppScript, AppScriptRun};
+
+    impl From<Script> for AppScript {
+        fn from(value: Script) -> Self {
+            Self {
+                name: value.name,
+                source: value.source,
+            }
+        }
+    }
+
+    impl From<AppScript> for Script {
+        fn from(value: AppScript) -> Self {
+            Self {
+                name: value.name,
+                source: value.source,
+            }
+        }
+    }
+
+    impl From<AppScriptRun> for ScriptRun {
+        fn from(value: AppScriptRun) -> Self {
+            Self {
+                id: value.id,
+                script_id: value.script_id,
+                script_name: value.script_name,
+            }
+        }
+    }
+}
@@ -0,0 +1,239 @@
+use serde::{Deserialize, Serialize};
+
+use super::open_options::{Duration, LineEnding};
+
+/// Only the matching lines are forwarded.
+///
+/// Requires [`SubscriptionMode::Lines`]. Subscribing or changing the filter or the mode fails otherwise.
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum SubscriptionFilter {
+    /// Regular expression matched against the lines.
+    Regex(String),
+    /// Bytes the lines start with.
+    Prefix(Vec<u8>),
+}
+
+/// See [`CoreLineForwarding`](crate::app::serial_state::model::CoreLineForwarding).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LineForwarding {
+    /// Replaces the line terminator read from the master.
+    #[serde(default = "default_line_ending")]
+    pub line_ending: LineEnding,
+    /// Prefixes every line with the name of the master.
+    #[serde(default)]
+    pub prefix_name: bool,
+    /// Prefixes every line with the time it was read.
+    #[serde(default)]
+    pub prefix_timestamp: bool,
+}
+
+fn default_line_ending() -> LineEnding {
+    LineEnding::Lf
+}
+
+/// See [`CoreSubscriptionMode`](crate::app::serial_state::model::CoreSubscriptionMode).
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum SubscriptionMode {
+    /// Every read chunk as it is.
+    #[default]
+    Raw,
+    /// Every decoded line.
+    Lines(LineForwarding),
+}
+
+/// See [`CoreRateLimitOverflow`](crate::app::serial_state::model::CoreRateLimitOverflow).
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RateLimitOverflow {
+    #[default]
+    Drop,
+    Buffer,
+}
+
+/// See [`CoreSubscriptionRateLimit`](crate::app::serial_state::model::CoreSubscriptionRateLimit).
+#[derive(Debug, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionRateLimit {
+    /// `0` means unlimited.
+    #[serde(default)]
+    pub bytes_per_second: u64,
+    /// `0` means unlimited.
+    #[serde(default)]
+    pub frames_per_second: u64,
+    #[serde(default)]
+    pub overflow: RateLimitOverflow,
+    #[serde(default)]
+    pub latency: Duration,
+    /// Maximum number of frames waiting to be forwarded. Further frames are dropped.
+    #[serde(default = "default_rate_limit_capacity")]
+    pub capacity: usize,
+}
+
+fn default_rate_limit_capacity() -> usize {
+    1024
+}
+
+impl Default for SubscriptionRateLimit {
+    fn default() -> Self {
+        Self {
+            bytes_per_second: 0,
+            frames_per_second: 0,
+            overflow: Default::default(),
+            latency: Default::default(),
+            capacity: default_rate_limit_capacity(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Subscription {
+    /// Name of the subscriber serial port.
+    pub name: String,
+    /// Every read chunk or line is forwarded if not set.
+    pub filter: Option<SubscriptionFilter>,
+    pub mode: SubscriptionMode,
+    pub rate_limit: SubscriptionRateLimit,
+    /// See [`CoreSubscription::echo_guard`](crate::app::serial_state::model::CoreSubscription::echo_guard).
+    pub echo_guard: bool,
+    pub metrics: SubscriptionMetrics,
+}
+
+/// See [`CoreSubscriptionMetrics`](crate::app::serial_state::model::CoreSubscriptionMetrics).
+#[derive(Debug, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionMetrics {
+    pub bytes_forwarded: u64,
+    pub frames_forwarded: u64,
+    pub send_failures: u64,
+    /// Frames not forwarded due to [`RateLimitOverflow::Drop`] or [`SubscriptionRateLimit::capacity`].
+    pub frames_dropped: u64,
+    /// Frames waiting for the rate limit or the write queue of the subscriber.
+    pub frames_queued: u64,
+    /// Not set if nothing was forwarded yet.
+    pub last_forward_timestamp_micros: Option<u64>,
+}
+
+mod core_impl {
+    use super::*;
+    #[cfg(feature = "subscriptions")]
+    use crate::app::serial_state::model::{
+        CoreRateLimitOverflow, CoreSubscription, CoreSubscriptionFilter, CoreSubscriptionMetrics,
+        CoreSubscriptionRateLimit,
+    };
+    use crate::app::{
+        model::subscription::{
+            AppRateLimitOverflow, AppSubscriptionFilter, AppSubscriptionRateLimit,
+        },
+        serial_state::model::{CoreLineForwarding, CoreSubscriptionMode},
+    };
+
+    impl From<SubscriptionFilter> for AppSubscriptionFilter {
+        fn from(value: SubscriptionFilter) -> Self {
+            match value {
+                SubscriptionFilter::Regex(pattern) => Self::Regex(pattern),
+                SubscriptionFilter::Prefix(prefix) => Self::Prefix(prefix.into()),
+            }
+        }
+    }
+
+    impl From<SubscriptionMode> for CoreSubscriptionMode {
+        fn from(value: SubscriptionMode) -> Self {
+            match value {
+                SubscriptionMode::Raw => Self::Raw,
+                SubscriptionMode::Lines(line_forwarding) => Self::Lines(CoreLineForwarding {
+                    line_ending: line_forwarding.line_ending.into(),
+                    prefix_name: line_forwarding.prefix_name,
+                    prefix_timestamp: line_forwarding.prefix_timestamp,
+                }),
+            }
+        }
+    }
+
+    impl From<CoreSubscriptionMode> for SubscriptionMode {
+        fn from(value: CoreSubscriptionMode) -> Self {
+            match value {
+                CoreSubscriptionMode::Raw => Self::Raw,
+                CoreSubscriptionMode::Lines(line_forwarding) => Self::Lines(LineForwarding {
+                    line_ending: line_forwarding.line_ending.into(),
+                    prefix_name: line_forwarding.prefix_name,
+                    prefix_timestamp: line_forwarding.prefix_timestamp,
+                }),
+            }
+        }
+    }
+
+    impl From<SubscriptionRateLimit> for AppSubscriptionRateLimit {
+        fn from(value: SubscriptionRateLimit) -> Self {
+            Self {
+                bytes_per_second: value.bytes_per_second,
+                frames_per_second: value.frames_per_second,
+                overflow: match value.overflow {
+                    RateLimitOverflow::Drop => AppRateLimitOverflow::Drop,
+                    RateLimitOverflow::Buffer => AppRateLimitOverflow::Buffer,
+                },
+                latency: value.latency.into(),
+                capacity: value.capacity,
+            }
+        }
+    }
+
+    #[cfg(feature = "subscriptions")]
+    impl From<CoreSubscriptionRateLimit> for SubscriptionRateLimit {
+        fn from(value: CoreSubscriptionRateLimit) -> Self {
+            Self {
+                bytes_per_second: value.bytes_per_second,
+                frames_per_second: value.frames_per_second,
+                overflow: match value.overflow {
+                    CoreRateLimitOverflow::Drop => RateLimitOverflow::Drop,
+                    CoreRateLimitOverflow::Buffer => RateLimitOverflow::Buffer,
+                },
+                latency: value.latency.into(),
+                capacity: value.capacity,
+            }
+        }
+    }
+
+    #[cfg(feature = "subscriptions")]
+    impl From<CoreSubscriptionFilter> for SubscriptionFilter {
+        fn from(value: CoreSubscriptionFilter) -> Self {
+            match value {
+                CoreSubscriptionFilter::Regex(regex) => Self::Regex(regex.as_str().to_string()),
+                CoreSubscriptionFilter::Prefix(prefix) => Self::Prefix(prefix.to_vec()),
+            }
+        }
+    }
+
+    #[cfg(feature = "subscriptions")]
+    impl From<CoreSubscription> for Subscription {
+        fn from(value: CoreSubscription) -> Self {
+            Self {
+                name: value.name,
+                filter: value.filter.map(Into::into),
+                mode: value.mode.into(),
+                rate_limit: value.rate_limit.into(),
+                echo_guard: value.echo_guard,
+                metrics: value.metrics.into(),
+            }
+        }
+    }
+
+    #[cfg(feature = "subscriptions")]
+    impl From<CoreSubscriptionMetrics> for SubscriptionMetrics {
+        fn from(value: CoreSubscriptionMetrics) -> Self {
+            Self {
+                bytes_forwarded: value.bytes_forwarded,
+                frames_forwarded: value.frames_forwarded,
+                send_failures: value.send_failures,
+                frames_dropped: value.frames_dropped,
+                frames_queued: value.frames_queued,
+                last_forward_timestamp_micros: value.last_forward_timestamp_micros,
+            }
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use serde::Deserialize;
+
+use super::open_options::Duration;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ChunksPacing {
+    pub chunk_size: usize,
+    /// Delay after each chunk.
+    pub delay: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LinesPacing {
+    /// Delay after each line.
+    pub delay: Duration,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+#[serde(tag = "type", content = "content")]
+pub enum SendFilePacing {
+    Chunks(ChunksPacing),
+    Lines(LinesPacing),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum XmodemProtocol {
+    Xmodem,
+    Ymodem,
+}
+
+mod core_impl {
+    use super::*;
+    use crate::app::serial_state::model::{CoreSendFilePacing, CoreXmodemProtocol};
+
+    impl From<SendFilePacing> for CoreSendFilePacing {
+        fn from(value: SendFilePacing) -> Self {
+            match value {
+                SendFilePacing::Chunks(pacing) => Self::Chunks {
+                    chunk_size: pacing.chunk_size,
+                    delay: pacing.delay.into(),
+                },
+                SendFilePacing::Lines(pacing) => Self::Lines {
+                    delay: pacing.delay.into(),
+                },
+            }
+        }
+    }
+
+    impl From<XmodemProtocol> for CoreXmodemProtocol {
+        fn from(value: XmodemProtocol) -> Self {
+            match value {
+                XmodemProtocol::Xmodem => Self::Xmodem,
+                XmodemProtocol::Ymodem => Self::Ymodem,
+            }
+        }
+    }
+}